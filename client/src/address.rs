@@ -0,0 +1,35 @@
+//! Bech32 encoding and decoding of the addresses used by pools.
+
+use radix_common::prelude::*;
+
+/// Encodes a component, resource or package address as Bech32 string of the network, e.g. `component_rdx1...`.
+pub fn encode_address(network: &NetworkDefinition, address: impl Into<GlobalAddress>) -> String {
+    let address: GlobalAddress = address.into();
+    AddressBech32Encoder::new(network)
+        .encode(address.as_node_id().as_bytes())
+        .expect("Global addresses are always encodable")
+}
+
+/// Decodes a Bech32 component address of the network, returning `None` if it is invalid or of another entity type.
+pub fn decode_component_address(
+    network: &NetworkDefinition,
+    address: &str,
+) -> Option<ComponentAddress> {
+    ComponentAddress::try_from_bech32(&AddressBech32Decoder::new(network), address)
+}
+
+/// Decodes a Bech32 resource address of the network, returning `None` if it is invalid or of another entity type.
+pub fn decode_resource_address(
+    network: &NetworkDefinition,
+    address: &str,
+) -> Option<ResourceAddress> {
+    ResourceAddress::try_from_bech32(&AddressBech32Decoder::new(network), address)
+}
+
+/// Decodes a Bech32 package address of the network, returning `None` if it is invalid or of another entity type.
+pub fn decode_package_address(
+    network: &NetworkDefinition,
+    address: &str,
+) -> Option<PackageAddress> {
+    PackageAddress::try_from_bech32(&AddressBech32Decoder::new(network), address)
+}
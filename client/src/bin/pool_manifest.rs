@@ -0,0 +1,426 @@
+//! Generates manifests for pool operations on any network.
+//!
+//! Example:
+//! ```text
+//! pool-manifest --network stokenet --account account_tdx_2_1... swap \
+//!     --pool component_tdx_2_1... --input resource_tdx_2_1... --amount 10 \
+//!     --min-output resource_tdx_2_1...=4.9
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use ociswap_pool_client::*;
+use radix_common::prelude::*;
+use radix_transactions::prelude::*;
+
+#[derive(Parser)]
+#[command(about = "Generates transaction manifests for Ociswap pools")]
+struct Cli {
+    /// Network the addresses belong to: mainnet, stokenet, localnet or simulator.
+    #[arg(long, default_value = "mainnet")]
+    network: String,
+    /// Account paying the fee, providing the inputs and receiving the outputs.
+    #[arg(long)]
+    account: String,
+    /// Amount of XRD locked for the transaction fee.
+    #[arg(long, default_value = "10")]
+    lock_fee: String,
+    /// Directory the manifest is written to.
+    #[arg(long, default_value = "./transaction-manifest")]
+    output: PathBuf,
+    /// File name of the manifest without extension, defaults to the name of the command.
+    #[arg(long)]
+    name: Option<String>,
+    #[arg(long, value_enum, default_value_t = Format::Rtm)]
+    format: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Human readable `.rtm` manifest.
+    Rtm,
+    /// SBOR encoded `.bin` manifest.
+    Compiled,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Instantiates a pool without liquidity.
+    Instantiate {
+        #[arg(long)]
+        package: String,
+        #[arg(long)]
+        a: String,
+        #[arg(long)]
+        b: String,
+        #[arg(long)]
+        input_fee_rate: String,
+        #[arg(long)]
+        flash_loan_fee_rate: String,
+        #[arg(long, default_value = "0.5")]
+        a_share: String,
+    },
+    /// Instantiates a pool and adds initial liquidity.
+    InstantiateWithLiquidity {
+        #[arg(long)]
+        package: String,
+        #[arg(long)]
+        a: String,
+        #[arg(long)]
+        a_amount: String,
+        #[arg(long)]
+        b: String,
+        #[arg(long)]
+        b_amount: String,
+        #[arg(long)]
+        input_fee_rate: String,
+        #[arg(long)]
+        flash_loan_fee_rate: String,
+        #[arg(long, default_value = "0.5")]
+        a_share: String,
+    },
+    /// Adds liquidity to a pool.
+    AddLiquidity {
+        #[arg(long)]
+        pool: String,
+        #[arg(long)]
+        a: String,
+        #[arg(long)]
+        a_amount: String,
+        #[arg(long)]
+        b: String,
+        #[arg(long)]
+        b_amount: String,
+        /// Minimum amount of LP tokens as `<lp_address>=<amount>`.
+        #[arg(long)]
+        min_output: Vec<String>,
+    },
+    /// Removes liquidity from a pool.
+    RemoveLiquidity {
+        #[arg(long)]
+        pool: String,
+        #[arg(long)]
+        lp: String,
+        #[arg(long)]
+        lp_amount: String,
+        /// Minimum output amounts as `<resource_address>=<amount>`, can be repeated.
+        #[arg(long)]
+        min_output: Vec<String>,
+    },
+    /// Migrates liquidity to an approved successor pool.
+    Migrate {
+        #[arg(long)]
+        pool: String,
+        #[arg(long)]
+        lp: String,
+        #[arg(long)]
+        lp_amount: String,
+        /// The successor pool to contribute the redeemed tokens into.
+        #[arg(long)]
+        target: String,
+        /// Minimum amount of successor LP tokens as `<lp_address>=<amount>`.
+        #[arg(long)]
+        min_output: Vec<String>,
+    },
+    /// Swaps tokens.
+    Swap {
+        #[arg(long)]
+        pool: String,
+        #[arg(long)]
+        input: String,
+        #[arg(long)]
+        amount: String,
+        /// Minimum output amount as `<resource_address>=<amount>`.
+        #[arg(long)]
+        min_output: Vec<String>,
+    },
+    /// Takes a flash loan and repays it in the same transaction.
+    FlashLoan {
+        #[arg(long)]
+        pool: String,
+        #[arg(long)]
+        resource: String,
+        #[arg(long)]
+        amount: String,
+        /// Fee withdrawn from the account to repay the loan.
+        #[arg(long)]
+        fee: String,
+        /// Address of the transient flash loan token, see the pool's `flash_loan_address`.
+        #[arg(long)]
+        flash_loan_address: String,
+    },
+    /// Whitelists the registry, hook and successor packages in the metadata of the pool package.
+    Whitelist {
+        #[arg(long)]
+        package: String,
+        /// Owner badge of the pool package.
+        #[arg(long)]
+        owner_badge: String,
+        #[arg(long)]
+        registry: String,
+        #[arg(long)]
+        hook_package: Vec<String>,
+        /// Packages pools can migrate liquidity to, `successor_packages` is only set if given.
+        #[arg(long)]
+        successor_package: Vec<String>,
+        /// Locks the metadata afterwards, which cannot be undone.
+        #[arg(long)]
+        lock: bool,
+    },
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Instantiate { .. } => "instantiate",
+            Command::InstantiateWithLiquidity { .. } => "instantiate_with_liquidity",
+            Command::AddLiquidity { .. } => "add_liquidity",
+            Command::RemoveLiquidity { .. } => "remove_liquidity",
+            Command::Migrate { .. } => "migrate",
+            Command::Swap { .. } => "swap",
+            Command::FlashLoan { .. } => "flash_loan",
+            Command::Whitelist { .. } => "whitelist",
+        }
+    }
+}
+
+/// Parses addresses and amounts of the command line, addresses are Bech32 encoded for the selected network.
+struct Args {
+    network: NetworkDefinition,
+}
+
+impl Args {
+    fn component(&self, address: &str) -> Result<ComponentAddress, String> {
+        decode_component_address(&self.network, address)
+            .ok_or_else(|| format!("Invalid component address {address}"))
+    }
+
+    fn resource(&self, address: &str) -> Result<ResourceAddress, String> {
+        decode_resource_address(&self.network, address)
+            .ok_or_else(|| format!("Invalid resource address {address}"))
+    }
+
+    fn package(&self, address: &str) -> Result<PackageAddress, String> {
+        decode_package_address(&self.network, address)
+            .ok_or_else(|| format!("Invalid package address {address}"))
+    }
+
+    fn decimal(&self, amount: &str) -> Result<Decimal, String> {
+        amount
+            .parse::<Decimal>()
+            .map_err(|_| format!("Invalid amount {amount}"))
+    }
+
+    fn min_outputs(
+        &self,
+        min_outputs: &[String],
+    ) -> Result<Vec<(ResourceAddress, Decimal)>, String> {
+        min_outputs
+            .iter()
+            .map(|min_output| {
+                let (address, amount) = min_output
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid minimum output {min_output}"))?;
+                Ok((self.resource(address)?, self.decimal(amount)?))
+            })
+            .collect()
+    }
+}
+
+fn network(name: &str) -> Result<NetworkDefinition, String> {
+    match name {
+        "mainnet" => Ok(NetworkDefinition::mainnet()),
+        "stokenet" => Ok(NetworkDefinition::stokenet()),
+        "localnet" => Ok(NetworkDefinition::localnet()),
+        "simulator" => Ok(NetworkDefinition::simulator()),
+        _ => Err(format!("Unknown network {name}")),
+    }
+}
+
+fn manifest(cli: &Cli, args: &Args) -> Result<TransactionManifestV1, String> {
+    let builder = PoolManifestBuilder::new(args.component(&cli.account)?)
+        .lock_fee(args.decimal(&cli.lock_fee)?);
+    let builder = match &cli.command {
+        Command::Instantiate {
+            package,
+            a,
+            b,
+            input_fee_rate,
+            flash_loan_fee_rate,
+            a_share,
+        } => builder.instantiate(
+            args.package(package)?,
+            args.resource(a)?,
+            args.resource(b)?,
+            args.decimal(input_fee_rate)?,
+            args.decimal(flash_loan_fee_rate)?,
+            args.decimal(a_share)?,
+            vec![],
+        ),
+        Command::InstantiateWithLiquidity {
+            package,
+            a,
+            a_amount,
+            b,
+            b_amount,
+            input_fee_rate,
+            flash_loan_fee_rate,
+            a_share,
+        } => builder.instantiate_with_liquidity(
+            args.package(package)?,
+            (args.resource(a)?, args.decimal(a_amount)?),
+            (args.resource(b)?, args.decimal(b_amount)?),
+            args.decimal(input_fee_rate)?,
+            args.decimal(flash_loan_fee_rate)?,
+            args.decimal(a_share)?,
+            vec![],
+        ),
+        Command::AddLiquidity {
+            pool,
+            a,
+            a_amount,
+            b,
+            b_amount,
+            min_output,
+        } => assert_min_outputs(
+            builder.add_liquidity(
+                args.component(pool)?,
+                (args.resource(a)?, args.decimal(a_amount)?),
+                (args.resource(b)?, args.decimal(b_amount)?),
+            ),
+            args.min_outputs(min_output)?,
+        ),
+        Command::RemoveLiquidity {
+            pool,
+            lp,
+            lp_amount,
+            min_output,
+        } => assert_min_outputs(
+            builder.remove_liquidity(
+                args.component(pool)?,
+                args.resource(lp)?,
+                args.decimal(lp_amount)?,
+            ),
+            args.min_outputs(min_output)?,
+        ),
+        Command::Migrate {
+            pool,
+            lp,
+            lp_amount,
+            target,
+            min_output,
+        } => assert_min_outputs(
+            builder.migrate(
+                args.component(pool)?,
+                args.resource(lp)?,
+                args.decimal(lp_amount)?,
+                args.component(target)?,
+            ),
+            args.min_outputs(min_output)?,
+        ),
+        Command::Swap {
+            pool,
+            input,
+            amount,
+            min_output,
+        } => assert_min_outputs(
+            builder.swap(
+                args.component(pool)?,
+                args.resource(input)?,
+                args.decimal(amount)?,
+            ),
+            args.min_outputs(min_output)?,
+        ),
+        Command::FlashLoan {
+            pool,
+            resource,
+            amount,
+            fee,
+            flash_loan_address,
+        } => builder.flash_loan_and_repay(
+            args.component(pool)?,
+            args.resource(resource)?,
+            args.decimal(amount)?,
+            args.decimal(fee)?,
+            args.resource(flash_loan_address)?,
+        ),
+        Command::Whitelist {
+            package,
+            owner_badge,
+            registry,
+            hook_package,
+            successor_package,
+            lock,
+        } => {
+            let package_address = args.package(package)?;
+            let hook_packages = hook_package
+                .iter()
+                .map(|hook_package| args.package(hook_package))
+                .collect::<Result<Vec<_>, _>>()?;
+            let successor_packages = successor_package
+                .iter()
+                .map(|successor_package| args.package(successor_package))
+                .collect::<Result<Vec<_>, _>>()?;
+            let set_successor_packages = !successor_packages.is_empty();
+            let mut builder = builder
+                .create_owner_proof(args.resource(owner_badge)?)
+                .set_registry(package_address, args.component(registry)?)
+                .set_hook_packages(package_address, hook_packages);
+            if set_successor_packages {
+                builder = builder.set_successor_packages(package_address, successor_packages);
+            }
+            if *lock {
+                builder = builder
+                    .lock_package_metadata(package_address, "registry")
+                    .lock_package_metadata(package_address, "hook_packages");
+                if set_successor_packages {
+                    builder = builder.lock_package_metadata(package_address, "successor_packages");
+                }
+            }
+            builder
+        }
+    };
+    Ok(builder.deposit_all().build())
+}
+
+fn assert_min_outputs(
+    builder: PoolManifestBuilder,
+    min_outputs: Vec<(ResourceAddress, Decimal)>,
+) -> PoolManifestBuilder {
+    min_outputs
+        .into_iter()
+        .fold(builder, |builder, (resource_address, min_amount)| {
+            builder.assert_min_amount(resource_address, min_amount)
+        })
+}
+
+fn run(cli: Cli) -> Result<PathBuf, String> {
+    let args = Args {
+        network: network(&cli.network)?,
+    };
+    let manifest = manifest(&cli, &args)?;
+    let format = match cli.format {
+        Format::Rtm => ManifestFormat::Text,
+        Format::Compiled => ManifestFormat::Compiled,
+    };
+    let name = cli.name.as_deref().unwrap_or(cli.command.name());
+    dump_manifest(&manifest, &cli.output, name, &args.network, format)
+        .map_err(|error| error.to_string())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(path) => {
+            println!("{}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
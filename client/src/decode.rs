@@ -0,0 +1,55 @@
+//! Decoders for the SBOR encoded outputs of `Pool` methods and the payloads of `Pool` events.
+
+use crate::types::*;
+use radix_common::prelude::*;
+
+/// Decodes the SBOR encoded output of a method call or the payload of an event.
+pub fn decode<T: ScryptoDecode>(bytes: &[u8]) -> Result<T, DecodeError> {
+    scrypto_decode(bytes)
+}
+
+pub fn decode_swap_event(payload: &[u8]) -> Result<SwapEvent, DecodeError> {
+    decode(payload)
+}
+
+pub fn decode_instantiate_event(payload: &[u8]) -> Result<InstantiateEvent, DecodeError> {
+    decode(payload)
+}
+
+pub fn decode_flash_loan_event(payload: &[u8]) -> Result<FlashLoanEvent, DecodeError> {
+    decode(payload)
+}
+
+pub fn decode_migration_event(payload: &[u8]) -> Result<MigrationEvent, DecodeError> {
+    decode(payload)
+}
+
+/// Decodes the output of `total_liquidity` and `removable_liquidity`.
+pub fn decode_amounts(output: &[u8]) -> Result<IndexMap<ResourceAddress, Decimal>, DecodeError> {
+    decode(output)
+}
+
+/// Decodes the output of `price_sqrt`.
+pub fn decode_price_sqrt(output: &[u8]) -> Result<Option<PreciseDecimal>, DecodeError> {
+    decode(output)
+}
+
+/// Decodes the output of `current_config`.
+pub fn decode_registry_config(output: &[u8]) -> Result<RegistryConfig, DecodeError> {
+    decode(output)
+}
+
+/// Decodes the output of `pending_fee_rates`.
+pub fn decode_pending_fee_rates(output: &[u8]) -> Result<Option<FeeRatesProposal>, DecodeError> {
+    decode(output)
+}
+
+/// Decodes the output of `protocol_fee_mode`.
+pub fn decode_protocol_fee_mode(output: &[u8]) -> Result<ProtocolFeeMode, DecodeError> {
+    decode(output)
+}
+
+/// Decodes the output of `state`.
+pub fn decode_pool_state(output: &[u8]) -> Result<PoolState, DecodeError> {
+    decode(output)
+}
@@ -0,0 +1,93 @@
+//! Writes manifests to the file system, either as `.rtm` text or SBOR encoded.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use radix_common::prelude::*;
+use radix_transactions::manifest::{
+    compile, decompile, BlobProvider, CompileError, DecompileError,
+};
+use radix_transactions::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManifestFormat {
+    /// Human readable manifest `<name>.rtm`, plus one `<hash>.blob` file per blob.
+    Text,
+    /// SBOR encoded manifest `<name>.bin`, including its blobs.
+    Compiled,
+}
+
+#[derive(Debug)]
+pub enum DumpManifestError {
+    NotADirectory(PathBuf),
+    Io(std::io::Error),
+    Decompile(DecompileError),
+    Compile(CompileError),
+    Encode(EncodeError),
+}
+
+impl fmt::Display for DumpManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpManifestError::NotADirectory(path) => {
+                write!(f, "{} is not a directory", path.display())
+            }
+            DumpManifestError::Io(error) => write!(f, "{error}"),
+            DumpManifestError::Decompile(error) => {
+                write!(f, "Failed to decompile manifest: {error:?}")
+            }
+            DumpManifestError::Compile(error) => write!(f, "Failed to compile manifest: {error:?}"),
+            DumpManifestError::Encode(error) => write!(f, "Failed to encode manifest: {error:?}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for DumpManifestError {
+    fn from(error: std::io::Error) -> Self {
+        DumpManifestError::Io(error)
+    }
+}
+
+/// Writes a manifest to `directory`, creating the directory if necessary, and returns the path of the manifest.
+///
+/// Text manifests are compiled again after decompiling them, to make sure they are valid for `network`.
+pub fn dump_manifest(
+    manifest: &TransactionManifestV1,
+    directory: impl AsRef<Path>,
+    name: &str,
+    network: &NetworkDefinition,
+    format: ManifestFormat,
+) -> Result<PathBuf, DumpManifestError> {
+    let directory = directory.as_ref();
+    if directory.exists() && !directory.is_dir() {
+        return Err(DumpManifestError::NotADirectory(directory.to_path_buf()));
+    }
+    std::fs::create_dir_all(directory)?;
+
+    match format {
+        ManifestFormat::Text => {
+            let manifest_string =
+                decompile(manifest, network).map_err(DumpManifestError::Decompile)?;
+            let blobs: Vec<Vec<u8>> = manifest.blobs.values().cloned().collect();
+            compile(
+                &manifest_string,
+                network,
+                BlobProvider::new_with_blobs(blobs),
+            )
+            .map_err(DumpManifestError::Compile)?;
+
+            for (hash, blob) in &manifest.blobs {
+                std::fs::write(directory.join(format!("{hash}.blob")), blob)?;
+            }
+            let path = directory.join(format!("{name}.rtm"));
+            std::fs::write(&path, manifest_string)?;
+            Ok(path)
+        }
+        ManifestFormat::Compiled => {
+            let encoded = manifest_encode(manifest).map_err(DumpManifestError::Encode)?;
+            let path = directory.join(format!("{name}.bin"));
+            std::fs::write(&path, encoded)?;
+            Ok(path)
+        }
+    }
+}
@@ -0,0 +1,11 @@
+pub mod address;
+pub mod decode;
+pub mod dump;
+pub mod manifest;
+pub mod types;
+
+pub use address::*;
+pub use decode::*;
+pub use dump::*;
+pub use manifest::*;
+pub use types::*;
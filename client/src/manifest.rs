@@ -0,0 +1,628 @@
+//! Typed manifest builders for the methods of the `Pool` blueprint.
+//!
+//! Input tokens are withdrawn from the account of the builder, while the returned tokens remain on the worktop
+//! until `deposit_all` deposits them into the account. Guardian and blueprint restricted methods are not included,
+//! since they can only be called by components. Whitelisting registries and hook packages via the metadata of the
+//! pool package requires a proof of the package owner badge, see `create_owner_proof`.
+
+use std::mem;
+
+use radix_common::prelude::*;
+use radix_transactions::prelude::*;
+
+pub struct PoolManifestBuilder {
+    builder: ManifestBuilder,
+    account: ComponentAddress,
+    names: usize,
+}
+
+impl PoolManifestBuilder {
+    /// Creates a builder for a new manifest withdrawing from and depositing to `account`.
+    pub fn new(account: ComponentAddress) -> Self {
+        Self::from_builder(ManifestBuilder::new(), account)
+    }
+
+    /// Continues an existing manifest, e.g. to combine pool calls with other instructions.
+    pub fn from_builder(builder: ManifestBuilder, account: ComponentAddress) -> Self {
+        Self {
+            builder,
+            account,
+            names: 0,
+        }
+    }
+
+    /// Returns the underlying `ManifestBuilder`.
+    pub fn into_builder(self) -> ManifestBuilder {
+        self.builder
+    }
+
+    pub fn build(self) -> TransactionManifestV1 {
+        self.builder.build()
+    }
+
+    /// Locks the transaction fee from the account.
+    pub fn lock_fee(mut self, amount: Decimal) -> Self {
+        self.builder = self.builder.lock_fee(self.account, amount);
+        self
+    }
+
+    /// Withdraws tokens from the account into a named bucket, returning the name of the bucket.
+    pub fn withdraw(&mut self, resource_address: ResourceAddress, amount: Decimal) -> String {
+        let name = self.new_name();
+        self.builder = mem::take(&mut self.builder)
+            .withdraw_from_account(self.account, resource_address, amount)
+            .take_from_worktop(resource_address, amount, name.clone());
+        name
+    }
+
+    /// Deposits all tokens on the worktop into the account, aborting if the account rejects any of them.
+    pub fn deposit_all(mut self) -> Self {
+        self.builder = self
+            .builder
+            .try_deposit_entire_worktop_or_abort(self.account, None);
+        self
+    }
+
+    pub fn instantiate(
+        mut self,
+        package_address: PackageAddress,
+        a_address: ResourceAddress,
+        b_address: ResourceAddress,
+        input_fee_rate: Decimal,
+        flash_loan_fee_rate: Decimal,
+        a_share: Decimal,
+        hook_badges: Vec<(ComponentAddress, ResourceAddress)>,
+    ) -> Self {
+        let hook_badges = self.withdraw_hook_badges(hook_badges);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            let hook_badges: Vec<(ComponentAddress, ManifestBucket)> = hook_badges
+                .iter()
+                .map(|(hook_address, name)| (*hook_address, lookup.bucket(name)))
+                .collect();
+            builder.call_function(
+                package_address,
+                "Pool",
+                "instantiate",
+                manifest_args!(
+                    a_address,
+                    b_address,
+                    input_fee_rate,
+                    flash_loan_fee_rate,
+                    a_share,
+                    hook_badges
+                ),
+            )
+        });
+        self
+    }
+
+    pub fn instantiate_with_liquidity(
+        mut self,
+        package_address: PackageAddress,
+        (a_address, a_amount): (ResourceAddress, Decimal),
+        (b_address, b_amount): (ResourceAddress, Decimal),
+        input_fee_rate: Decimal,
+        flash_loan_fee_rate: Decimal,
+        a_share: Decimal,
+        hook_badges: Vec<(ComponentAddress, ResourceAddress)>,
+    ) -> Self {
+        let a_bucket = self.withdraw(a_address, a_amount);
+        let b_bucket = self.withdraw(b_address, b_amount);
+        let hook_badges = self.withdraw_hook_badges(hook_badges);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            let hook_badges: Vec<(ComponentAddress, ManifestBucket)> = hook_badges
+                .iter()
+                .map(|(hook_address, name)| (*hook_address, lookup.bucket(name)))
+                .collect();
+            builder.call_function(
+                package_address,
+                "Pool",
+                "instantiate_with_liquidity",
+                manifest_args!(
+                    lookup.bucket(a_bucket),
+                    lookup.bucket(b_bucket),
+                    input_fee_rate,
+                    flash_loan_fee_rate,
+                    a_share,
+                    hook_badges
+                ),
+            )
+        });
+        self
+    }
+
+    pub fn swap(
+        mut self,
+        pool_address: ComponentAddress,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+    ) -> Self {
+        let input_bucket = self.withdraw(input_address, input_amount);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "swap",
+                manifest_args!(lookup.bucket(input_bucket)),
+            )
+        });
+        self
+    }
+
+    pub fn swap_with_credential(
+        mut self,
+        pool_address: ComponentAddress,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        credential: NonFungibleGlobalId,
+    ) -> Self {
+        let input_bucket = self.withdraw(input_address, input_amount);
+        let credential = self.credential_proof(credential);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "swap_with_credential",
+                manifest_args!(lookup.bucket(input_bucket), lookup.proof(credential)),
+            )
+        });
+        self
+    }
+
+    pub fn swap_with_referrer(
+        mut self,
+        pool_address: ComponentAddress,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        referrer: ComponentAddress,
+    ) -> Self {
+        let input_bucket = self.withdraw(input_address, input_amount);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "swap_with_referrer",
+                manifest_args!(lookup.bucket(input_bucket), referrer),
+            )
+        });
+        self
+    }
+
+    pub fn add_liquidity(
+        mut self,
+        pool_address: ComponentAddress,
+        (a_address, a_amount): (ResourceAddress, Decimal),
+        (b_address, b_amount): (ResourceAddress, Decimal),
+    ) -> Self {
+        let a_bucket = self.withdraw(a_address, a_amount);
+        let b_bucket = self.withdraw(b_address, b_amount);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "add_liquidity",
+                manifest_args!(lookup.bucket(a_bucket), lookup.bucket(b_bucket)),
+            )
+        });
+        self
+    }
+
+    pub fn add_liquidity_with_credential(
+        mut self,
+        pool_address: ComponentAddress,
+        (a_address, a_amount): (ResourceAddress, Decimal),
+        (b_address, b_amount): (ResourceAddress, Decimal),
+        credential: NonFungibleGlobalId,
+    ) -> Self {
+        let a_bucket = self.withdraw(a_address, a_amount);
+        let b_bucket = self.withdraw(b_address, b_amount);
+        let credential = self.credential_proof(credential);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "add_liquidity_with_credential",
+                manifest_args!(
+                    lookup.bucket(a_bucket),
+                    lookup.bucket(b_bucket),
+                    lookup.proof(credential)
+                ),
+            )
+        });
+        self
+    }
+
+    pub fn remove_liquidity(
+        mut self,
+        pool_address: ComponentAddress,
+        lp_address: ResourceAddress,
+        lp_amount: Decimal,
+    ) -> Self {
+        let lp_bucket = self.withdraw(lp_address, lp_amount);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "remove_liquidity",
+                manifest_args!(lookup.bucket(lp_bucket)),
+            )
+        });
+        self
+    }
+
+    /// Redeems LP tokens and contributes the redeemed tokens into an approved successor pool.
+    pub fn migrate(
+        mut self,
+        pool_address: ComponentAddress,
+        lp_address: ResourceAddress,
+        lp_amount: Decimal,
+        target_pool_address: ComponentAddress,
+    ) -> Self {
+        let lp_bucket = self.withdraw(lp_address, lp_amount);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "migrate",
+                manifest_args!(lookup.bucket(lp_bucket), target_pool_address),
+            )
+        });
+        self
+    }
+
+    /// Migrates liquidity like `migrate`, forwarding a credential to the remove liquidity hooks of the pool.
+    pub fn migrate_with_credential(
+        mut self,
+        pool_address: ComponentAddress,
+        lp_address: ResourceAddress,
+        lp_amount: Decimal,
+        target_pool_address: ComponentAddress,
+        credential: NonFungibleGlobalId,
+    ) -> Self {
+        let lp_bucket = self.withdraw(lp_address, lp_amount);
+        let credential = self.credential_proof(credential);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "migrate_with_credential",
+                manifest_args!(
+                    lookup.bucket(lp_bucket),
+                    target_pool_address,
+                    lookup.proof(credential)
+                ),
+            )
+        });
+        self
+    }
+
+    pub fn remove_liquidity_with_credential(
+        mut self,
+        pool_address: ComponentAddress,
+        lp_address: ResourceAddress,
+        lp_amount: Decimal,
+        credential: NonFungibleGlobalId,
+    ) -> Self {
+        let lp_bucket = self.withdraw(lp_address, lp_amount);
+        let credential = self.credential_proof(credential);
+        self.builder = self.builder.with_name_lookup(|builder, lookup| {
+            builder.call_method(
+                pool_address,
+                "remove_liquidity_with_credential",
+                manifest_args!(lookup.bucket(lp_bucket), lookup.proof(credential)),
+            )
+        });
+        self
+    }
+
+    /// Takes a flash loan, leaving the loaned tokens and the transient loan token on the worktop.
+    pub fn flash_loan(
+        mut self,
+        pool_address: ComponentAddress,
+        address: ResourceAddress,
+        loan_amount: Decimal,
+    ) -> Self {
+        self.builder = self.builder.call_method(
+            pool_address,
+            "flash_loan",
+            manifest_args!(address, loan_amount),
+        );
+        self
+    }
+
+    /// Repays a flash loan with tokens and the transient loan token taken from the worktop.
+    ///
+    /// The repayment has to cover the loan amount plus fee (see `FlashLoanEvent::due_amount`),
+    /// any excess is returned to the worktop.
+    pub fn repay_loan(
+        mut self,
+        pool_address: ComponentAddress,
+        address: ResourceAddress,
+        repayment_amount: Decimal,
+        flash_loan_address: ResourceAddress,
+    ) -> Self {
+        let repayment = self.new_name();
+        let loan_terms = self.new_name();
+        self.builder = self
+            .builder
+            .take_from_worktop(address, repayment_amount, repayment.clone())
+            .take_all_from_worktop(flash_loan_address, loan_terms.clone())
+            .with_name_lookup(|builder, lookup| {
+                builder.call_method(
+                    pool_address,
+                    "repay_loan",
+                    manifest_args!(lookup.bucket(repayment), lookup.bucket(loan_terms)),
+                )
+            });
+        self
+    }
+
+    /// Takes a flash loan and immediately repays it, withdrawing the fee from the account.
+    ///
+    /// Useful to verify the flash loan setup of a pool, since no instructions can use the loan in between.
+    pub fn flash_loan_and_repay(
+        mut self,
+        pool_address: ComponentAddress,
+        address: ResourceAddress,
+        loan_amount: Decimal,
+        fee_amount: Decimal,
+        flash_loan_address: ResourceAddress,
+    ) -> Self {
+        self = self.flash_loan(pool_address, address, loan_amount);
+        self.builder = self
+            .builder
+            .withdraw_from_account(self.account, address, fee_amount);
+        self.repay_loan(
+            pool_address,
+            address,
+            loan_amount + fee_amount,
+            flash_loan_address,
+        )
+    }
+
+    /// Aborts the transaction if the worktop contains less than `min_amount` of a resource,
+    /// e.g. to limit the slippage of a swap or liquidity removal.
+    pub fn assert_min_amount(
+        mut self,
+        resource_address: ResourceAddress,
+        min_amount: Decimal,
+    ) -> Self {
+        self.builder = self
+            .builder
+            .assert_worktop_contains(resource_address, min_amount);
+        self
+    }
+
+    pub fn removable_liquidity(
+        mut self,
+        pool_address: ComponentAddress,
+        lp_amount: Decimal,
+    ) -> Self {
+        self.builder = self.builder.call_method(
+            pool_address,
+            "removable_liquidity",
+            manifest_args!(lp_amount),
+        );
+        self
+    }
+
+    pub fn quote(
+        mut self,
+        pool_address: ComponentAddress,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+    ) -> Self {
+        self.builder = self.builder.call_method(
+            pool_address,
+            "quote",
+            manifest_args!(input_address, input_amount),
+        );
+        self
+    }
+
+    pub fn hook(
+        mut self,
+        pool_address: ComponentAddress,
+        package_address: PackageAddress,
+        blueprint_name: String,
+    ) -> Self {
+        self.builder = self.builder.call_method(
+            pool_address,
+            "hook",
+            manifest_args!(package_address, blueprint_name),
+        );
+        self
+    }
+
+    pub fn observation(mut self, pool_address: ComponentAddress, timestamp: u64) -> Self {
+        self.builder =
+            self.builder
+                .call_method(pool_address, "observation", manifest_args!(timestamp));
+        self
+    }
+
+    pub fn observation_intervals(
+        mut self,
+        pool_address: ComponentAddress,
+        intervals: Vec<(u64, u64)>,
+    ) -> Self {
+        self.builder = self.builder.call_method(
+            pool_address,
+            "observation_intervals",
+            manifest_args!(intervals),
+        );
+        self
+    }
+
+    /// Calls a pool method without arguments, e.g. a getter like `price_sqrt` or `total_liquidity`,
+    /// or one of the permissionless actions `sync_registry`, `retry_registry_sync`, `fail_over_stale_registry` and
+    /// `apply_fee_rates`.
+    pub fn call(mut self, pool_address: ComponentAddress, method: PoolMethod) -> Self {
+        self.builder = self
+            .builder
+            .call_method(pool_address, method.name(), manifest_args!());
+        self
+    }
+
+    /// Creates a proof of the owner badge of the pool package from the account, which is required to set
+    /// its metadata.
+    pub fn create_owner_proof(mut self, owner_badge_address: ResourceAddress) -> Self {
+        self.builder = self.builder.create_proof_from_account_of_amount(
+            self.account,
+            owner_badge_address,
+            Decimal::ONE,
+        );
+        self
+    }
+
+    /// Sets the `registry` metadata of the pool package, which all newly instantiated pools sync with.
+    pub fn set_registry(
+        mut self,
+        package_address: PackageAddress,
+        registry_address: ComponentAddress,
+    ) -> Self {
+        let registry_address: GlobalAddress = registry_address.into();
+        self.builder = self
+            .builder
+            .set_metadata(package_address, "registry", registry_address);
+        self
+    }
+
+    /// Sets the `hook_packages` metadata of the pool package, whitelisting the packages pools can use hooks from.
+    pub fn set_hook_packages(
+        mut self,
+        package_address: PackageAddress,
+        hook_packages: Vec<PackageAddress>,
+    ) -> Self {
+        let hook_packages: Vec<GlobalAddress> =
+            hook_packages.into_iter().map(GlobalAddress::from).collect();
+        self.builder = self
+            .builder
+            .set_metadata(package_address, "hook_packages", hook_packages);
+        self
+    }
+
+    /// Sets the `successor_packages` metadata of the pool package, whitelisting the packages pools can migrate
+    /// liquidity to.
+    pub fn set_successor_packages(
+        mut self,
+        package_address: PackageAddress,
+        successor_packages: Vec<PackageAddress>,
+    ) -> Self {
+        let successor_packages: Vec<GlobalAddress> = successor_packages
+            .into_iter()
+            .map(GlobalAddress::from)
+            .collect();
+        self.builder =
+            self.builder
+                .set_metadata(package_address, "successor_packages", successor_packages);
+        self
+    }
+
+    /// Locks a metadata entry of the pool package, making it immutable.
+    pub fn lock_package_metadata(mut self, package_address: PackageAddress, key: &str) -> Self {
+        self.builder = self.builder.lock_metadata(package_address, key);
+        self
+    }
+
+    fn withdraw_hook_badges(
+        &mut self,
+        hook_badges: Vec<(ComponentAddress, ResourceAddress)>,
+    ) -> Vec<(ComponentAddress, String)> {
+        hook_badges
+            .into_iter()
+            .map(|(hook_address, badge_address)| {
+                (hook_address, self.withdraw(badge_address, Decimal::ONE))
+            })
+            .collect()
+    }
+
+    fn credential_proof(&mut self, credential: NonFungibleGlobalId) -> String {
+        let name = self.new_name();
+        self.builder = mem::take(&mut self.builder)
+            .create_proof_from_account_of_non_fungibles(
+                self.account,
+                credential.resource_address(),
+                vec![credential.local_id().clone()],
+            )
+            .pop_from_auth_zone(name.clone());
+        name
+    }
+
+    fn new_name(&mut self) -> String {
+        self.names += 1;
+        format!("pool_client_{}", self.names)
+    }
+}
+
+/// Pool methods without arguments which can be called from a manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolMethod {
+    PriceSqrt,
+    XAddress,
+    YAddress,
+    XDivisibility,
+    YDivisibility,
+    TotalLiquidity,
+    LpAddress,
+    LpTotalSupply,
+    XShare,
+    YShare,
+    InputFeeRate,
+    FeeProtocolShare,
+    FlashLoanFeeRate,
+    FlashLoanAddress,
+    LiquidityPool,
+    Registry,
+    RegistryFallback,
+    RegistrySyncDeferredUntil,
+    RegistrySyncGracePeriod,
+    NextSyncTime,
+    CurrentConfig,
+    ProtocolFeeMode,
+    Paused,
+    Guardian,
+    PendingFeeRates,
+    ObservationsLimit,
+    ObservationsStored,
+    OldestObservationAt,
+    LastObservationIndex,
+    State,
+    SyncRegistry,
+    RetryRegistrySync,
+    FailOverStaleRegistry,
+    ApplyFeeRates,
+}
+
+impl PoolMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PoolMethod::PriceSqrt => "price_sqrt",
+            PoolMethod::XAddress => "x_address",
+            PoolMethod::YAddress => "y_address",
+            PoolMethod::XDivisibility => "x_divisibility",
+            PoolMethod::YDivisibility => "y_divisibility",
+            PoolMethod::TotalLiquidity => "total_liquidity",
+            PoolMethod::LpAddress => "lp_address",
+            PoolMethod::LpTotalSupply => "lp_total_supply",
+            PoolMethod::XShare => "x_share",
+            PoolMethod::YShare => "y_share",
+            PoolMethod::InputFeeRate => "input_fee_rate",
+            PoolMethod::FeeProtocolShare => "fee_protocol_share",
+            PoolMethod::FlashLoanFeeRate => "flash_loan_fee_rate",
+            PoolMethod::FlashLoanAddress => "flash_loan_address",
+            PoolMethod::LiquidityPool => "liquidity_pool",
+            PoolMethod::Registry => "registry",
+            PoolMethod::RegistryFallback => "registry_fallback",
+            PoolMethod::RegistrySyncDeferredUntil => "registry_sync_deferred_until",
+            PoolMethod::RegistrySyncGracePeriod => "registry_sync_grace_period",
+            PoolMethod::NextSyncTime => "next_sync_time",
+            PoolMethod::CurrentConfig => "current_config",
+            PoolMethod::ProtocolFeeMode => "protocol_fee_mode",
+            PoolMethod::Paused => "paused",
+            PoolMethod::Guardian => "guardian",
+            PoolMethod::PendingFeeRates => "pending_fee_rates",
+            PoolMethod::ObservationsLimit => "observations_limit",
+            PoolMethod::ObservationsStored => "observations_stored",
+            PoolMethod::OldestObservationAt => "oldest_observation_at",
+            PoolMethod::LastObservationIndex => "last_observation_index",
+            PoolMethod::State => "state",
+            PoolMethod::SyncRegistry => "sync_registry",
+            PoolMethod::RetryRegistrySync => "retry_registry_sync",
+            PoolMethod::FailOverStaleRegistry => "fail_over_stale_registry",
+            PoolMethod::ApplyFeeRates => "apply_fee_rates",
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Client side mirrors of the SBOR types returned by the `Pool` blueprint.
+//! Field order and types have to match the blueprint exactly. Events are shared via `ociswap_pool_events`.
+
+use radix_common::prelude::*;
+
+pub use ociswap_pool_events::{
+    FlashLoanEvent, InstantiateEvent, MigrationEvent, ProtocolFeeMode, SwapEvent,
+};
+
+/// Pool configuration returned by the registry on every sync, available via `current_config`.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct RegistryConfig {
+    pub fee_protocol_share: Decimal,
+    pub next_sync_time: u64,
+    pub flash_loan_fee_protocol_share: Decimal,
+    pub paused: Option<bool>,
+    pub flash_loan_max_fraction: Option<Decimal>,
+}
+
+/// Fee rates proposed by the guardian, available via `pending_fee_rates`.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct FeeRatesProposal {
+    pub input_fee_rate: Decimal,
+    pub flash_loan_fee_rate: Decimal,
+    pub effective_at: u64,
+}
+
+/// Lifecycle calls a hook can be registered for, mirroring `HookCall` of `ociswap_pool_hooks`.
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookCall {
+    BeforeInstantiate,
+    AfterInstantiate,
+    BeforeSwap,
+    AfterSwap,
+    BeforeAddLiquidity,
+    AfterAddLiquidity,
+    BeforeRemoveLiquidity,
+    AfterRemoveLiquidity,
+}
+
+/// A hook of a pool with the calls it is registered for, part of `PoolState`.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct PoolHook {
+    pub component: ComponentAddress,
+    pub calls: Vec<HookCall>,
+}
+
+/// Snapshot of the complete state of a pool, available via `state`.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct PoolState {
+    pub pool_address: ComponentAddress,
+    pub x_address: ResourceAddress,
+    pub y_address: ResourceAddress,
+    pub x_divisibility: u8,
+    pub y_divisibility: u8,
+    pub lp_address: ResourceAddress,
+    pub flash_loan_address: ResourceAddress,
+    pub liquidity_pool: ComponentAddress,
+    pub x_share: Decimal,
+    pub ratio: Decimal,
+    pub x_reserve: Decimal,
+    pub y_reserve: Decimal,
+    pub lp_total_supply: Decimal,
+    pub price_sqrt: Option<PreciseDecimal>,
+    pub input_fee_rate: Decimal,
+    pub flash_loan_fee_rate: Decimal,
+    pub pending_fee_rates: Option<FeeRatesProposal>,
+    pub protocol_fee_mode: ProtocolFeeMode,
+    pub x_protocol_fee: Decimal,
+    pub y_protocol_fee: Decimal,
+    pub protocol_fee_checkpoint: Option<(PreciseDecimal, PreciseDecimal)>,
+    pub registry: ComponentAddress,
+    pub registry_fallback: Option<ComponentAddress>,
+    pub registry_config: RegistryConfig,
+    pub registry_sync_deferred_until: Option<u64>,
+    pub registry_sync_grace_period: u64,
+    pub hooks: Vec<PoolHook>,
+    pub guardian: Option<ComponentAddress>,
+    pub paused: bool,
+    pub observations_limit: u16,
+    pub observations_stored: u16,
+    pub oldest_observation_at: Option<u64>,
+    pub last_observation_index: Option<u16>,
+}
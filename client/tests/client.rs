@@ -0,0 +1,249 @@
+use ociswap_pool_client::*;
+use radix_common::prelude::*;
+use radix_transactions::prelude::TransactionManifestV1;
+
+fn pool_address() -> ComponentAddress {
+    FAUCET
+}
+
+fn account() -> ComponentAddress {
+    ComponentAddress::preallocated_account_from_public_key(&Ed25519PublicKey(
+        [1; Ed25519PublicKey::LENGTH],
+    ))
+}
+
+#[test]
+fn test_address_roundtrip() {
+    let network = NetworkDefinition::mainnet();
+    let encoded = encode_address(&network, pool_address());
+    assert!(encoded.starts_with("component_rdx1"));
+    assert_eq!(
+        decode_component_address(&network, &encoded),
+        Some(pool_address())
+    );
+    assert_eq!(decode_resource_address(&network, &encoded), None);
+    assert_eq!(
+        decode_component_address(&NetworkDefinition::stokenet(), &encoded),
+        None
+    );
+
+    let encoded = encode_address(&network, XRD);
+    assert_eq!(decode_resource_address(&network, &encoded), Some(XRD));
+
+    let encoded = encode_address(&network, ACCOUNT_PACKAGE);
+    assert_eq!(
+        decode_package_address(&network, &encoded),
+        Some(ACCOUNT_PACKAGE)
+    );
+}
+
+#[test]
+fn test_decode_swap_event() {
+    let event = SwapEvent {
+        input_address: XRD,
+        input_amount: dec!(9.97),
+        input_gross_amount: dec!(10),
+        input_fee_lp: dec!(0.03),
+        input_fee_protocol: dec!(0),
+        output_address: XRD,
+        output_amount: dec!(5),
+        output_return_amount: dec!(5),
+        price_sqrt: pdec!(1.5),
+    };
+    let payload = scrypto_encode(&event).unwrap();
+    assert_eq!(decode_swap_event(&payload), Ok(event));
+    assert!(decode_flash_loan_event(&payload).is_err());
+}
+
+#[test]
+fn test_decode_flash_loan_event() {
+    let event = FlashLoanEvent {
+        address: XRD,
+        due_amount: dec!(101),
+        fee: dec!(1),
+    };
+    let payload = scrypto_encode(&event).unwrap();
+    assert_eq!(decode_flash_loan_event(&payload), Ok(event));
+}
+
+#[test]
+fn test_decode_migration_event() {
+    let event = MigrationEvent {
+        target_pool_address: pool_address(),
+        lp_amount: dec!(10),
+        x_amount: dec!(100),
+        y_amount: dec!(5),
+        target_lp_address: PACKAGE_OWNER_BADGE,
+        target_lp_amount: dec!(10),
+        remainder_address: None,
+        remainder_amount: dec!(0),
+    };
+    let payload = scrypto_encode(&event).unwrap();
+    assert_eq!(decode_migration_event(&payload), Ok(event));
+}
+
+#[test]
+fn test_decode_outputs() {
+    let config = RegistryConfig {
+        fee_protocol_share: dec!(0.1),
+        next_sync_time: 100,
+        flash_loan_fee_protocol_share: dec!(1),
+        paused: Some(false),
+        flash_loan_max_fraction: None,
+    };
+    assert_eq!(
+        decode_registry_config(&scrypto_encode(&config).unwrap()),
+        Ok(config)
+    );
+    assert_eq!(
+        decode_protocol_fee_mode(&scrypto_encode(&ProtocolFeeMode::LpTokens).unwrap()),
+        Ok(ProtocolFeeMode::LpTokens)
+    );
+    assert_eq!(
+        decode_price_sqrt(&scrypto_encode(&Some(pdec!(2))).unwrap()),
+        Ok(Some(pdec!(2)))
+    );
+    assert_eq!(
+        decode_pending_fee_rates(&scrypto_encode(&Option::<FeeRatesProposal>::None).unwrap()),
+        Ok(None)
+    );
+    let amounts: IndexMap<ResourceAddress, Decimal> = indexmap!(XRD => dec!(1));
+    assert_eq!(
+        decode_amounts(&scrypto_encode(&amounts).unwrap()),
+        Ok(amounts)
+    );
+}
+
+#[test]
+fn test_swap_manifest() {
+    let manifest = PoolManifestBuilder::new(account())
+        .lock_fee(dec!(10))
+        .swap(pool_address(), XRD, dec!(10))
+        .deposit_all()
+        .build();
+    // lock fee, withdraw, take from worktop, swap, deposit
+    assert_eq!(manifest.instructions.len(), 5);
+}
+
+#[test]
+fn test_flash_loan_manifest() {
+    let manifest = PoolManifestBuilder::new(account())
+        .flash_loan(pool_address(), XRD, dec!(100))
+        .repay_loan(pool_address(), XRD, dec!(101), XRD)
+        .deposit_all()
+        .build();
+    // flash loan, take repayment, take loan terms, repay loan, deposit
+    assert_eq!(manifest.instructions.len(), 5);
+}
+
+#[test]
+fn test_credential_manifest() {
+    let credential = NonFungibleGlobalId::new(XRD, NonFungibleLocalId::integer(1));
+    let manifest = PoolManifestBuilder::new(account())
+        .add_liquidity_with_credential(pool_address(), (XRD, dec!(1)), (XRD, dec!(2)), credential)
+        .deposit_all()
+        .build();
+    // 2x (withdraw, take from worktop), create proof, pop from auth zone, add liquidity, deposit
+    assert_eq!(manifest.instructions.len(), 8);
+}
+
+#[test]
+fn test_getter_manifest() {
+    let manifest = PoolManifestBuilder::new(account())
+        .call(pool_address(), PoolMethod::TotalLiquidity)
+        .call(pool_address(), PoolMethod::PriceSqrt)
+        .quote(pool_address(), XRD, dec!(1))
+        .build();
+    assert_eq!(manifest.instructions.len(), 3);
+}
+
+#[test]
+fn test_swap_with_limit_manifest() {
+    let manifest = PoolManifestBuilder::new(account())
+        .swap(pool_address(), XRD, dec!(10))
+        .assert_min_amount(ACCOUNT_OWNER_BADGE, dec!(4.9))
+        .deposit_all()
+        .build();
+    // withdraw, take from worktop, swap, assert worktop contains, deposit
+    assert_eq!(manifest.instructions.len(), 5);
+}
+
+#[test]
+fn test_flash_loan_and_repay_manifest() {
+    let manifest = PoolManifestBuilder::new(account())
+        .flash_loan_and_repay(pool_address(), XRD, dec!(100), dec!(1), PACKAGE_OWNER_BADGE)
+        .deposit_all()
+        .build();
+    // flash loan, withdraw fee, take repayment, take loan terms, repay loan, deposit
+    assert_eq!(manifest.instructions.len(), 6);
+}
+
+#[test]
+fn test_whitelist_manifest() {
+    let manifest = PoolManifestBuilder::new(account())
+        .create_owner_proof(PACKAGE_OWNER_BADGE)
+        .set_registry(ACCOUNT_PACKAGE, pool_address())
+        .set_hook_packages(ACCOUNT_PACKAGE, vec![FAUCET_PACKAGE])
+        .set_successor_packages(ACCOUNT_PACKAGE, vec![ACCOUNT_PACKAGE])
+        .lock_package_metadata(ACCOUNT_PACKAGE, "registry")
+        .build();
+    assert_eq!(manifest.instructions.len(), 5);
+}
+
+#[test]
+fn test_migrate_manifest() {
+    let manifest = PoolManifestBuilder::new(account())
+        .migrate(
+            pool_address(),
+            PACKAGE_OWNER_BADGE,
+            dec!(10),
+            pool_address(),
+        )
+        .deposit_all()
+        .build();
+    // withdraw, take from worktop, migrate, deposit
+    assert_eq!(manifest.instructions.len(), 4);
+}
+
+#[test]
+fn test_dump_manifest() {
+    let directory = std::env::temp_dir().join("ociswap_pool_client_dump_manifest");
+    let network = NetworkDefinition::stokenet();
+    let manifest = PoolManifestBuilder::new(account())
+        .lock_fee(dec!(10))
+        .swap(pool_address(), XRD, dec!(10))
+        .deposit_all()
+        .build();
+
+    let path = dump_manifest(
+        &manifest,
+        &directory,
+        "swap",
+        &network,
+        ManifestFormat::Text,
+    )
+    .unwrap();
+    assert_eq!(path, directory.join("swap.rtm"));
+    let manifest_string = std::fs::read_to_string(&path).unwrap();
+    assert!(manifest_string.contains(&encode_address(&network, pool_address())));
+    assert!(manifest_string.contains("\"swap\""));
+
+    let path = dump_manifest(
+        &manifest,
+        &directory,
+        "swap",
+        &network,
+        ManifestFormat::Compiled,
+    )
+    .unwrap();
+    assert_eq!(path, directory.join("swap.bin"));
+    assert_eq!(
+        manifest_decode::<TransactionManifestV1>(&std::fs::read(&path).unwrap()).unwrap(),
+        manifest
+    );
+
+    assert!(matches!(
+        dump_manifest(&manifest, &path, "swap", &network, ManifestFormat::Text),
+        Err(DumpManifestError::NotADirectory(_))
+    ));
+}
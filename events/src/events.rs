@@ -0,0 +1,163 @@
+//! Events emitted by the `Pool` blueprint, shared between the blueprint and off-ledger consumers.
+
+use radix_common::prelude::*;
+
+/// Determines how a pool collects the protocol's share of the fees.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolFeeMode {
+    /// The protocol's share of the input fee is taken out of every swap and sent to the registry on sync.
+    Skim,
+    /// The full input fee stays in the reserves. On sync, LP tokens worth the protocol's share of the
+    /// invariant growth are minted and sent to the registry.
+    LpTokens,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct InstantiateEvent {
+    pub pool_address: ComponentAddress,
+    pub lp_address: ResourceAddress,
+    pub x_address: ResourceAddress,
+    pub y_address: ResourceAddress,
+    pub x_share: Decimal,
+    pub input_fee_rate: Decimal,
+    pub flash_loan_address: ResourceAddress,
+    pub flash_loan_fee_rate: Decimal,
+    pub registry_address: ComponentAddress,
+    pub registry_fallback_address: Option<ComponentAddress>,
+    pub protocol_fee_mode: ProtocolFeeMode,
+    pub liquidity_pool_address: ComponentAddress,
+    pub hooks: Vec<ComponentAddress>,
+    pub dapp_definition: ComponentAddress,
+    pub guardian: Option<ComponentAddress>,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct SwapEvent {
+    pub input_address: ResourceAddress,
+    pub input_amount: Decimal,
+    pub input_gross_amount: Decimal,
+    pub input_fee_lp: Decimal,
+    pub input_fee_protocol: Decimal,
+    pub output_address: ResourceAddress,
+    pub output_amount: Decimal,
+    pub output_return_amount: Decimal,
+    pub price_sqrt: PreciseDecimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct FlashLoanEvent {
+    pub address: ResourceAddress,
+    pub due_amount: Decimal,
+    pub fee: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct PauseEvent {
+    pub paused: bool,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct FeeRatesProposalEvent {
+    pub input_fee_rate: Decimal,
+    pub flash_loan_fee_rate: Decimal,
+    pub effective_at: u64,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct FeeRatesCancelEvent {
+    pub input_fee_rate: Decimal,
+    pub flash_loan_fee_rate: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct FeeRatesUpdateEvent {
+    pub input_fee_rate: Decimal,
+    pub flash_loan_fee_rate: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct RegistrySyncDeferEvent {
+    pub deferred_until: u64,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct RegistryFailoverEvent {
+    pub registry_address: ComponentAddress,
+}
+
+/// Emitted when LP tokens are redeemed and the tokens are contributed into an approved successor pool.
+#[derive(ScryptoSbor, ScryptoEvent, Clone, Debug, PartialEq)]
+pub struct MigrationEvent {
+    pub target_pool_address: ComponentAddress,
+    pub lp_amount: Decimal,
+    pub x_amount: Decimal,
+    pub y_amount: Decimal,
+    pub target_lp_address: ResourceAddress,
+    pub target_lp_amount: Decimal,
+    /// The token not fully contributed into the target pool, returned to the caller.
+    pub remainder_address: Option<ResourceAddress>,
+    pub remainder_amount: Decimal,
+}
+
+/// `ContributionEvent` of the native `TwoResourcePool` holding the reserves of a pool.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct LiquidityPoolContributionEvent {
+    pub contributed_resources: IndexMap<ResourceAddress, Decimal>,
+    pub pool_units_minted: Decimal,
+}
+
+/// `RedemptionEvent` of the native `TwoResourcePool` holding the reserves of a pool.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct LiquidityPoolRedemptionEvent {
+    pub pool_unit_tokens_redeemed: Decimal,
+    pub redeemed_resources: IndexMap<ResourceAddress, Decimal>,
+}
+
+/// `DepositEvent` of the native `TwoResourcePool`, emitted when a pool deposits swap inputs and fees.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct LiquidityPoolDepositEvent {
+    pub resource_address: ResourceAddress,
+    pub amount: Decimal,
+}
+
+/// `WithdrawEvent` of the native `TwoResourcePool`, emitted when a pool withdraws swap outputs and flash loans.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct LiquidityPoolWithdrawEvent {
+    pub resource_address: ResourceAddress,
+    pub amount: Decimal,
+}
+
+/// Events relevant for indexing pools, emitted either by a pool or by its liquidity pool.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PoolEvent {
+    Instantiate(InstantiateEvent),
+    Swap(SwapEvent),
+    FlashLoan(FlashLoanEvent),
+    Migration(MigrationEvent),
+    Contribution(LiquidityPoolContributionEvent),
+    Redemption(LiquidityPoolRedemptionEvent),
+    Deposit(LiquidityPoolDepositEvent),
+    Withdraw(LiquidityPoolWithdrawEvent),
+}
+
+impl PoolEvent {
+    /// Decodes the SBOR payload of an event by its name, as reported by the Gateway.
+    ///
+    /// # Returns
+    /// * `Ok(None)` for events which are not relevant for indexing.
+    /// * An error if the payload does not match the event.
+    pub fn decode(name: &str, payload: &[u8]) -> Result<Option<Self>, DecodeError> {
+        let event = match name {
+            "InstantiateEvent" => PoolEvent::Instantiate(scrypto_decode(payload)?),
+            "SwapEvent" => PoolEvent::Swap(scrypto_decode(payload)?),
+            "FlashLoanEvent" => PoolEvent::FlashLoan(scrypto_decode(payload)?),
+            "MigrationEvent" => PoolEvent::Migration(scrypto_decode(payload)?),
+            "ContributionEvent" => PoolEvent::Contribution(scrypto_decode(payload)?),
+            "RedemptionEvent" => PoolEvent::Redemption(scrypto_decode(payload)?),
+            "DepositEvent" => PoolEvent::Deposit(scrypto_decode(payload)?),
+            "WithdrawEvent" => PoolEvent::Withdraw(scrypto_decode(payload)?),
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+}
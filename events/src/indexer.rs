@@ -0,0 +1,240 @@
+//! Folds a stream of pool events into per-pool state.
+//!
+//! Pools are registered by their `InstantiateEvent`, which is emitted by the pool package. Afterwards the reserves
+//! are tracked via the events of the pool's native liquidity pool, while volumes, fees and prices are tracked via
+//! the `SwapEvent`, `FlashLoanEvent` and `MigrationEvent` of the pool itself.
+
+use crate::events::*;
+use radix_common::prelude::*;
+
+/// Amounts of both tokens of a pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PairAmounts {
+    pub x: Decimal,
+    pub y: Decimal,
+}
+
+impl PairAmounts {
+    fn add(&mut self, is_x: bool, amount: Decimal) {
+        if is_x {
+            self.x += amount;
+        } else {
+            self.y += amount;
+        }
+    }
+}
+
+/// The price of a pool after a swap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PricePoint {
+    /// Timestamp (or any other monotonic sequence number) passed to the indexer with the event.
+    pub timestamp: u64,
+    pub price_sqrt: PreciseDecimal,
+}
+
+impl PricePoint {
+    /// Returns the price of token X in token Y.
+    pub fn price(&self) -> PreciseDecimal {
+        self.price_sqrt * self.price_sqrt
+    }
+}
+
+/// The indexed state of a pool.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexedPool {
+    pub pool_address: ComponentAddress,
+    pub liquidity_pool_address: ComponentAddress,
+    pub x_address: ResourceAddress,
+    pub y_address: ResourceAddress,
+    pub x_share: Decimal,
+    pub reserves: PairAmounts,
+    /// Cumulative gross input amounts of all swaps.
+    pub volume: PairAmounts,
+    /// Cumulative input fees paid to the liquidity providers.
+    pub fees_lp: PairAmounts,
+    /// Cumulative input fees collected as protocol fees.
+    pub fees_protocol: PairAmounts,
+    /// Cumulative flash loan fees.
+    pub flash_loan_fees: PairAmounts,
+    /// Cumulative amounts redeemed and contributed into successor pools.
+    pub migrated: PairAmounts,
+    pub swap_count: u64,
+    pub prices: Vec<PricePoint>,
+}
+
+impl IndexedPool {
+    fn new(event: &InstantiateEvent) -> Self {
+        Self {
+            pool_address: event.pool_address,
+            liquidity_pool_address: event.liquidity_pool_address,
+            x_address: event.x_address,
+            y_address: event.y_address,
+            x_share: event.x_share,
+            reserves: PairAmounts::default(),
+            volume: PairAmounts::default(),
+            fees_lp: PairAmounts::default(),
+            fees_protocol: PairAmounts::default(),
+            flash_loan_fees: PairAmounts::default(),
+            migrated: PairAmounts::default(),
+            swap_count: 0,
+            prices: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, timestamp: u64, event: PoolEvent) {
+        match event {
+            PoolEvent::Instantiate(_) => {}
+            PoolEvent::Swap(swap) => {
+                let is_x = swap.input_address == self.x_address;
+                self.volume.add(is_x, swap.input_gross_amount);
+                self.fees_lp.add(is_x, swap.input_fee_lp);
+                self.fees_protocol.add(is_x, swap.input_fee_protocol);
+                self.swap_count += 1;
+                self.prices.push(PricePoint {
+                    timestamp,
+                    price_sqrt: swap.price_sqrt,
+                });
+            }
+            PoolEvent::FlashLoan(flash_loan) => {
+                self.flash_loan_fees
+                    .add(flash_loan.address == self.x_address, flash_loan.fee);
+            }
+            PoolEvent::Migration(migration) => {
+                self.migrated.add(true, migration.x_amount);
+                self.migrated.add(false, migration.y_amount);
+            }
+            PoolEvent::Contribution(contribution) => {
+                for (address, amount) in contribution.contributed_resources {
+                    self.reserves.add(address == self.x_address, amount);
+                }
+            }
+            PoolEvent::Redemption(redemption) => {
+                for (address, amount) in redemption.redeemed_resources {
+                    self.reserves.add(address == self.x_address, -amount);
+                }
+            }
+            PoolEvent::Deposit(deposit) => {
+                self.reserves
+                    .add(deposit.resource_address == self.x_address, deposit.amount);
+            }
+            PoolEvent::Withdraw(withdraw) => {
+                self.reserves.add(
+                    withdraw.resource_address == self.x_address,
+                    -withdraw.amount,
+                );
+            }
+        }
+    }
+
+    /// Returns the latest price of token X in token Y, if the pool has been swapped against.
+    pub fn last_price(&self) -> Option<PreciseDecimal> {
+        self.prices.last().map(PricePoint::price)
+    }
+}
+
+/// Folds the events of all pools instantiated by a set of pool packages into per-pool state.
+///
+/// Events have to be applied in ledger order. Events of unknown emitters are ignored, which allows feeding the
+/// indexer the complete event stream of a transaction.
+#[derive(Clone, Debug, Default)]
+pub struct PoolIndexer {
+    pool_packages: IndexSet<PackageAddress>,
+    pools: IndexMap<ComponentAddress, IndexedPool>,
+    liquidity_pools: IndexMap<ComponentAddress, ComponentAddress>,
+}
+
+impl PoolIndexer {
+    /// Creates an indexer for the pools instantiated by the given packages.
+    pub fn new(pool_packages: Vec<PackageAddress>) -> Self {
+        Self {
+            pool_packages: pool_packages.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Applies a decoded event.
+    ///
+    /// # Arguments
+    /// * `emitter`: Package address for `InstantiateEvent`, component address for all other events.
+    /// * `timestamp`: Timestamp (or any other monotonic sequence number) recorded in the price series.
+    /// * `event`: The decoded event.
+    pub fn apply(&mut self, emitter: GlobalAddress, timestamp: u64, event: PoolEvent) {
+        if let PoolEvent::Instantiate(instantiate) = &event {
+            if let Ok(package_address) = PackageAddress::try_from(emitter.as_node_id().0) {
+                if self.pool_packages.contains(&package_address) {
+                    self.liquidity_pools
+                        .insert(instantiate.liquidity_pool_address, instantiate.pool_address);
+                    self.pools
+                        .insert(instantiate.pool_address, IndexedPool::new(instantiate));
+                }
+            }
+            return;
+        }
+        if let Some(pool) = self
+            .pool_address_for(emitter, &event)
+            .and_then(|pool_address| self.pools.get_mut(&pool_address))
+        {
+            pool.apply(timestamp, event);
+        }
+    }
+
+    /// Decodes and applies a raw event, as reported by the Gateway or a transaction receipt.
+    ///
+    /// Events of unknown emitters are ignored without decoding them, since they may share their name with pool
+    /// events while having a different schema.
+    pub fn apply_raw(
+        &mut self,
+        emitter: GlobalAddress,
+        timestamp: u64,
+        name: &str,
+        payload: &[u8],
+    ) -> Result<(), DecodeError> {
+        if !self.is_known_emitter(emitter) {
+            return Ok(());
+        }
+        if let Some(event) = PoolEvent::decode(name, payload)? {
+            self.apply(emitter, timestamp, event);
+        }
+        Ok(())
+    }
+
+    /// Returns the indexed state of a pool.
+    pub fn pool(&self, pool_address: ComponentAddress) -> Option<&IndexedPool> {
+        self.pools.get(&pool_address)
+    }
+
+    /// Returns the indexed state of all pools in the order of their instantiation.
+    pub fn pools(&self) -> impl Iterator<Item = &IndexedPool> {
+        self.pools.values()
+    }
+
+    fn is_known_emitter(&self, emitter: GlobalAddress) -> bool {
+        if let Ok(package_address) = PackageAddress::try_from(emitter.as_node_id().0) {
+            return self.pool_packages.contains(&package_address);
+        }
+        ComponentAddress::try_from(emitter.as_node_id().0).map_or(false, |component_address| {
+            self.pools.contains_key(&component_address)
+                || self.liquidity_pools.contains_key(&component_address)
+        })
+    }
+
+    /// Returns the pool an event belongs to: swaps and flash loans are emitted by the pool itself, reserve
+    /// changes by its liquidity pool.
+    fn pool_address_for(
+        &self,
+        emitter: GlobalAddress,
+        event: &PoolEvent,
+    ) -> Option<ComponentAddress> {
+        let component_address = ComponentAddress::try_from(emitter.as_node_id().0).ok()?;
+        match event {
+            PoolEvent::Instantiate(_) => None,
+            PoolEvent::Swap(_) | PoolEvent::FlashLoan(_) | PoolEvent::Migration(_) => {
+                Some(component_address)
+            }
+            PoolEvent::Contribution(_)
+            | PoolEvent::Redemption(_)
+            | PoolEvent::Deposit(_)
+            | PoolEvent::Withdraw(_) => self.liquidity_pools.get(&component_address).copied(),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod events;
+pub mod indexer;
+
+pub use events::*;
+pub use indexer::*;
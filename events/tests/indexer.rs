@@ -0,0 +1,237 @@
+use ociswap_pool_events::*;
+use radix_common::prelude::*;
+
+const POOL_PACKAGE: PackageAddress = ACCOUNT_PACKAGE;
+const POOL: ComponentAddress = FAUCET;
+const LIQUIDITY_POOL: ComponentAddress = TRANSACTION_TRACKER;
+const X: ResourceAddress = XRD;
+const Y: ResourceAddress = ACCOUNT_OWNER_BADGE;
+
+fn instantiate_event() -> InstantiateEvent {
+    InstantiateEvent {
+        pool_address: POOL,
+        lp_address: PACKAGE_OWNER_BADGE,
+        x_address: X,
+        y_address: Y,
+        x_share: dec!(0.5),
+        input_fee_rate: dec!(0.003),
+        flash_loan_address: PACKAGE_OWNER_BADGE,
+        flash_loan_fee_rate: dec!(0.001),
+        registry_address: FAUCET,
+        registry_fallback_address: None,
+        protocol_fee_mode: ProtocolFeeMode::Skim,
+        liquidity_pool_address: LIQUIDITY_POOL,
+        hooks: vec![],
+        dapp_definition: FAUCET,
+        guardian: None,
+    }
+}
+
+fn swap_event(input_address: ResourceAddress, input_gross_amount: Decimal) -> SwapEvent {
+    SwapEvent {
+        input_address,
+        input_amount: input_gross_amount * dec!(0.997),
+        input_gross_amount,
+        input_fee_lp: input_gross_amount * dec!(0.002),
+        input_fee_protocol: input_gross_amount * dec!(0.001),
+        output_address: if input_address == X { Y } else { X },
+        output_amount: dec!(1),
+        output_return_amount: dec!(1),
+        price_sqrt: pdec!(1.5),
+    }
+}
+
+fn indexer() -> PoolIndexer {
+    let mut indexer = PoolIndexer::new(vec![POOL_PACKAGE]);
+    indexer.apply(
+        POOL_PACKAGE.into(),
+        0,
+        PoolEvent::Instantiate(instantiate_event()),
+    );
+    indexer
+}
+
+#[test]
+fn test_decode() {
+    let event = swap_event(X, dec!(10));
+    let payload = scrypto_encode(&event).unwrap();
+    assert_eq!(
+        PoolEvent::decode("SwapEvent", &payload),
+        Ok(Some(PoolEvent::Swap(event)))
+    );
+    assert_eq!(PoolEvent::decode("PauseEvent", &payload), Ok(None));
+    assert!(PoolEvent::decode("FlashLoanEvent", &payload).is_err());
+}
+
+#[test]
+fn test_instantiate_unknown_package() {
+    let mut indexer = PoolIndexer::new(vec![]);
+    indexer.apply(
+        POOL_PACKAGE.into(),
+        0,
+        PoolEvent::Instantiate(instantiate_event()),
+    );
+    assert_eq!(indexer.pool(POOL), None);
+}
+
+#[test]
+fn test_reserves() {
+    let mut indexer = indexer();
+    let events = vec![
+        PoolEvent::Contribution(LiquidityPoolContributionEvent {
+            contributed_resources: indexmap!(X => dec!(100), Y => dec!(200)),
+            pool_units_minted: dec!(100),
+        }),
+        PoolEvent::Deposit(LiquidityPoolDepositEvent {
+            resource_address: X,
+            amount: dec!(10),
+        }),
+        PoolEvent::Withdraw(LiquidityPoolWithdrawEvent {
+            resource_address: Y,
+            amount: dec!(18),
+        }),
+        PoolEvent::Redemption(LiquidityPoolRedemptionEvent {
+            pool_unit_tokens_redeemed: dec!(10),
+            redeemed_resources: indexmap!(X => dec!(11), Y => dec!(18.2)),
+        }),
+    ];
+    for event in events {
+        indexer.apply(LIQUIDITY_POOL.into(), 1, event);
+    }
+    assert_eq!(
+        indexer.pool(POOL).unwrap().reserves,
+        PairAmounts {
+            x: dec!(99),
+            y: dec!(163.8)
+        }
+    );
+}
+
+#[test]
+fn test_swaps_and_flash_loans() {
+    let mut indexer = indexer();
+    indexer.apply(POOL.into(), 1, PoolEvent::Swap(swap_event(X, dec!(10))));
+    indexer.apply(POOL.into(), 2, PoolEvent::Swap(swap_event(Y, dec!(100))));
+    indexer.apply(
+        POOL.into(),
+        3,
+        PoolEvent::FlashLoan(FlashLoanEvent {
+            address: Y,
+            due_amount: dec!(10.01),
+            fee: dec!(0.01),
+        }),
+    );
+
+    let pool = indexer.pool(POOL).unwrap();
+    assert_eq!(
+        (
+            pool.volume,
+            pool.fees_lp,
+            pool.fees_protocol,
+            pool.flash_loan_fees,
+            pool.swap_count
+        ),
+        (
+            PairAmounts {
+                x: dec!(10),
+                y: dec!(100)
+            },
+            PairAmounts {
+                x: dec!(0.02),
+                y: dec!(0.2)
+            },
+            PairAmounts {
+                x: dec!(0.01),
+                y: dec!(0.1)
+            },
+            PairAmounts {
+                x: dec!(0),
+                y: dec!(0.01)
+            },
+            2
+        )
+    );
+    assert_eq!(
+        pool.prices,
+        vec![
+            PricePoint {
+                timestamp: 1,
+                price_sqrt: pdec!(1.5)
+            },
+            PricePoint {
+                timestamp: 2,
+                price_sqrt: pdec!(1.5)
+            }
+        ]
+    );
+    assert_eq!(pool.last_price(), Some(pdec!(2.25)));
+}
+
+#[test]
+fn test_migrations() {
+    let mut indexer = indexer();
+    let migration = MigrationEvent {
+        target_pool_address: FAUCET,
+        lp_amount: dec!(10),
+        x_amount: dec!(100),
+        y_amount: dec!(5),
+        target_lp_address: PACKAGE_OWNER_BADGE,
+        target_lp_amount: dec!(10),
+        remainder_address: Some(Y),
+        remainder_amount: dec!(0.5),
+    };
+    let payload = scrypto_encode(&migration).unwrap();
+    let event = PoolEvent::decode("MigrationEvent", &payload)
+        .unwrap()
+        .unwrap();
+    indexer.apply(POOL.into(), 1, event.clone());
+    indexer.apply(POOL.into(), 2, event);
+
+    assert_eq!(
+        indexer.pool(POOL).unwrap().migrated,
+        PairAmounts {
+            x: dec!(200),
+            y: dec!(10)
+        }
+    );
+}
+
+#[test]
+fn test_ignores_unknown_emitters() {
+    let mut indexer = indexer();
+    let swap_payload = scrypto_encode(&swap_event(X, dec!(10))).unwrap();
+    // A vault's `DepositEvent` has a different schema, but is not decoded since the emitter is unknown.
+    let vault_deposit_payload = scrypto_encode(&dec!(10)).unwrap();
+    assert_eq!(
+        indexer.apply_raw(FAUCET_PACKAGE.into(), 1, "SwapEvent", &swap_payload),
+        Ok(())
+    );
+    assert_eq!(
+        indexer.apply_raw(CONSENSUS_MANAGER.into(), 1, "SwapEvent", &swap_payload),
+        Ok(())
+    );
+    assert_eq!(
+        indexer.apply_raw(
+            CONSENSUS_MANAGER.into(),
+            1,
+            "DepositEvent",
+            &vault_deposit_payload
+        ),
+        Ok(())
+    );
+    assert_eq!(indexer.pool(POOL).unwrap().swap_count, 0);
+
+    assert_eq!(
+        indexer.apply_raw(POOL.into(), 1, "SwapEvent", &swap_payload),
+        Ok(())
+    );
+    assert_eq!(indexer.pool(POOL).unwrap().swap_count, 1);
+    assert!(indexer
+        .apply_raw(
+            LIQUIDITY_POOL.into(),
+            1,
+            "DepositEvent",
+            &vault_deposit_payload
+        )
+        .is_err());
+}
@@ -0,0 +1,4 @@
+use scrypto::prelude::*;
+
+pub const INPUT_FEE_RATE_MAX: Decimal = dec!(0.1);
+pub const HOOKS_MIN_REMAINING_BUCKET_FRACTION: Decimal = dec!(0.9);
@@ -28,6 +28,7 @@ pub struct BeforeSwapState {
     pub price_sqrt: PreciseDecimal,
     pub input_fee_rate: Decimal,
     pub fee_protocol_share: Decimal,
+    pub credential: Option<NonFungibleGlobalId>,
 }
 
 #[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
@@ -35,6 +36,8 @@ pub struct AfterSwapState {
     pub pool_address: ComponentAddress,
     pub swap_type: SwapType,
     pub price_sqrt: PreciseDecimal,
+    /// The input fee rate charged for the swap. Returning a different rate proposes it as the pool's new base rate,
+    /// subject to the fee rates timelock.
     pub input_fee_rate: Decimal,
     pub fee_protocol_share: Decimal,
     pub input_address: ResourceAddress,
@@ -43,6 +46,38 @@ pub struct AfterSwapState {
     pub output_amount: Decimal,
     pub input_fee_lp: Decimal,
     pub input_fee_protocol: Decimal,
+    pub referrer: Option<ComponentAddress>,
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct BeforeAddLiquidityState {
+    pub pool_address: ComponentAddress,
+    pub x_amount: Decimal,
+    pub y_amount: Decimal,
+    pub lp_total_supply: Decimal,
+    pub credential: Option<NonFungibleGlobalId>,
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct AfterAddLiquidityState {
+    pub pool_address: ComponentAddress,
+    pub lp_amount: Decimal,
+    pub lp_total_supply: Decimal,
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct BeforeRemoveLiquidityState {
+    pub pool_address: ComponentAddress,
+    pub lp_amount: Decimal,
+    pub lp_total_supply: Decimal,
+    pub credential: Option<NonFungibleGlobalId>,
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct AfterRemoveLiquidityState {
+    pub pool_address: ComponentAddress,
+    pub lp_amount: Decimal,
+    pub lp_total_supply: Decimal,
 }
 
 #[derive(ScryptoSbor, Clone, Debug, PartialEq, ManifestSbor)]
@@ -51,6 +86,10 @@ pub enum HookCall {
     AfterInstantiate,
     BeforeSwap,
     AfterSwap,
+    BeforeAddLiquidity,
+    AfterAddLiquidity,
+    BeforeRemoveLiquidity,
+    AfterRemoveLiquidity,
 }
 
 #[derive(ScryptoSbor, Clone, Debug)]
@@ -59,6 +98,10 @@ pub struct HookCalls {
     pub after_instantiate: (String, Vec<Global<AnyComponent>>),
     pub before_swap: (String, Vec<Global<AnyComponent>>),
     pub after_swap: (String, Vec<Global<AnyComponent>>),
+    pub before_add_liquidity: (String, Vec<Global<AnyComponent>>),
+    pub after_add_liquidity: (String, Vec<Global<AnyComponent>>),
+    pub before_remove_liquidity: (String, Vec<Global<AnyComponent>>),
+    pub after_remove_liquidity: (String, Vec<Global<AnyComponent>>),
 }
 
 impl HookCalls {
@@ -68,6 +111,10 @@ impl HookCalls {
             after_instantiate: ("after_instantiate".into(), Vec::new()),
             before_swap: ("before_swap".into(), Vec::new()),
             after_swap: ("after_swap".into(), Vec::new()),
+            before_add_liquidity: ("before_add_liquidity".into(), Vec::new()),
+            after_add_liquidity: ("after_add_liquidity".into(), Vec::new()),
+            before_remove_liquidity: ("before_remove_liquidity".into(), Vec::new()),
+            after_remove_liquidity: ("after_remove_liquidity".into(), Vec::new()),
         }
     }
 }
@@ -127,6 +174,10 @@ fn generate_hooks_badges(
                 HookCall::AfterInstantiate => hook_calls.after_instantiate.1.push(hook),
                 HookCall::BeforeSwap => hook_calls.before_swap.1.push(hook),
                 HookCall::AfterSwap => hook_calls.after_swap.1.push(hook),
+                HookCall::BeforeAddLiquidity => hook_calls.before_add_liquidity.1.push(hook),
+                HookCall::AfterAddLiquidity => hook_calls.after_add_liquidity.1.push(hook),
+                HookCall::BeforeRemoveLiquidity => hook_calls.before_remove_liquidity.1.push(hook),
+                HookCall::AfterRemoveLiquidity => hook_calls.after_remove_liquidity.1.push(hook),
             }
         }
 
@@ -1,3 +1,21 @@
+//! Hook interface of the Ociswap pool blueprint.
+//!
+//! Hooks receive the pool's state before and after each lifecycle event (see [`HookCall`]) and
+//! return it, so every state struct is part of the SBOR interface between pool and hook.
+//!
+//! # Migrating from 0.1
+//!
+//! Version 0.2 changes the SBOR layout of the hook states, so hooks built against 0.1 fail to
+//! decode them and have to be rebuilt against 0.2:
+//! - `BeforeSwapState`, `BeforeAddLiquidityState` and `BeforeRemoveLiquidityState` gained a
+//!   `credential: Option<NonFungibleGlobalId>` field carrying the caller's credential, if any.
+//!   Hooks not doing access control can ignore it.
+//! - `AfterSwapState` gained a `referrer: Option<ComponentAddress>` field carrying the referrer
+//!   passed via `swap_with_referrer`, if any.
+//! - The `BeforeAddLiquidity`, `AfterAddLiquidity`, `BeforeRemoveLiquidity` and
+//!   `AfterRemoveLiquidity` calls and their state structs are new.
+
+pub mod constants;
 pub mod hooks;
 
 pub use hooks::*;
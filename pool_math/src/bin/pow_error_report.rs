@@ -0,0 +1,94 @@
+//! Audits `POW_ERROR_MARGIN` against the high-precision reference model.
+//!
+//! Sweeps the weights from `MINIMUM_SHARE` to `MAXIMUM_SHARE`, swaps in both directions and input amounts from
+//! 10^-12 to 10^3 of the input vault, and reports per weight the worst pool-unfavourable error of the power and the
+//! overcharge of users relative to the exact output amount. Exits with an error if the margin is not safe.
+//!
+//! Example:
+//! ```text
+//! cargo run --release --features reference --bin pow-error-report -- 0.05
+//! ```
+//! The optional argument sets the step between the swept weights (default: 0.05).
+
+use std::process::ExitCode;
+
+use ociswap_pool_math::constants::{MAXIMUM_SHARE, MINIMUM_SHARE, POW_ERROR_MARGIN};
+use ociswap_pool_math::reference::*;
+use radix_common::prelude::*;
+use radix_common_derive::dec;
+
+const VAULTS: [(Decimal, Decimal); 4] = [
+    (dec!(1000000), dec!(1000000)),
+    (dec!(1), dec!(1000000000)),
+    (dec!(1000000000), dec!(1)),
+    (dec!(0.001), dec!(0.002)),
+];
+
+/// Input amounts as fractions of the input vault, `m * 10^e` for `m` in {1, 2, 5} and `e` in [-12, 3].
+fn input_fractions() -> Vec<Decimal> {
+    (-12..=3)
+        .flat_map(|exponent: i32| {
+            let power = if exponent < 0 {
+                dec!(1) / Decimal::from(10u64.pow(exponent.unsigned_abs()))
+            } else {
+                Decimal::from(10u64.pow(exponent as u32))
+            };
+            [dec!(1), dec!(2), dec!(5)].map(|mantissa| mantissa * power)
+        })
+        .collect()
+}
+
+fn main() -> ExitCode {
+    let step = match std::env::args().nth(1) {
+        Some(step) => match Decimal::try_from(step.as_str()) {
+            Ok(step) if step > Decimal::ZERO => step,
+            _ => {
+                eprintln!("Invalid weight step: {}", step);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => dec!(0.05),
+    };
+    let input_fractions = input_fractions();
+
+    println!("POW_ERROR_MARGIN: {}", POW_ERROR_MARGIN);
+    println!(
+        "{:>8} {:>8} {:>12} {:>12} {:>14} {:>14}",
+        "x_share", "samples", "violations", "worst_pow", "mean_over", "max_over"
+    );
+    let mut total = ErrorReport::default();
+    let mut x_share = MINIMUM_SHARE;
+    while x_share <= MAXIMUM_SHARE {
+        let report = sweep(x_share, &VAULTS, &input_fractions);
+        println!(
+            "{:>8} {:>8} {:>12} {:>12.3e} {:>14.3e} {:>14.3e}",
+            x_share.to_string(),
+            report.samples,
+            report.margin_violations,
+            report
+                .worst_pow_error
+                .as_ref()
+                .map_or(0.0, |worst| worst.to_f64()),
+            report.relative_overcharge_mean(),
+            report.relative_overcharge_max,
+        );
+        total.merge(&report);
+        x_share += step;
+    }
+
+    println!(
+        "total: {} samples, {} margin violations, worst pow error {:.3e}, mean overcharge {:.3e}, max overcharge {:.3e}",
+        total.samples,
+        total.margin_violations,
+        total.worst_pow_error.as_ref().map_or(0.0, |worst| worst.to_f64()),
+        total.relative_overcharge_mean(),
+        total.relative_overcharge_max,
+    );
+    if total.is_margin_safe() {
+        println!("POW_ERROR_MARGIN is safe for all swept swaps.");
+        ExitCode::SUCCESS
+    } else {
+        println!("POW_ERROR_MARGIN is NOT safe!");
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,7 @@
+use radix_common::prelude::*;
+use radix_common_derive::dec;
+
+pub const POW_ERROR_MARGIN: Decimal = dec!(0.0000000000000001);
+pub const SPLIT_ROUTE_ITERATIONS: u32 = 32;
+pub const MINIMUM_SHARE: Decimal = dec!(0.05);
+pub const MAXIMUM_SHARE: Decimal = dec!(0.95);
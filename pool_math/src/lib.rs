@@ -0,0 +1,9 @@
+pub mod constants;
+pub mod math;
+#[cfg(feature = "reference")]
+pub mod reference;
+pub mod rounding;
+pub mod split;
+
+pub use math::*;
+pub use split::*;
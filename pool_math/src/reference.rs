@@ -0,0 +1,417 @@
+//! High-precision reference model of the swap math, used to audit the rounding of the pool.
+//!
+//! All calculations are done on fixed-point big integers with `REFERENCE_DIGITS` decimal places, which makes their
+//! error negligible compared to the precision of `Decimal` and `PreciseDecimal`.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::constants::POW_ERROR_MARGIN;
+use crate::math::{imbalanced_power_operands, output_amount, SwapType};
+use crate::rounding::DivisibilityRounding;
+use num_bigint::BigInt;
+use radix_common::prelude::*;
+use radix_common_derive::dec;
+use scrypto_math::PowerDecimal;
+
+/// The number of decimal places of `ReferenceDecimal`.
+pub const REFERENCE_DIGITS: u32 = 100;
+
+/// A fixed-point decimal number with `REFERENCE_DIGITS` decimal places and an unlimited integer part.
+///
+/// Multiplications and divisions truncate towards zero.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReferenceDecimal(BigInt);
+
+fn scale() -> BigInt {
+    BigInt::from(10u32).pow(REFERENCE_DIGITS)
+}
+
+impl ReferenceDecimal {
+    pub fn zero() -> Self {
+        Self(BigInt::from(0u32))
+    }
+
+    pub fn one() -> Self {
+        Self(scale())
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.0 > BigInt::from(0u32)
+    }
+
+    /// Parses a decimal number without exponent, as printed by `Decimal` and `PreciseDecimal`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `value` is not a valid decimal number.
+    pub fn parse(value: &str) -> Self {
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+        let fraction: String = fraction
+            .chars()
+            .chain(std::iter::repeat('0'))
+            .take(REFERENCE_DIGITS as usize)
+            .collect();
+        let digits = BigInt::parse_bytes(format!("{}{}", integer, fraction).as_bytes(), 10)
+            .expect("Invalid decimal number!");
+        Self(if negative { -digits } else { digits })
+    }
+
+    /// Returns the natural logarithm.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the value is not positive.
+    pub fn ln(&self) -> Self {
+        assert!(self.is_positive(), "Logarithm of a non-positive number!");
+
+        // Reduce the value to `mantissa * 2^exponent` with the mantissa in [1, 2).
+        let one = scale();
+        let two = &one * 2;
+        let mut mantissa = self.0.clone();
+        let mut exponent: i64 = 0;
+        while mantissa >= two {
+            mantissa >>= 1;
+            exponent += 1;
+        }
+        while mantissa < one {
+            mantissa <<= 1;
+            exponent -= 1;
+        }
+
+        // ln(m) = 2 * atanh((m - 1) / (m + 1)) converges quickly for (m - 1) / (m + 1) in [0, 1/3).
+        let mantissa = Self(mantissa);
+        let ln_mantissa = atanh(&((mantissa.clone() - Self::one()) / (mantissa + Self::one())));
+        ln_mantissa * Self::from_integer(2) + ln_2() * Self::from_integer(exponent)
+    }
+
+    /// Returns `e^self`.
+    pub fn exp(&self) -> Self {
+        // Reduce the value to `remainder + exponent * ln(2)` with |remainder| < ln(2).
+        let ln_2 = ln_2();
+        let exponent = i64::try_from(&(&self.0 / &ln_2.0)).expect("Exponent too large!");
+        let remainder = self.clone() - ln_2 * Self::from_integer(exponent);
+
+        // Taylor series e^r = sum(r^n / n!)
+        let mut sum = Self::one();
+        let mut term = Self::one();
+        for n in 1.. {
+            term = term * remainder.clone() / Self::from_integer(n);
+            if term == Self::zero() {
+                break;
+            }
+            sum = sum + term.clone();
+        }
+
+        if exponent >= 0 {
+            Self(sum.0 << exponent as usize)
+        } else {
+            Self(sum.0 >> exponent.unsigned_abs() as usize)
+        }
+    }
+
+    /// Returns `self^exponent`, with `0^exponent = 0`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the value is negative.
+    pub fn pow(&self, exponent: &Self) -> Self {
+        if self.0 == BigInt::from(0u32) {
+            return Self::zero();
+        }
+        (self.ln() * exponent.clone()).exp()
+    }
+
+    /// Converts the value to the nearest `f64`, e.g. for reporting.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    fn from_integer(value: i64) -> Self {
+        Self(BigInt::from(value) * scale())
+    }
+}
+
+/// Returns `atanh(z) = sum(z^(2n+1) / (2n+1))` for |z| < 1.
+fn atanh(z: &ReferenceDecimal) -> ReferenceDecimal {
+    let z_squared = z.clone() * z.clone();
+    let mut sum = z.clone();
+    let mut power = z.clone();
+    for n in 1.. {
+        power = power * z_squared.clone();
+        let term = power.clone() / ReferenceDecimal::from_integer(2 * n + 1);
+        if term == ReferenceDecimal::zero() {
+            break;
+        }
+        sum = sum + term;
+    }
+    sum
+}
+
+/// Returns `ln(2) = 2 * atanh(1/3)`.
+fn ln_2() -> ReferenceDecimal {
+    atanh(&(ReferenceDecimal::one() / ReferenceDecimal::from_integer(3)))
+        * ReferenceDecimal::from_integer(2)
+}
+
+impl From<Decimal> for ReferenceDecimal {
+    fn from(value: Decimal) -> Self {
+        Self::parse(&value.to_string())
+    }
+}
+
+impl From<PreciseDecimal> for ReferenceDecimal {
+    fn from(value: PreciseDecimal) -> Self {
+        Self::parse(&value.to_string())
+    }
+}
+
+impl Add for ReferenceDecimal {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for ReferenceDecimal {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Mul for ReferenceDecimal {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(self.0 * other.0 / scale())
+    }
+}
+
+impl Div for ReferenceDecimal {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self(self.0 * scale() / other.0)
+    }
+}
+
+impl fmt::Display for ReferenceDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.0.magnitude().to_string();
+        let digits = format!("{:0>width$}", digits, width = REFERENCE_DIGITS as usize + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - REFERENCE_DIGITS as usize);
+        let sign = if self.0 < BigInt::from(0u32) { "-" } else { "" };
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+/// Calculates the exact output amount of a swap, the reference for `output_amount`.
+///
+/// # Arguments
+///
+/// * `input_vault` - The amount of tokens in the input vault.
+/// * `output_vault` - The amount of tokens in the output vault.
+/// * `input_amount_net` - The net amount of input tokens after fees.
+/// * `ratio` - The ratio of the token weights (x_share / y_share).
+/// * `swap_type` - The type of swap, either buying or selling the input token.
+///
+/// # Returns
+///
+/// * `ReferenceDecimal` - `R_o * in / (R_i + in)` for balanced pools and `R_o * (1 - (R_i / (R_i + in))^w)` for
+///   imbalanced pools, without any rounding in favour of the pool.
+pub fn output_amount_exact(
+    input_vault: Decimal,
+    output_vault: Decimal,
+    input_amount_net: Decimal,
+    ratio: Decimal,
+    swap_type: SwapType,
+) -> ReferenceDecimal {
+    let input_vault = ReferenceDecimal::from(input_vault);
+    let output_vault = ReferenceDecimal::from(output_vault);
+    let input_amount_net = ReferenceDecimal::from(input_amount_net);
+    if ratio == Decimal::ONE {
+        return output_vault * input_amount_net.clone() / (input_vault + input_amount_net);
+    }
+
+    let weight = match swap_type {
+        SwapType::BuyX => ReferenceDecimal::one() / ReferenceDecimal::from(ratio),
+        SwapType::SellX => ReferenceDecimal::from(ratio),
+    };
+    let input_vault_share = input_vault.clone() / (input_vault + input_amount_net);
+    output_vault * (ReferenceDecimal::one() - input_vault_share.pow(&weight))
+}
+
+/// The deviation of `output_amount` from the reference model for a single swap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorSample {
+    /// The exact minus the calculated value of the power in `output_amount_imbalanced` for the same operands,
+    /// positive if the pool would pay out too much without `POW_ERROR_MARGIN`. Zero for balanced pools.
+    pub pow_error: ReferenceDecimal,
+    /// The exact output amount.
+    pub output_amount_exact: ReferenceDecimal,
+    /// The output amount calculated by `output_amount` with a divisibility of 18.
+    pub output_amount: Decimal,
+}
+
+impl ErrorSample {
+    /// Calculates the deviation of `output_amount` from the reference model for a single swap.
+    pub fn new(
+        input_vault: Decimal,
+        output_vault: Decimal,
+        input_amount_net: Decimal,
+        ratio: Decimal,
+        swap_type: SwapType,
+    ) -> Self {
+        let pow_error = if ratio == Decimal::ONE {
+            ReferenceDecimal::zero()
+        } else {
+            let (base, exponent) =
+                imbalanced_power_operands(input_vault.into(), input_amount_net, ratio, swap_type);
+            let power = base.pow(exponent).unwrap();
+            ReferenceDecimal::from(base).pow(&exponent.into()) - ReferenceDecimal::from(power)
+        };
+        Self {
+            pow_error,
+            output_amount_exact: output_amount_exact(
+                input_vault,
+                output_vault,
+                input_amount_net,
+                ratio,
+                swap_type,
+            ),
+            output_amount: output_amount(
+                input_vault,
+                output_vault,
+                input_amount_net,
+                ratio,
+                swap_type,
+                18,
+            ),
+        }
+    }
+
+    /// Returns the exact minus the calculated output amount, negative if the user receives more than the exact amount.
+    pub fn overcharge(&self) -> ReferenceDecimal {
+        self.output_amount_exact.clone() - ReferenceDecimal::from(self.output_amount)
+    }
+}
+
+/// Aggregates error samples, e.g. of a sweep over the input space.
+#[derive(Clone, Debug, Default)]
+pub struct ErrorReport {
+    /// The number of samples.
+    pub samples: usize,
+    /// The largest pool-unfavourable error of the power, i.e. the largest `ErrorSample::pow_error`.
+    pub worst_pow_error: Option<ReferenceDecimal>,
+    /// The number of samples for which the user receives more than the exact output amount.
+    pub margin_violations: usize,
+    /// The sum of the overcharges relative to the exact output amount, of samples with a positive exact output.
+    pub relative_overcharge_sum: f64,
+    /// The largest overcharge relative to the exact output amount.
+    pub relative_overcharge_max: f64,
+    /// The number of samples with a positive exact output amount.
+    pub relative_samples: usize,
+}
+
+impl ErrorReport {
+    pub fn add(&mut self, sample: &ErrorSample) {
+        self.samples += 1;
+        self.record_pow_error(&sample.pow_error);
+        let overcharge = sample.overcharge();
+        if overcharge < ReferenceDecimal::zero() {
+            self.margin_violations += 1;
+        }
+        if sample.output_amount_exact.is_positive() {
+            let relative_overcharge = (overcharge / sample.output_amount_exact.clone()).to_f64();
+            self.relative_overcharge_sum += relative_overcharge;
+            self.relative_overcharge_max = self.relative_overcharge_max.max(relative_overcharge);
+            self.relative_samples += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &ErrorReport) {
+        self.samples += other.samples;
+        if let Some(pow_error) = &other.worst_pow_error {
+            self.record_pow_error(pow_error);
+        }
+        self.margin_violations += other.margin_violations;
+        self.relative_overcharge_sum += other.relative_overcharge_sum;
+        self.relative_overcharge_max = self
+            .relative_overcharge_max
+            .max(other.relative_overcharge_max);
+        self.relative_samples += other.relative_samples;
+    }
+
+    fn record_pow_error(&mut self, pow_error: &ReferenceDecimal) {
+        if !matches!(&self.worst_pow_error, Some(worst) if worst >= pow_error) {
+            self.worst_pow_error = Some(pow_error.clone());
+        }
+    }
+
+    /// Returns the average overcharge relative to the exact output amount.
+    pub fn relative_overcharge_mean(&self) -> f64 {
+        if self.relative_samples == 0 {
+            return 0.0;
+        }
+        self.relative_overcharge_sum / self.relative_samples as f64
+    }
+
+    /// Returns true if `POW_ERROR_MARGIN` covers the worst error of the power and no user received more than the
+    /// exact output amount.
+    pub fn is_margin_safe(&self) -> bool {
+        let pow_error_covered = match &self.worst_pow_error {
+            Some(worst) => worst < &ReferenceDecimal::from(POW_ERROR_MARGIN),
+            None => true,
+        };
+        self.margin_violations == 0 && pow_error_covered
+    }
+}
+
+/// Sweeps swaps in both directions of a pool with the given share of token X.
+///
+/// # Arguments
+///
+/// * `x_share` - The share of token X in the pool's total value, the ratio is derived like in the pool.
+/// * `vaults` - The reserves `(x_vault, y_vault)` to sweep.
+/// * `input_fractions` - The net input amounts to sweep, as fractions of the input vault.
+///
+/// # Returns
+///
+/// * `ErrorReport` - The aggregated errors of all swaps with a non-zero input amount.
+pub fn sweep(
+    x_share: Decimal,
+    vaults: &[(Decimal, Decimal)],
+    input_fractions: &[Decimal],
+) -> ErrorReport {
+    let ratio = x_share / (dec!(1) - x_share);
+    let mut report = ErrorReport::default();
+    for &(x_vault, y_vault) in vaults {
+        for swap_type in [SwapType::SellX, SwapType::BuyX] {
+            let (input_vault, output_vault) = match swap_type {
+                SwapType::SellX => (x_vault, y_vault),
+                SwapType::BuyX => (y_vault, x_vault),
+            };
+            for &input_fraction in input_fractions {
+                let input_amount_net = (input_vault * input_fraction).floor_to(18);
+                if input_amount_net.is_zero() {
+                    continue;
+                }
+                report.add(&ErrorSample::new(
+                    input_vault,
+                    output_vault,
+                    input_amount_net,
+                    ratio,
+                    swap_type,
+                ));
+            }
+        }
+    }
+    report
+}
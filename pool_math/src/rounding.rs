@@ -0,0 +1,44 @@
+use radix_common::prelude::*;
+
+/// Trait to round a value to the divisibility of a resource.
+pub trait DivisibilityRounding {
+    /// Rounds the value down to `divisibility` decimal places.
+    fn floor_to(self, divisibility: u8) -> Decimal;
+    /// Rounds the value up to `divisibility` decimal places.
+    fn ceil_to(self, divisibility: u8) -> Decimal;
+}
+
+impl DivisibilityRounding for Decimal {
+    fn floor_to(self, divisibility: u8) -> Decimal {
+        self.checked_round(divisibility, RoundingMode::ToNegativeInfinity)
+            .unwrap()
+    }
+
+    fn ceil_to(self, divisibility: u8) -> Decimal {
+        self.checked_round(divisibility, RoundingMode::ToPositiveInfinity)
+            .unwrap()
+    }
+}
+
+impl DivisibilityRounding for PreciseDecimal {
+    fn floor_to(self, divisibility: u8) -> Decimal {
+        self.checked_round(divisibility, RoundingMode::ToNegativeInfinity)
+            .and_then(|value| value.checked_truncate(RoundingMode::ToNegativeInfinity))
+            .unwrap()
+    }
+
+    fn ceil_to(self, divisibility: u8) -> Decimal {
+        self.checked_round(divisibility, RoundingMode::ToPositiveInfinity)
+            .and_then(|value| value.checked_truncate(RoundingMode::ToPositiveInfinity))
+            .unwrap()
+    }
+}
+
+/// Trait providing the smallest positive `PreciseDecimal`.
+pub trait AttoPreciseDecimal {
+    const ATTO: PreciseDecimal;
+}
+
+impl AttoPreciseDecimal for PreciseDecimal {
+    const ATTO: Self = Self::from_attos(I256::ONE);
+}
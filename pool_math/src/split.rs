@@ -0,0 +1,167 @@
+use crate::constants::SPLIT_ROUTE_ITERATIONS;
+use crate::rounding::DivisibilityRounding;
+use radix_common::prelude::*;
+use radix_common_derive::dec;
+use scrypto_math::PowerDecimal;
+
+/// The state of a pool relevant for splitting an input amount between pools of the same pair.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SplitPool {
+    /// The reserves of the input token.
+    pub input_vault: Decimal,
+    /// The reserves of the output token.
+    pub output_vault: Decimal,
+    /// The fee rate applied to swap inputs.
+    pub input_fee_rate: Decimal,
+    /// The weight of the input token divided by the weight of the output token.
+    pub weight: Decimal,
+}
+
+impl SplitPool {
+    /// Returns whether the pool has no reserves of either token and can therefore not receive a part.
+    fn is_empty(&self) -> bool {
+        self.input_vault <= Decimal::ZERO || self.output_vault <= Decimal::ZERO
+    }
+
+    /// Returns the net input amount per gross input amount, i.e. `1 - input_fee_rate`.
+    fn input_factor(&self) -> Decimal {
+        dec!(1) - self.input_fee_rate
+    }
+
+    /// Returns the marginal output per gross input amount before the swap, i.e. the spot price after fees.
+    fn initial_marginal_rate(&self) -> Decimal {
+        self.output_vault * self.weight * self.input_factor() / self.input_vault
+    }
+
+    /// Returns the gross input amount after which the marginal output of the pool has dropped to `marginal_rate`.
+    fn input_amount_at(&self, marginal_rate: Decimal) -> Decimal {
+        if self.is_empty() {
+            return Decimal::ZERO;
+        }
+        let initial_marginal_rate = self.initial_marginal_rate();
+        if marginal_rate >= initial_marginal_rate {
+            return Decimal::ZERO;
+        }
+        if marginal_rate <= Decimal::ZERO {
+            return Decimal::MAX;
+        }
+        match (initial_marginal_rate / marginal_rate).pow(dec!(1) / (self.weight + dec!(1))) {
+            Some(growth) => self.input_vault * (growth - dec!(1)) / self.input_factor(),
+            None => Decimal::MAX,
+        }
+    }
+}
+
+/// Splits an input amount between pools of the same pair, maximising the total output.
+///
+/// The total output is maximal if the marginal output of all pools receiving a part is equal.
+///
+/// # Arguments
+///
+/// * `input_amount` - The gross amount of input tokens to be split.
+/// * `pools` - The pools to split the input amount between.
+/// * `divisibility` - The divisibility of the input token.
+///
+/// # Returns
+///
+/// * `Vec<Decimal>` - The gross input amount for each pool, in the order of `pools`, summing up to `input_amount`.
+///
+/// # Details
+///
+/// Based on the output formula `out = R_o * (1 - (R_i / (R_i + g * in))^w)`, with the net input per gross input `g` and
+/// the weight `w`, the marginal output of a pool is `m(in) = m_0 * (R_i / (R_i + g * in))^(w + 1)` with the initial
+/// marginal output `m_0 = R_o * w * g / R_i`. Solving `m(in) = m` results in `in = R_i * ((m_0 / m)^(1 / (w + 1)) - 1) / g`.
+/// Since the total input is decreasing in `m`, the common marginal output is found via bisection. The resulting
+/// amounts are then scaled to match `input_amount` exactly, with the rounding remainder added to the largest part.
+///
+/// Pools without reserves receive nothing. The lower bound of the bisection is halved at most `SPLIT_ROUTE_ITERATIONS`
+/// times; if it still does not allocate the input amount, e.g. for inputs exceeding the reserves by orders of
+/// magnitude, the input amount is split proportionally to the input reserves instead.
+pub fn split_input_amount(
+    input_amount: Decimal,
+    pools: &[SplitPool],
+    divisibility: u8,
+) -> Vec<Decimal> {
+    let total_input_at = |marginal_rate: Decimal| -> Decimal {
+        pools
+            .iter()
+            .map(|pool| pool.input_amount_at(marginal_rate))
+            .fold(Decimal::ZERO, |total, amount| {
+                total.checked_add(amount).unwrap_or(Decimal::MAX)
+            })
+    };
+
+    // The upper bound allocates nothing, while the lower bound is decreased until it allocates at least the input amount.
+    let mut upper = pools
+        .iter()
+        .filter(|pool| !pool.is_empty())
+        .map(|pool| pool.initial_marginal_rate())
+        .max()
+        .unwrap_or(Decimal::ZERO);
+    let mut lower = upper / 2;
+    let mut halvings = 0;
+    while lower > Decimal::ZERO
+        && halvings < SPLIT_ROUTE_ITERATIONS
+        && total_input_at(lower) < input_amount
+    {
+        upper = lower;
+        lower = lower / 2;
+        halvings += 1;
+    }
+    let reachable = lower > Decimal::ZERO && total_input_at(lower) >= input_amount;
+
+    let amounts: Vec<Decimal> = if reachable {
+        for _ in 0..SPLIT_ROUTE_ITERATIONS {
+            let middle = match (lower * upper).checked_sqrt() {
+                Some(middle) if middle > lower && middle < upper => middle,
+                _ => break,
+            };
+            if total_input_at(middle) < input_amount {
+                upper = middle;
+            } else {
+                lower = middle;
+            }
+        }
+        pools
+            .iter()
+            .map(|pool| pool.input_amount_at(lower))
+            .collect()
+    } else {
+        pools
+            .iter()
+            .map(|pool| {
+                if pool.is_empty() {
+                    Decimal::ZERO
+                } else {
+                    pool.input_vault
+                }
+            })
+            .collect()
+    };
+    let total = amounts.iter().fold(Decimal::ZERO, |total, amount| {
+        total.checked_add(*amount).unwrap_or(Decimal::MAX)
+    });
+    let mut amounts: Vec<Decimal> = amounts
+        .iter()
+        .map(|amount| {
+            if total > Decimal::ZERO {
+                (PreciseDecimal::from(input_amount) * *amount / total).floor_to(divisibility)
+            } else {
+                Decimal::ZERO
+            }
+        })
+        .collect();
+
+    let remainder = input_amount
+        - amounts
+            .iter()
+            .fold(Decimal::ZERO, |sum, amount| sum + *amount);
+    if let Some(largest) =
+        amounts
+            .iter_mut()
+            .reduce(|largest, amount| if *amount > *largest { amount } else { largest })
+    {
+        *largest += remainder;
+    }
+    amounts
+}
@@ -0,0 +1,134 @@
+use ociswap_pool_math::*;
+use radix_common::prelude::*;
+use radix_common_derive::dec;
+
+const VAULTS: [(Decimal, Decimal); 3] = [
+    (dec!(100000), dec!(5000)),
+    (dec!(1), dec!(1000000)),
+    (dec!(0.001), dec!(0.002)),
+];
+const RATIOS: [Decimal; 4] = [dec!(1), dec!(4), dec!(0.25), dec!(19)];
+const SWAP_TYPES: [SwapType; 2] = [SwapType::BuyX, SwapType::SellX];
+
+fn smallest_unit(divisibility: u8) -> Decimal {
+    dec!(1) / 10u64.pow(divisibility as u32)
+}
+
+#[test]
+fn test_input_amount_gross() {
+    for input_fee_rate in [dec!(0), dec!(0.003), dec!(0.1), dec!(0.5), dec!(0.999)] {
+        for divisibility in [18u8, 6, 0] {
+            for input_amount_net_expected in [dec!(0), dec!(1), dec!(12.345678), dec!(100000)] {
+                let input_amount_net_expected = input_amount_net_expected
+                    .checked_round(divisibility, RoundingMode::ToNegativeInfinity)
+                    .unwrap();
+                let gross =
+                    input_amount_gross(input_amount_net_expected, input_fee_rate, divisibility);
+                let (net, _, _) = input_amount_net(gross, input_fee_rate, dec!(0), divisibility);
+                assert!(net >= input_amount_net_expected);
+
+                // One unit less is not sufficient, hence the gross amount is minimal.
+                if gross > Decimal::ZERO {
+                    let (net, _, _) = input_amount_net(
+                        gross - smallest_unit(divisibility),
+                        input_fee_rate,
+                        dec!(0),
+                        divisibility,
+                    );
+                    assert!(net < input_amount_net_expected);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_input_amount_gross_full_fee() {
+    let result = std::panic::catch_unwind(|| input_amount_gross(dec!(1), dec!(1), 18));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_input_amount_for_output() {
+    for (input_vault, output_vault) in VAULTS {
+        for ratio in RATIOS {
+            for swap_type in SWAP_TYPES {
+                for output_fraction in [dec!(0.000001), dec!(0.1), dec!(0.5)] {
+                    let output_amount_expected = output_vault * output_fraction;
+                    let input_amount = input_amount_for_output(
+                        input_vault,
+                        output_vault,
+                        output_amount_expected,
+                        ratio,
+                        swap_type,
+                        18,
+                    );
+                    let output = output_amount(
+                        input_vault,
+                        output_vault,
+                        input_amount,
+                        ratio,
+                        swap_type,
+                        18,
+                    );
+                    assert!(
+                        output >= output_amount_expected,
+                        "Vaults: {:?} {:?}, ratio: {:?}, swap type: {:?}, output: {:?}",
+                        input_vault,
+                        output_vault,
+                        ratio,
+                        swap_type,
+                        output_amount_expected
+                    );
+                    // The input amount overshoots by a negligible amount only.
+                    assert!(output - output_amount_expected <= output_vault * dec!(0.000000000001));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_input_amount_for_output_zero() {
+    assert_eq!(
+        input_amount_for_output(dec!(100), dec!(100), dec!(0), dec!(1), SwapType::SellX, 18),
+        dec!(0)
+    );
+}
+
+#[test]
+fn test_input_amount_for_output_whole_vault() {
+    let result = std::panic::catch_unwind(|| {
+        input_amount_for_output(
+            dec!(100),
+            dec!(100),
+            dec!(100),
+            dec!(1),
+            SwapType::SellX,
+            18,
+        )
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_input_amount_for_output_divisibility() {
+    let input_amount =
+        input_amount_for_output(dec!(100), dec!(200), dec!(10), dec!(1), SwapType::SellX, 2);
+    assert_eq!(
+        input_amount,
+        input_amount
+            .checked_round(2, RoundingMode::ToPositiveInfinity)
+            .unwrap()
+    );
+    assert!(
+        output_amount(
+            dec!(100),
+            dec!(200),
+            input_amount,
+            dec!(1),
+            SwapType::SellX,
+            18
+        ) >= dec!(10)
+    );
+}
@@ -0,0 +1,170 @@
+use num_bigint::BigUint;
+use ociswap_pool_math::constants::{MAXIMUM_SHARE, MINIMUM_SHARE};
+use ociswap_pool_math::rounding::DivisibilityRounding;
+use ociswap_pool_math::*;
+use proptest::prelude::*;
+use radix_common::prelude::*;
+use radix_common_derive::dec;
+
+const DIVISIBILITIES: [u8; 5] = [18, 8, 6, 2, 0];
+
+/// Converts a non-negative amount into its exact number of attos.
+fn attos(amount: Decimal) -> BigUint {
+    BigUint::parse_bytes(amount.attos().to_string().as_bytes(), 10).unwrap()
+}
+
+/// Returns the weighted invariant `x^x_share * y^y_share` raised to the power of 100, which is exact
+/// for shares with two decimal places.
+fn invariant(x_vault: Decimal, y_vault: Decimal, x_share_percent: u32) -> BigUint {
+    attos(x_vault).pow(x_share_percent) * attos(y_vault).pow(100 - x_share_percent)
+}
+
+fn percent(share: Decimal) -> u32 {
+    (share * dec!(100)).to_string().parse().unwrap()
+}
+
+/// Decimal between 10^-6 and 10^12 rounded down to `divisibility`.
+fn amount(mantissa: u64, exponent: u32, divisibility: u8) -> Decimal {
+    (Decimal::from(mantissa) / Decimal::from(10u64.pow(exponent))).floor_to(divisibility)
+}
+
+#[derive(Clone, Debug)]
+struct Swap {
+    sell_x: bool,
+    /// Input amount as fraction of the input vault in basis points.
+    fraction_bps: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Scenario {
+    x_share_percent: u32,
+    input_fee_rate: Decimal,
+    fee_protocol_share: Decimal,
+    x_divisibility: u8,
+    y_divisibility: u8,
+    x_vault: Decimal,
+    y_vault: Decimal,
+    swaps: Vec<Swap>,
+}
+
+impl Scenario {
+    fn ratio(&self) -> Decimal {
+        let x_share = Decimal::from(self.x_share_percent) / dec!(100);
+        x_share / (dec!(1) - x_share)
+    }
+}
+
+fn swap_strategy() -> impl Strategy<Value = Swap> {
+    (any::<bool>(), 1u64..=50_000).prop_map(|(sell_x, fraction_bps)| Swap {
+        sell_x,
+        fraction_bps,
+    })
+}
+
+prop_compose! {
+    fn scenario_strategy()(
+        x_share_percent in percent(MINIMUM_SHARE)..=percent(MAXIMUM_SHARE),
+        input_fee_ppm in 0u64..=100_000,
+        fee_protocol_share_percent in 0u64..=25,
+        x_divisibility in prop::sample::select(DIVISIBILITIES.to_vec()),
+        y_divisibility in prop::sample::select(DIVISIBILITIES.to_vec()),
+        (x_mantissa, x_exponent) in (1u64..=1_000_000_000_000, 0u32..=6),
+        (y_mantissa, y_exponent) in (1u64..=1_000_000_000_000, 0u32..=6),
+        swaps in prop::collection::vec(swap_strategy(), 1..8),
+    ) -> Scenario {
+        Scenario {
+            x_share_percent,
+            input_fee_rate: Decimal::from(input_fee_ppm) / dec!(1000000),
+            fee_protocol_share: Decimal::from(fee_protocol_share_percent) / dec!(100),
+            x_divisibility,
+            y_divisibility,
+            x_vault: amount(x_mantissa, x_exponent, x_divisibility),
+            y_vault: amount(y_mantissa, y_exponent, y_divisibility),
+            swaps,
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    /// Applies a sequence of swaps to the reserves, keeping the net input and the LP fee in the pool while the
+    /// protocol fee leaves it, and checks the weighted invariant after every swap.
+    #[test]
+    fn test_swaps_never_decrease_invariant(scenario in scenario_strategy()) {
+        let (mut x_vault, mut y_vault) = (scenario.x_vault, scenario.y_vault);
+        prop_assume!(x_vault > Decimal::ZERO && y_vault > Decimal::ZERO);
+        let ratio = scenario.ratio();
+
+        for swap in &scenario.swaps {
+            let (swap_type, input_vault, output_vault, input_divisibility, output_divisibility) =
+                if swap.sell_x {
+                    let divisibilities = (scenario.x_divisibility, scenario.y_divisibility);
+                    (SwapType::SellX, x_vault, y_vault, divisibilities.0, divisibilities.1)
+                } else {
+                    let divisibilities = (scenario.y_divisibility, scenario.x_divisibility);
+                    (SwapType::BuyX, y_vault, x_vault, divisibilities.0, divisibilities.1)
+                };
+            let input_amount = (input_vault * Decimal::from(swap.fraction_bps) / dec!(10000))
+                .floor_to(input_divisibility);
+            if input_amount.is_zero() {
+                continue;
+            }
+
+            let (input_amount_net, input_fee_lp, input_fee_protocol) = input_amount_net(
+                input_amount,
+                scenario.input_fee_rate,
+                scenario.fee_protocol_share,
+                input_divisibility,
+            );
+            prop_assert_eq!(input_amount_net + input_fee_lp + input_fee_protocol, input_amount);
+            let output = output_amount(
+                input_vault,
+                output_vault,
+                input_amount_net,
+                ratio,
+                swap_type,
+                output_divisibility,
+            );
+            prop_assert!(output < output_vault);
+
+            let (new_x_vault, new_y_vault) = if swap.sell_x {
+                (x_vault + input_amount_net + input_fee_lp, y_vault - output)
+            } else {
+                (x_vault - output, y_vault + input_amount_net + input_fee_lp)
+            };
+            prop_assert!(
+                invariant(new_x_vault, new_y_vault, scenario.x_share_percent)
+                    >= invariant(x_vault, y_vault, scenario.x_share_percent),
+                "Invariant decreased: {:?} {:?} -> {:?} {:?}",
+                x_vault,
+                y_vault,
+                new_x_vault,
+                new_y_vault
+            );
+            (x_vault, y_vault) = (new_x_vault, new_y_vault);
+        }
+    }
+
+    /// The fee is rounded up and split without loss, so the net input never exceeds the exact net input.
+    #[test]
+    fn test_input_amount_net_favours_pool(
+        input_mantissa in 1u64..=1_000_000_000_000,
+        input_exponent in 0u32..=6,
+        input_fee_ppm in 0u64..=1_000_000,
+        fee_protocol_share_percent in 0u64..=100,
+        divisibility in prop::sample::select(DIVISIBILITIES.to_vec()),
+    ) {
+        let input_amount = amount(input_mantissa, input_exponent, divisibility);
+        let input_fee_rate = Decimal::from(input_fee_ppm) / dec!(1000000);
+        let fee_protocol_share = Decimal::from(fee_protocol_share_percent) / dec!(100);
+        let (net, fee_lp, fee_protocol) =
+            input_amount_net(input_amount, input_fee_rate, fee_protocol_share, divisibility);
+        prop_assert!(net >= Decimal::ZERO);
+        prop_assert!(fee_lp >= Decimal::ZERO && fee_protocol >= Decimal::ZERO);
+        prop_assert_eq!(net + fee_lp + fee_protocol, input_amount);
+        let net_exact = PreciseDecimal::from(input_amount) * (PreciseDecimal::ONE - input_fee_rate);
+        prop_assert!(PreciseDecimal::from(net) <= net_exact);
+        prop_assert_eq!(net, net.floor_to(divisibility));
+    }
+}
@@ -0,0 +1,142 @@
+use ociswap_pool_math::constants::{MAXIMUM_SHARE, MINIMUM_SHARE};
+use ociswap_pool_math::reference::*;
+use ociswap_pool_math::*;
+use radix_common::prelude::*;
+use radix_common_derive::dec;
+
+const VAULTS: [(Decimal, Decimal); 3] = [
+    (dec!(100000), dec!(5000)),
+    (dec!(1), dec!(1000000)),
+    (dec!(0.001), dec!(0.002)),
+];
+const INPUT_FRACTIONS: [Decimal; 7] = [
+    dec!(0.000000000001),
+    dec!(0.000001),
+    dec!(0.001),
+    dec!(0.1),
+    dec!(1),
+    dec!(10),
+    dec!(1000),
+];
+
+/// Asserts that the difference of both values is below 10^-90.
+fn assert_close(actual: ReferenceDecimal, expected: &str) {
+    let expected = ReferenceDecimal::parse(expected);
+    let tolerance = ReferenceDecimal::parse(&format!("0.{}1", "0".repeat(89)));
+    let difference = if actual > expected {
+        actual.clone() - expected.clone()
+    } else {
+        expected.clone() - actual.clone()
+    };
+    assert!(
+        difference < tolerance,
+        "{} is not close to {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_parse_and_display() {
+    assert_eq!(
+        ReferenceDecimal::from(dec!(-12.5)).to_string(),
+        format!("-12.5{}", "0".repeat(99))
+    );
+    assert_eq!(
+        ReferenceDecimal::from(PreciseDecimal::ATTO),
+        ReferenceDecimal::parse("0.000000000000000000000000000000000001")
+    );
+    assert_eq!(ReferenceDecimal::from(dec!(0.25)).to_f64(), 0.25);
+}
+
+#[test]
+fn test_ln_exp() {
+    assert_close(
+        ReferenceDecimal::from(dec!(2)).ln(),
+        "0.693147180559945309417232121458176568075500134360255254120680009493393621969694715605863326996418687",
+    );
+    assert_close(
+        ReferenceDecimal::one().exp(),
+        "2.718281828459045235360287471352662497757247093699959574966967627724076630353547594571382178525166427",
+    );
+    assert_close(ReferenceDecimal::one().ln(), "0");
+    assert_close(ReferenceDecimal::zero().exp(), "1");
+}
+
+#[test]
+fn test_pow() {
+    assert_close(
+        ReferenceDecimal::from(dec!(0.25)).pow(&ReferenceDecimal::from(dec!(0.5))),
+        "0.5",
+    );
+    assert_close(
+        ReferenceDecimal::from(dec!(0.5)).pow(&ReferenceDecimal::from(dec!(19))),
+        "0.0000019073486328125",
+    );
+    assert_close(
+        ReferenceDecimal::from(dec!(8))
+            .pow(&(ReferenceDecimal::one() / ReferenceDecimal::from(dec!(3)))),
+        "2",
+    );
+    assert_eq!(
+        ReferenceDecimal::zero().pow(&ReferenceDecimal::from(dec!(3))),
+        ReferenceDecimal::zero()
+    );
+}
+
+#[test]
+fn test_output_amount_exact_balanced() {
+    // out = R_o * in / (R_i + in) = 200 * 100 / (100 + 100)
+    assert_close(
+        output_amount_exact(dec!(100), dec!(200), dec!(100), dec!(1), SwapType::SellX),
+        "100",
+    );
+}
+
+#[test]
+fn test_output_amount_exact_imbalanced() {
+    // out = R_o * (1 - (R_i / (R_i + in))^w) = 100 * (1 - (100 / 400)^0.5) with w = 1 / 2
+    assert_close(
+        output_amount_exact(dec!(100), dec!(100), dec!(300), dec!(2), SwapType::BuyX),
+        "50",
+    );
+    // with w = 2
+    assert_close(
+        output_amount_exact(dec!(100), dec!(100), dec!(100), dec!(2), SwapType::SellX),
+        "75",
+    );
+}
+
+#[test]
+fn test_output_amount_never_exceeds_exact() {
+    for x_share in [
+        MINIMUM_SHARE,
+        dec!(0.2),
+        dec!(0.5),
+        dec!(0.8),
+        MAXIMUM_SHARE,
+    ] {
+        let report = sweep(x_share, &VAULTS, &INPUT_FRACTIONS);
+        assert_eq!(
+            report.samples,
+            VAULTS.len() * 2 * INPUT_FRACTIONS.len(),
+            "x_share {}",
+            x_share
+        );
+        assert_eq!(report.margin_violations, 0, "x_share {}", x_share);
+        assert!(report.relative_overcharge_mean() >= 0.0);
+    }
+}
+
+#[test]
+fn test_pow_error_margin_is_safe_at_extreme_weights() {
+    for x_share in [MINIMUM_SHARE, MAXIMUM_SHARE] {
+        let report = sweep(x_share, &VAULTS, &INPUT_FRACTIONS);
+        assert!(
+            report.is_margin_safe(),
+            "x_share {}: worst pow error {:?}",
+            x_share,
+            report.worst_pow_error
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use ociswap_pool_math::*;
+use radix_common::prelude::*;
+use radix_common_derive::dec;
+
+fn pool(input_vault: Decimal, output_vault: Decimal) -> SplitPool {
+    SplitPool {
+        input_vault,
+        output_vault,
+        input_fee_rate: dec!(0.003),
+        weight: dec!(1),
+    }
+}
+
+fn sum(amounts: &[Decimal]) -> Decimal {
+    amounts
+        .iter()
+        .fold(Decimal::ZERO, |sum, amount| sum + *amount)
+}
+
+#[test]
+fn test_split_equal_pools() {
+    let pools = [pool(dec!(100), dec!(100)), pool(dec!(100), dec!(100))];
+    let amounts = split_input_amount(dec!(10), &pools, 18);
+    assert_eq!(sum(&amounts), dec!(10));
+    assert!((amounts[0] - amounts[1]).checked_abs().unwrap() <= dec!(0.000001));
+}
+
+#[test]
+fn test_split_skips_empty_pools() {
+    let pools = [
+        pool(dec!(0), dec!(100)),
+        pool(dec!(100), dec!(100)),
+        pool(dec!(100), dec!(0)),
+    ];
+    assert_eq!(
+        split_input_amount(dec!(10), &pools, 18),
+        vec![dec!(0), dec!(10), dec!(0)]
+    );
+}
+
+#[test]
+fn test_split_unreachable_input_amount() {
+    // The input amount exceeds the reserves by far more than the bounded search can allocate,
+    // so it is split proportionally to the input reserves.
+    let pools = [pool(dec!(1), dec!(1)), pool(dec!(3), dec!(3))];
+    let input_amount = dec!(1000000000000000000);
+    assert_eq!(
+        split_input_amount(input_amount, &pools, 18),
+        vec![input_amount / 4, input_amount * 3 / 4]
+    );
+}
+
+#[test]
+fn test_split_tiny_reserves() {
+    let pools = [
+        pool(Decimal::from_attos(I192::ONE), dec!(1)),
+        pool(dec!(1), dec!(1)),
+    ];
+    let amounts = split_input_amount(dec!(1), &pools, 18);
+    assert_eq!(sum(&amounts), dec!(1));
+}
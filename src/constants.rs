@@ -1,9 +1,15 @@
 use scrypto::prelude::*;
 
-pub const INPUT_FEE_RATE_MAX: Decimal = dec!(0.1);
+pub use ociswap_pool_hooks::constants::{HOOKS_MIN_REMAINING_BUCKET_FRACTION, INPUT_FEE_RATE_MAX};
+pub use ociswap_pool_math::constants::{
+    MAXIMUM_SHARE, MINIMUM_SHARE, POW_ERROR_MARGIN, SPLIT_ROUTE_ITERATIONS,
+};
+
 pub const FEE_PROTOCOL_SHARE_MAX: Decimal = dec!(0.25);
 pub const FLASH_LOAN_FEE_RATE_MAX: Decimal = dec!(0.1);
-pub const HOOKS_MIN_REMAINING_BUCKET_FRACTION: Decimal = dec!(0.9);
-pub const MINIMUM_SHARE: Decimal = dec!(0.05);
-pub const MAXIMUM_SHARE: Decimal = dec!(0.95);
-pub const POW_ERROR_MARGIN: Decimal = dec!(0.0000000000000001);
+pub const FEE_RATES_UPDATE_TIMELOCK: u64 = 7 * 24 * 60 * 60; // 7 days in seconds
+pub const FACTORY_PAGE_SIZE_MAX: u64 = 100;
+pub const SPLIT_ROUTE_POOLS_MAX: usize = 10;
+pub const REGISTRY_SYNC_DEFERRAL_MAX: u64 = 24 * 60 * 60; // 1 day in seconds
+pub const REGISTRY_SYNC_GRACE_PERIOD_MAX: u64 = 24 * 60 * 60; // 1 day in seconds
+pub const REGISTRY_FAILOVER_STALENESS_MIN: u64 = 7 * 24 * 60 * 60; // 7 days in seconds
@@ -0,0 +1,339 @@
+use crate::constants::*;
+use crate::pool::pool::Pool;
+use common::pools::check_and_sort_addresses;
+use scrypto::prelude::*;
+
+#[blueprint]
+#[events(FactoryPoolEvent, CanonicalPoolEvent)]
+mod factory {
+    enable_method_auth! {
+        methods {
+            instantiate_pool                => PUBLIC;
+            instantiate_pool_with_liquidity => PUBLIC;
+            set_canonical_pool              => restrict_to: [OWNER];
+            pools_for_pair                  => PUBLIC;
+            pair_pool_count                 => PUBLIC;
+            pool_for_key                    => PUBLIC;
+            canonical_pool                  => PUBLIC;
+            pools                           => PUBLIC;
+            pool_count                      => PUBLIC;
+        }
+    }
+
+    struct Factory {
+        pools: KeyValueStore<u64, FactoryPool>,
+        pool_count: u64,
+        pool_indices: KeyValueStore<ComponentAddress, u64>,
+        pair_pools: KeyValueStore<((ResourceAddress, ResourceAddress), u64), u64>,
+        pair_pool_counts: KeyValueStore<(ResourceAddress, ResourceAddress), u64>,
+        key_pools: KeyValueStore<FactoryPoolKey, ComponentAddress>,
+        canonical_pools: KeyValueStore<(ResourceAddress, ResourceAddress), ComponentAddress>,
+    }
+
+    impl Factory {
+        /// Instantiates a new pool factory.
+        ///
+        /// The factory instantiates pools via the `Pool` blueprint of this package and indexes them by token pair as well as
+        /// by fee tier and weight, allowing routers to find pools on-ledger. It allows only one pool per pair, fee tier and weight.
+        ///
+        /// ## Arguments
+        /// - `owner_badge`: Resource address of the badge allowed to flag canonical pools.
+        ///
+        /// ## Returns
+        /// - A global reference to the instantiated `Factory`.
+        pub fn instantiate(owner_badge: ResourceAddress) -> Global<Factory> {
+            (Self {
+                pools: KeyValueStore::new(),
+                pool_count: 0,
+                pool_indices: KeyValueStore::new(),
+                pair_pools: KeyValueStore::new(),
+                pair_pool_counts: KeyValueStore::new(),
+                key_pools: KeyValueStore::new(),
+                canonical_pools: KeyValueStore::new(),
+            })
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::Fixed(rule!(require(owner_badge))))
+            .globalize()
+        }
+
+        /// Instantiates a new pool via `Pool::instantiate` and records it in the factory.
+        ///
+        /// ## Arguments
+        /// See `Pool::instantiate`.
+        ///
+        /// ## Returns
+        /// - A tuple containing the instantiated `Pool` and the resource address of its LP token.
+        ///
+        /// ## Panics
+        /// - If the factory already contains a pool with the same pair, input fee rate and weight.
+        /// - If the pool instantiation fails (see `Pool::instantiate`).
+        pub fn instantiate_pool(
+            &mut self,
+            a_address: ResourceAddress,
+            b_address: ResourceAddress,
+            input_fee_rate: Decimal,
+            flash_loan_fee_rate: Decimal,
+            a_share: Decimal,
+            hook_badges: Vec<(ComponentAddress, Bucket)>,
+        ) -> (Global<Pool>, ResourceAddress) {
+            let key = FactoryPoolKey::new(a_address, b_address, input_fee_rate, a_share);
+            self.assert_key_is_available(&key);
+
+            let (pool, lp_address) = Blueprint::<Pool>::instantiate(
+                a_address,
+                b_address,
+                input_fee_rate,
+                flash_loan_fee_rate,
+                a_share,
+                hook_badges,
+            );
+            self.record_pool(key, pool.address(), lp_address);
+            (pool, lp_address)
+        }
+
+        /// Instantiates a new pool with initial liquidity via `Pool::instantiate_with_liquidity` and records it in the factory.
+        ///
+        /// ## Arguments
+        /// See `Pool::instantiate_with_liquidity`.
+        ///
+        /// ## Returns
+        /// - A tuple containing the instantiated `Pool` and the LP tokens of the initial liquidity.
+        ///
+        /// ## Panics
+        /// - If the factory already contains a pool with the same pair, input fee rate and weight.
+        /// - If the pool instantiation fails (see `Pool::instantiate_with_liquidity`).
+        pub fn instantiate_pool_with_liquidity(
+            &mut self,
+            a_bucket: Bucket,
+            b_bucket: Bucket,
+            input_fee_rate: Decimal,
+            flash_loan_fee_rate: Decimal,
+            a_share: Decimal,
+            hook_badges: Vec<(ComponentAddress, Bucket)>,
+        ) -> (Global<Pool>, Bucket) {
+            let key = FactoryPoolKey::new(
+                a_bucket.resource_address(),
+                b_bucket.resource_address(),
+                input_fee_rate,
+                a_share,
+            );
+            self.assert_key_is_available(&key);
+
+            let (pool, lp_token) = Blueprint::<Pool>::instantiate_with_liquidity(
+                a_bucket,
+                b_bucket,
+                input_fee_rate,
+                flash_loan_fee_rate,
+                a_share,
+                hook_badges,
+            );
+            self.record_pool(key, pool.address(), lp_token.resource_address());
+            (pool, lp_token)
+        }
+
+        /// Flags a pool of the factory as the canonical pool of its pair, e.g. the pool routers should prefer.
+        /// Only callable by the owner.
+        ///
+        /// ## Panics
+        /// - If the pool has not been instantiated via this factory.
+        pub fn set_canonical_pool(&mut self, pool_address: ComponentAddress) {
+            let pool = self
+                .find_pool(pool_address)
+                .expect("[Factory]: Pool has not been instantiated via this factory.");
+            self.canonical_pools
+                .insert((pool.x_address, pool.y_address), pool_address);
+            Runtime::emit_event(CanonicalPoolEvent {
+                x_address: pool.x_address,
+                y_address: pool.y_address,
+                pool_address,
+            });
+        }
+
+        /// Enumerates the pools of the pair in order of instantiation, independent of the order of `a_address` and `b_address`.
+        ///
+        /// ## Arguments
+        /// - `start`: Index of the first pool of the pair to return.
+        /// - `limit`: Maximum number of pools to return, at most `FACTORY_PAGE_SIZE_MAX`.
+        pub fn pools_for_pair(
+            &self,
+            a_address: ResourceAddress,
+            b_address: ResourceAddress,
+            start: u64,
+            limit: u64,
+        ) -> Vec<FactoryPool> {
+            let pair = check_and_sort_addresses(a_address, b_address);
+            let end = start
+                .saturating_add(limit.min(FACTORY_PAGE_SIZE_MAX))
+                .min(self.pair_pool_count(pair.0, pair.1));
+            (start..end)
+                .map(|pair_index| {
+                    let index = *self.pair_pools.get(&(pair, pair_index)).unwrap();
+                    self.pools.get(&index).unwrap().clone()
+                })
+                .collect()
+        }
+
+        /// Returns the number of pools of the pair, independent of the order of `a_address` and `b_address`.
+        pub fn pair_pool_count(
+            &self,
+            a_address: ResourceAddress,
+            b_address: ResourceAddress,
+        ) -> u64 {
+            self.pair_pool_counts
+                .get(&check_and_sort_addresses(a_address, b_address))
+                .map(|count| *count)
+                .unwrap_or(0)
+        }
+
+        /// Returns the pool of the pair with the given input fee rate (fee tier) and share of token A (weight).
+        pub fn pool_for_key(
+            &self,
+            a_address: ResourceAddress,
+            b_address: ResourceAddress,
+            input_fee_rate: Decimal,
+            a_share: Decimal,
+        ) -> Option<ComponentAddress> {
+            let key = FactoryPoolKey::new(a_address, b_address, input_fee_rate, a_share);
+            self.key_pools.get(&key).map(|pool_address| *pool_address)
+        }
+
+        /// Returns the canonical pool of the pair, if flagged by the owner.
+        pub fn canonical_pool(
+            &self,
+            a_address: ResourceAddress,
+            b_address: ResourceAddress,
+        ) -> Option<ComponentAddress> {
+            self.canonical_pools
+                .get(&check_and_sort_addresses(a_address, b_address))
+                .map(|pool_address| *pool_address)
+        }
+
+        /// Enumerates the pools of the factory in order of instantiation.
+        ///
+        /// ## Arguments
+        /// - `start`: Index of the first pool to return.
+        /// - `limit`: Maximum number of pools to return, at most `FACTORY_PAGE_SIZE_MAX`.
+        pub fn pools(&self, start: u64, limit: u64) -> Vec<FactoryPool> {
+            let end = start
+                .saturating_add(limit.min(FACTORY_PAGE_SIZE_MAX))
+                .min(self.pool_count);
+            (start..end)
+                .map(|index| self.pools.get(&index).unwrap().clone())
+                .collect()
+        }
+
+        pub fn pool_count(&self) -> u64 {
+            self.pool_count
+        }
+
+        fn assert_key_is_available(&self, key: &FactoryPoolKey) {
+            assert!(
+                self.key_pools.get(key).is_none(),
+                "[Factory]: Pool with the same pair, input fee rate and weight already exists."
+            );
+        }
+
+        fn record_pool(
+            &mut self,
+            key: FactoryPoolKey,
+            pool_address: ComponentAddress,
+            lp_address: ResourceAddress,
+        ) {
+            let pool = FactoryPool {
+                index: self.pool_count,
+                pool_address,
+                lp_address,
+                x_address: key.x_address,
+                y_address: key.y_address,
+                input_fee_rate: key.input_fee_rate,
+                x_share: key.x_share,
+            };
+            let pair = (key.x_address, key.y_address);
+            let pair_pool_count = self.pair_pool_count(pair.0, pair.1);
+            self.pair_pools.insert((pair, pair_pool_count), pool.index);
+            self.pair_pool_counts.insert(pair, pair_pool_count + 1);
+            self.pools.insert(self.pool_count, pool.clone());
+            self.pool_indices.insert(pool_address, pool.index);
+            self.key_pools.insert(key, pool_address);
+            self.pool_count += 1;
+
+            Runtime::emit_event(FactoryPoolEvent {
+                index: pool.index,
+                pool_address,
+                lp_address,
+                x_address: pool.x_address,
+                y_address: pool.y_address,
+                input_fee_rate: pool.input_fee_rate,
+                x_share: pool.x_share,
+            });
+        }
+
+        fn find_pool(&self, pool_address: ComponentAddress) -> Option<FactoryPool> {
+            let index = *self.pool_indices.get(&pool_address)?;
+            self.pools.get(&index).map(|pool| pool.clone())
+        }
+    }
+}
+
+/// A pool instantiated via the factory.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct FactoryPool {
+    pub index: u64,
+    pub pool_address: ComponentAddress,
+    pub lp_address: ResourceAddress,
+    pub x_address: ResourceAddress,
+    pub y_address: ResourceAddress,
+    /// Fee tier, i.e. the input fee rate at instantiation. The pool's current input fee rate can differ after a
+    /// fee rate change by its guardian (see `Pool::input_fee_rate`), while the fee tier stays the pool's key.
+    pub input_fee_rate: Decimal,
+    pub x_share: Decimal,
+}
+
+/// Identifies a pool by its sorted token pair, fee tier (input fee rate at instantiation) and weight (share of token X).
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FactoryPoolKey {
+    pub x_address: ResourceAddress,
+    pub y_address: ResourceAddress,
+    pub input_fee_rate: Decimal,
+    pub x_share: Decimal,
+}
+
+impl FactoryPoolKey {
+    pub fn new(
+        a_address: ResourceAddress,
+        b_address: ResourceAddress,
+        input_fee_rate: Decimal,
+        a_share: Decimal,
+    ) -> Self {
+        let (x_address, y_address) = check_and_sort_addresses(a_address, b_address);
+        let x_share = if x_address == a_address {
+            a_share
+        } else {
+            Decimal::ONE - a_share
+        };
+        Self {
+            x_address,
+            y_address,
+            input_fee_rate,
+            x_share,
+        }
+    }
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct FactoryPoolEvent {
+    index: u64,
+    pool_address: ComponentAddress,
+    lp_address: ResourceAddress,
+    x_address: ResourceAddress,
+    y_address: ResourceAddress,
+    input_fee_rate: Decimal,
+    x_share: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct CanonicalPoolEvent {
+    x_address: ResourceAddress,
+    y_address: ResourceAddress,
+    pool_address: ComponentAddress,
+}
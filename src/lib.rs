@@ -1,4 +1,8 @@
 pub mod constants;
+pub mod factory;
 pub mod pool;
 pub mod pool_math;
+pub mod protocol_fee;
+pub mod registry_config;
+pub mod router;
 pub mod utils;
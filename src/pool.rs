@@ -2,26 +2,52 @@ use scrypto::prelude::*;
 
 use crate::constants::*;
 use crate::pool_math::*;
+use crate::protocol_fee::*;
+use crate::registry_config::*;
 use crate::utils::*;
 use common::math::DivisibilityRounding;
 use common::metadata::{address_from_metadata, assert_component_packages_are_approved};
 use common::pools::*;
 use common::time::*;
+use ociswap_pool_events::{
+    FeeRatesCancelEvent, FeeRatesProposalEvent, FeeRatesUpdateEvent, FlashLoanEvent,
+    InstantiateEvent, MigrationEvent, PauseEvent, RegistryFailoverEvent, RegistrySyncDeferEvent,
+    SwapEvent,
+};
 use ociswap_pool_hooks::*;
 use oracle::{AccumulatedObservation, ObservationInterval, Oracle};
 
 #[blueprint]
-#[events(InstantiateEvent, SwapEvent, FlashLoanEvent)]
+#[events(
+    InstantiateEvent,
+    SwapEvent,
+    FlashLoanEvent,
+    PauseEvent,
+    FeeRatesProposalEvent,
+    FeeRatesCancelEvent,
+    FeeRatesUpdateEvent,
+    RegistrySyncDeferEvent,
+    RegistryFailoverEvent,
+    MigrationEvent
+)]
 mod pool {
     enable_method_auth! {
         roles {
             blueprint => updatable_by: [];
+            guardian => updatable_by: [];
         },
         methods {
             swap                        => PUBLIC;
+            swap_with_credential        => PUBLIC;
+            swap_with_referrer          => PUBLIC;
+            quote                       => PUBLIC;
             add_liquidity               => PUBLIC;
+            add_liquidity_with_credential => PUBLIC;
             remove_liquidity            => PUBLIC;
+            remove_liquidity_with_credential => PUBLIC;
             removable_liquidity         => PUBLIC;
+            migrate                     => PUBLIC;
+            migrate_with_credential     => PUBLIC;
             x_share                     => PUBLIC;
             y_share                     => PUBLIC;
             x_address                   => PUBLIC;
@@ -41,7 +67,16 @@ mod pool {
             liquidity_pool              => PUBLIC;
             registry                    => PUBLIC;
             sync_registry               => PUBLIC;
+            retry_registry_sync         => PUBLIC;
+            defer_registry_sync         => restrict_to: [guardian];
+            fail_over_registry          => restrict_to: [guardian];
+            fail_over_stale_registry    => PUBLIC;
+            registry_fallback           => PUBLIC;
+            registry_sync_deferred_until => PUBLIC;
+            registry_sync_grace_period  => PUBLIC;
             next_sync_time              => PUBLIC;
+            current_config              => PUBLIC;
+            protocol_fee_mode           => PUBLIC;
             hook                        => PUBLIC;
             observations_limit          => PUBLIC;
             observation                 => PUBLIC;
@@ -49,6 +84,15 @@ mod pool {
             observations_stored         => PUBLIC;
             oldest_observation_at       => PUBLIC;
             last_observation_index      => PUBLIC;
+            state                       => PUBLIC;
+            paused                      => PUBLIC;
+            guardian                    => PUBLIC;
+            pause                       => restrict_to: [guardian];
+            unpause                     => restrict_to: [guardian];
+            pending_fee_rates           => PUBLIC;
+            propose_fee_rates           => restrict_to: [guardian];
+            cancel_fee_rates            => restrict_to: [guardian];
+            apply_fee_rates             => PUBLIC;
             set_liquidity_pool_meta     => restrict_to: [blueprint];
             execute_after_instantiate   => restrict_to: [blueprint]; // called within instantiate on the newly created component
         }
@@ -65,16 +109,23 @@ mod pool {
         ratio: Decimal,
         x_protocol_fee: Vault,
         y_protocol_fee: Vault,
-        fee_protocol_share: Decimal,
         liquidity_pool: Global<TwoResourcePool>,
         lp_manager: ResourceManager,
         flash_manager: NonFungibleResourceManager,
         flash_loan_fee_rate: Decimal,
         registry: Global<AnyComponent>,
-        next_sync_time: u64,
+        registry_fallback: Option<Global<AnyComponent>>,
+        registry_config: RegistryConfig,
+        registry_sync_deferred_until: Option<u64>,
+        registry_sync_grace_period: u64,
+        protocol_fee_mode: ProtocolFeeMode,
+        protocol_fee_checkpoint: Option<(PreciseDecimal, PreciseDecimal)>,
         hooks: HashMap<(PackageAddress, String), Global<AnyComponent>>,
         hook_calls: HookCalls,
         hook_badges: HashMap<ComponentAddress, Vault>,
+        guardian: Option<ComponentAddress>,
+        paused: bool,
+        pending_fee_rates: Option<FeeRatesProposal>,
 
         oracle: Oracle,
     }
@@ -125,8 +176,19 @@ mod pool {
 
             let registry_address: ComponentAddress =
                 address_from_metadata("registry").expect("Failed to get registry from metadata");
+            let registry_fallback_address: Option<ComponentAddress> =
+                address_from_metadata("registry_fallback");
+            let registry_sync_grace_period =
+                registry_sync_grace_period_from_metadata(registry_fallback_address.is_some());
+            let protocol_fee_mode = ProtocolFeeMode::from_package_metadata();
             let dapp_definition: ComponentAddress = address_from_metadata("dapp_definition")
                 .expect("Failed to get dapp definition from metadata");
+            // The guardian is optional, pools without guardian can never be paused.
+            let guardian: Option<ComponentAddress> = address_from_metadata("guardian");
+            let guardian_rule = match guardian {
+                Some(guardian) => rule!(require(global_caller(guardian))),
+                None => rule!(deny_all),
+            };
 
             assert_component_packages_are_approved(
                 "hook_packages",
@@ -233,15 +295,22 @@ mod pool {
                 ratio,
                 x_protocol_fee: Vault::new(x_address),
                 y_protocol_fee: Vault::new(y_address),
-                fee_protocol_share: Decimal::ZERO,
                 liquidity_pool,
                 lp_manager,
                 flash_manager,
                 registry: registry_address.into(),
-                next_sync_time: 0,
+                registry_fallback: registry_fallback_address.map(|address| address.into()),
+                registry_config: RegistryConfig::legacy(Decimal::ZERO, 0),
+                registry_sync_deferred_until: None,
+                registry_sync_grace_period,
+                protocol_fee_mode,
+                protocol_fee_checkpoint: None,
                 hook_calls,
                 hook_badges: hook_badges_vault,
                 hooks,
+                guardian,
+                paused: false,
+                pending_fee_rates: None,
 
                 oracle: Oracle::new(u16::MAX),
             })
@@ -249,9 +318,16 @@ mod pool {
             .prepare_to_globalize(OwnerRole::None)
             .roles(roles!(
                 blueprint => rule!(require(global_caller(Pool::blueprint_id())));
+                guardian => guardian_rule;
             ))
             .with_address(address_reservation)
             .metadata(metadata! {
+                roles {
+                    metadata_setter => rule!(require(global_caller(pool_address)));
+                    metadata_setter_updater => rule!(deny_all);
+                    metadata_locker => rule!(deny_all);
+                    metadata_locker_updater => rule!(deny_all);
+                },
                 init {
                     "pool_address" => pool_address, locked;
                     "name" => pool_name, locked;
@@ -261,9 +337,9 @@ mod pool {
                     "x_address" => x_address, locked;
                     "y_address" => y_address, locked;
                     "x_share" => x_share, locked;
-                    "input_fee_rate" => input_fee_rate, locked;
-                    "flash_loan_fee_rate" => flash_loan_fee_rate, locked;
-                    "registry" => registry_address, locked;
+                    "input_fee_rate" => input_fee_rate, updatable;
+                    "flash_loan_fee_rate" => flash_loan_fee_rate, updatable;
+                    "registry" => registry_address, updatable;
                     "hooks" => hooks_vec.clone(), locked;
                     "dapp_definition" => dapp_definition, locked;
                 }
@@ -299,9 +375,12 @@ mod pool {
                 flash_loan_address: flash_manager.address(),
                 flash_loan_fee_rate,
                 registry_address,
+                registry_fallback_address,
+                protocol_fee_mode,
                 liquidity_pool_address: liquidity_pool.address(),
                 hooks: hooks_vec,
                 dapp_definition,
+                guardian,
             });
 
             (pool, lp_address)
@@ -367,9 +446,77 @@ mod pool {
             a_bucket: Bucket,
             b_bucket: Bucket,
         ) -> (Bucket, Option<Bucket>) {
+            self.add_liquidity_internal(a_bucket, b_bucket, None)
+        }
+
+        /// Add liquidity to the Pool by providing both tokens and a credential, which is forwarded to the
+        /// `BeforeAddLiquidity` hooks (e.g. for permissioned pools).
+        ///
+        /// # Arguments
+        /// * `a_bucket`: Bucket with one of the two tokens of the pool.
+        /// * `b_bucket`: Bucket with one of the two tokens of the pool.
+        /// * `credential`: Proof of a single non fungible identifying the liquidity provider.
+        ///
+        /// # Returns
+        /// Returns a tuple consisting of:
+        /// * LP tokens you get for providing liquidity.
+        /// * Some(Bucket)` if we have a remainder or else None.
+        pub fn add_liquidity_with_credential(
+            &mut self,
+            a_bucket: Bucket,
+            b_bucket: Bucket,
+            credential: Proof,
+        ) -> (Bucket, Option<Bucket>) {
+            let credential = credential_id(credential);
+            self.add_liquidity_internal(a_bucket, b_bucket, Some(credential))
+        }
+
+        fn add_liquidity_internal(
+            &mut self,
+            a_bucket: Bucket,
+            b_bucket: Bucket,
+            credential: Option<NonFungibleGlobalId>,
+        ) -> (Bucket, Option<Bucket>) {
+            // Syncing before checking the pause flag picks up a pause or unpause issued by the registry.
+            self.sync_registry_after_grace_period();
+            self.assert_not_paused();
+
+            if !self.hook_calls.before_add_liquidity.1.is_empty() {
+                let (x_amount, y_amount) = if a_bucket.resource_address() == self.x_address {
+                    (a_bucket.amount(), b_bucket.amount())
+                } else {
+                    (b_bucket.amount(), a_bucket.amount())
+                };
+                let before_add_liquidity_state = BeforeAddLiquidityState {
+                    pool_address: self.pool_address,
+                    x_amount,
+                    y_amount,
+                    lp_total_supply: self.lp_total_supply(),
+                    credential,
+                };
+                self.execute_hooks(HookCall::BeforeAddLiquidity, (before_add_liquidity_state,));
+            }
+
             let (lp_token, remainder) = self
                 .liquidity_pool
                 .contribute((a_bucket.as_fungible(), b_bucket.as_fungible()));
+
+            // The first liquidity sets the reference for the invariant growth collected as protocol fees.
+            if self.protocol_fee_mode == ProtocolFeeMode::LpTokens
+                && lp_token.amount() == self.lp_total_supply()
+            {
+                self.checkpoint_protocol_fee();
+            }
+
+            if !self.hook_calls.after_add_liquidity.1.is_empty() {
+                let after_add_liquidity_state = AfterAddLiquidityState {
+                    pool_address: self.pool_address,
+                    lp_amount: lp_token.amount(),
+                    lp_total_supply: self.lp_total_supply(),
+                };
+                self.execute_hooks(HookCall::AfterAddLiquidity, (after_add_liquidity_state,));
+            }
+
             (lp_token.into(), remainder.map(|b| b.into()))
         }
 
@@ -385,7 +532,62 @@ mod pool {
         /// * A Bucket which contains tokens A from the LP tokens
         /// * A Bucket which contains tokens B from the LP tokens.
         pub fn remove_liquidity(&mut self, lp_token: Bucket) -> (Bucket, Bucket) {
+            self.remove_liquidity_internal(lp_token, None)
+        }
+
+        /// Remove liquidity from the liquidity pool and provide a credential, which is forwarded to the
+        /// `BeforeRemoveLiquidity` hooks (e.g. for permissioned pools).
+        ///
+        /// # Arguments
+        /// * `lp_token`: Bucket with the LP tokens generated after adding liquidity to this Pool.
+        /// * `credential`: Proof of a single non fungible identifying the liquidity provider.
+        ///
+        /// # Returns
+        /// Returns a tuple consisting of:
+        /// * A Bucket which contains tokens A from the LP tokens
+        /// * A Bucket which contains tokens B from the LP tokens.
+        pub fn remove_liquidity_with_credential(
+            &mut self,
+            lp_token: Bucket,
+            credential: Proof,
+        ) -> (Bucket, Bucket) {
+            let credential = credential_id(credential);
+            self.remove_liquidity_internal(lp_token, Some(credential))
+        }
+
+        fn remove_liquidity_internal(
+            &mut self,
+            lp_token: Bucket,
+            credential: Option<NonFungibleGlobalId>,
+        ) -> (Bucket, Bucket) {
+            if !self.hook_calls.before_remove_liquidity.1.is_empty() {
+                let before_remove_liquidity_state = BeforeRemoveLiquidityState {
+                    pool_address: self.pool_address,
+                    lp_amount: lp_token.amount(),
+                    lp_total_supply: self.lp_total_supply(),
+                    credential,
+                };
+                self.execute_hooks(
+                    HookCall::BeforeRemoveLiquidity,
+                    (before_remove_liquidity_state,),
+                );
+            }
+
+            let lp_amount = lp_token.amount();
             let (a_bucket, b_bucket) = self.liquidity_pool.redeem(lp_token.as_fungible());
+
+            if !self.hook_calls.after_remove_liquidity.1.is_empty() {
+                let after_remove_liquidity_state = AfterRemoveLiquidityState {
+                    pool_address: self.pool_address,
+                    lp_amount,
+                    lp_total_supply: self.lp_total_supply(),
+                };
+                self.execute_hooks(
+                    HookCall::AfterRemoveLiquidity,
+                    (after_remove_liquidity_state,),
+                );
+            }
+
             (a_bucket.into(), b_bucket.into())
         }
 
@@ -403,6 +605,94 @@ mod pool {
             self.liquidity_pool.get_redemption_value(lp_amount)
         }
 
+        /// Migrates liquidity to a successor pool by redeeming the LP tokens of this pool and contributing the
+        /// redeemed tokens into the target pool in the same call.
+        ///
+        /// The target pool has to be instantiated from a package approved in the `successor_packages` metadata of
+        /// this package and has to provide the same `add_liquidity` interface. The remove liquidity hooks of this
+        /// pool and the add liquidity hooks of the target pool are executed as usual.
+        ///
+        /// # Arguments
+        /// * `lp_token`: Bucket with the LP tokens of this pool.
+        /// * `target_pool`: Address of the successor pool to contribute the redeemed tokens into.
+        ///
+        /// # Returns
+        /// Returns a tuple consisting of:
+        /// * LP tokens of the target pool.
+        /// * `Some(Bucket)` if the target pool returned a remainder or else None.
+        pub fn migrate(
+            &mut self,
+            lp_token: Bucket,
+            target_pool: ComponentAddress,
+        ) -> (Bucket, Option<Bucket>) {
+            self.migrate_internal(lp_token, target_pool, None)
+        }
+
+        /// Migrates liquidity like `migrate`, additionally forwarding a credential to the `BeforeRemoveLiquidity`
+        /// hooks of this pool (e.g. for permissioned pools). The target pool is called without credential.
+        ///
+        /// # Arguments
+        /// * `lp_token`: Bucket with the LP tokens of this pool.
+        /// * `target_pool`: Address of the successor pool to contribute the redeemed tokens into.
+        /// * `credential`: Proof of a single non fungible identifying the liquidity provider.
+        ///
+        /// # Returns
+        /// Returns a tuple consisting of:
+        /// * LP tokens of the target pool.
+        /// * `Some(Bucket)` if the target pool returned a remainder or else None.
+        pub fn migrate_with_credential(
+            &mut self,
+            lp_token: Bucket,
+            target_pool: ComponentAddress,
+            credential: Proof,
+        ) -> (Bucket, Option<Bucket>) {
+            let credential = credential_id(credential);
+            self.migrate_internal(lp_token, target_pool, Some(credential))
+        }
+
+        fn migrate_internal(
+            &mut self,
+            lp_token: Bucket,
+            target_pool: ComponentAddress,
+            credential: Option<NonFungibleGlobalId>,
+        ) -> (Bucket, Option<Bucket>) {
+            assert!(
+                lp_token.resource_address() == self.lp_address(),
+                "[Migrate]: Bucket should contain the LP tokens of this pool."
+            );
+            assert!(
+                target_pool != self.pool_address,
+                "[Migrate]: Target pool should not be this pool."
+            );
+            assert_component_packages_are_approved("successor_packages", vec![target_pool]);
+
+            let lp_amount = lp_token.amount();
+            let (x_bucket, y_bucket) = self.remove_liquidity_internal(lp_token, credential);
+            let (x_amount, y_amount) = (x_bucket.amount(), y_bucket.amount());
+
+            let (target_lp_bucket, remainder) =
+                Global::<AnyComponent>::from(target_pool)
+                    .call::<(Bucket, Bucket), (Bucket, Option<Bucket>)>(
+                        "add_liquidity",
+                        &(x_bucket, y_bucket),
+                    );
+
+            Runtime::emit_event(MigrationEvent {
+                target_pool_address: target_pool,
+                lp_amount,
+                x_amount,
+                y_amount,
+                target_lp_address: target_lp_bucket.resource_address(),
+                target_lp_amount: target_lp_bucket.amount(),
+                remainder_address: remainder.as_ref().map(|bucket| bucket.resource_address()),
+                remainder_amount: remainder
+                    .as_ref()
+                    .map_or(Decimal::ZERO, |bucket| bucket.amount()),
+            });
+
+            (target_lp_bucket, remainder)
+        }
+
         /// Executes a token swap within the liquidity pool.
         ///
         /// This method handles the entire lifecycle of a token swap, including pre-swap hooks, fee calculations,
@@ -414,9 +704,94 @@ mod pool {
         ///
         /// # Returns
         /// * A `Bucket` containing the other token after the swap.
-        pub fn swap(&mut self, mut input_bucket: Bucket) -> Bucket {
+        pub fn swap(&mut self, input_bucket: Bucket) -> Bucket {
+            self.swap_internal(input_bucket, None, None)
+        }
+
+        /// Executes a token swap like `swap`, additionally forwarding a credential to the `BeforeSwap` hooks.
+        ///
+        /// Hooks cannot see the caller of the pool, so permissioned pools require the caller to identify
+        /// itself via a non fungible credential (e.g. a KYC badge). The proof is validated by the engine and
+        /// reduced to its `NonFungibleGlobalId`; deciding which credentials are accepted is up to the hooks.
+        ///
+        /// # Arguments
+        /// * `input_bucket`: A `Bucket` containing one of the two tokens in the pool to be swapped for the other token.
+        /// * `credential`: Proof of a single non fungible identifying the trader.
+        ///
+        /// # Returns
+        /// * A `Bucket` containing the other token after the swap.
+        pub fn swap_with_credential(&mut self, input_bucket: Bucket, credential: Proof) -> Bucket {
+            let credential = credential_id(credential);
+            self.swap_internal(input_bucket, Some(credential), None)
+        }
+
+        /// Executes a token swap like `swap`, additionally forwarding the referrer which routed the swap
+        /// (e.g. a frontend) to the `AfterSwap` hooks, allowing them to reward the referrer.
+        ///
+        /// # Arguments
+        /// * `input_bucket`: A `Bucket` containing one of the two tokens in the pool to be swapped for the other token.
+        /// * `referrer`: The component (usually an account) referring the swap.
+        ///
+        /// # Returns
+        /// * A `Bucket` containing the other token after the swap.
+        pub fn swap_with_referrer(
+            &mut self,
+            input_bucket: Bucket,
+            referrer: ComponentAddress,
+        ) -> Bucket {
+            self.swap_internal(input_bucket, None, Some(referrer))
+        }
+
+        /// Quotes the output amount of a swap with the pool's current reserves and input fee rate, without executing it.
+        ///
+        /// The quote does not account for hooks, which can adjust the input fee rate or the swapped amounts during `swap`.
+        ///
+        /// # Arguments
+        /// * `input_address`: The address of the token to be swapped, either token X or token Y.
+        /// * `input_amount`: The gross amount of input tokens, including the input fee.
+        ///
+        /// # Returns
+        /// * The amount of the other token `swap` would return.
+        pub fn quote(&self, input_address: ResourceAddress, input_amount: Decimal) -> Decimal {
+            assert!(
+                input_address == self.x_address || input_address == self.y_address,
+                "Input token is not part of the pool!"
+            );
+            let swap_type = self.swap_type(input_address);
+            let (x_vault, y_vault) = self.vault_amounts();
+            assert!(x_vault > Decimal::ZERO, "X token reserves are empty!");
+            assert!(y_vault > Decimal::ZERO, "Y token reserves are empty!");
+
+            let (input_amount_net, _, _) = input_amount_net(
+                input_amount,
+                self.input_fee_rate,
+                self.swap_fee_protocol_share(),
+                self.input_divisibility(swap_type),
+            );
+            let (input_vault_amount, output_vault_amount) = match swap_type {
+                SwapType::BuyX => (y_vault, x_vault),
+                SwapType::SellX => (x_vault, y_vault),
+            };
+            output_amount(
+                input_vault_amount,
+                output_vault_amount,
+                input_amount_net,
+                self.ratio,
+                swap_type,
+                self.output_divisibility(swap_type),
+            )
+        }
+
+        fn swap_internal(
+            &mut self,
+            mut input_bucket: Bucket,
+            credential: Option<NonFungibleGlobalId>,
+            referrer: Option<ComponentAddress>,
+        ) -> Bucket {
             // Synchronize the pool's state with the registry to collect protocol fees and update protocol fee share.
-            self.sync_registry();
+            // Syncing before checking the pause flag picks up a pause or unpause issued by the registry.
+            self.sync_registry_after_grace_period();
+            self.assert_not_paused();
 
             // Determine the type of swap and retrieve the current vault amounts.
             let swap_type = self.swap_type(input_bucket.resource_address());
@@ -429,17 +804,21 @@ mod pool {
             let (input_address, input_gross_amount) =
                 (input_bucket.resource_address(), input_bucket.amount());
 
+            // Hooks can override the input fee rate for this swap only, the base rate is governed by the guardian.
+            let mut input_fee_rate = self.input_fee_rate;
+
             if !self.hook_calls.before_swap.1.is_empty() {
                 // Initialize the state for BeforeSwap hooks.
                 let mut before_swap_state: BeforeSwapState = BeforeSwapState {
                     pool_address: self.pool_address,
                     swap_type,
                     price_sqrt: price_sqrt(x_vault, y_vault, self.ratio).expect("Invalid price"),
-                    input_fee_rate: self.input_fee_rate,
-                    fee_protocol_share: self.fee_protocol_share,
+                    input_fee_rate,
+                    fee_protocol_share: self.swap_fee_protocol_share(),
+                    credential,
                 };
 
-                // Execute BeforeSwap hooks, validate the output, and adjust the fee rate.
+                // Execute BeforeSwap hooks, validate the output, and adjust the fee rate of this swap.
                 (before_swap_state, input_bucket) =
                     self.execute_hooks(HookCall::BeforeSwap, (before_swap_state, input_bucket));
                 assert_hooks_bucket_output_and_address(
@@ -448,14 +827,15 @@ mod pool {
                     &input_bucket,
                     "BeforeSwap",
                 );
-                self.set_input_fee_rate(before_swap_state.input_fee_rate);
+                assert_input_fee_rate_is_valid(before_swap_state.input_fee_rate);
+                input_fee_rate = before_swap_state.input_fee_rate;
             };
 
             // Calculate the net input amount and fees.
             let (input_amount_net, input_fee_lp, input_fee_protocol) = input_amount_net(
                 input_bucket.amount(),
-                self.input_fee_rate,
-                self.fee_protocol_share,
+                input_fee_rate,
+                self.swap_fee_protocol_share(),
                 self.input_divisibility(swap_type),
             );
 
@@ -488,17 +868,19 @@ mod pool {
                     pool_address: self.pool_address,
                     swap_type,
                     price_sqrt: price_sqrt_after_swap,
-                    input_fee_rate: self.input_fee_rate,
-                    fee_protocol_share: self.fee_protocol_share,
+                    input_fee_rate,
+                    fee_protocol_share: self.swap_fee_protocol_share(),
                     input_address,
                     input_amount: input_amount_net,
                     output_address,
                     output_amount: output_bucket.amount(),
                     input_fee_lp,
                     input_fee_protocol,
+                    referrer,
                 };
 
-                // Execute AfterSwap hooks, validate the output, and adjust the fee rate.
+                // Execute AfterSwap hooks and validate the output. The fees of this swap are already charged, so a
+                // changed fee rate returned by AfterSwap hooks is proposed as new base rate, subject to the timelock.
                 (after_swap_state, output_bucket) =
                     self.execute_hooks(HookCall::AfterSwap, (after_swap_state, output_bucket));
                 assert_hooks_bucket_output_and_address(
@@ -507,7 +889,10 @@ mod pool {
                     &output_bucket,
                     "AfterSwap",
                 );
-                self.set_input_fee_rate(after_swap_state.input_fee_rate);
+                self.propose_input_fee_rate_from_hooks(
+                    after_swap_state.input_fee_rate,
+                    input_fee_rate,
+                );
             };
 
             // Update the oracle with the new price square root.
@@ -545,12 +930,29 @@ mod pool {
             address: ResourceAddress,
             loan_amount: Decimal,
         ) -> (Bucket, Bucket) {
+            // Syncing before checking the pause flag picks up a pause or unpause issued by the registry.
+            self.sync_registry_after_grace_period();
+            self.assert_not_paused();
+
             let divisibility = ResourceManager::from_address(address)
                 .resource_type()
                 .divisibility()
                 .unwrap();
             let loan_amount = loan_amount.floor_to(divisibility);
 
+            if let Some(max_fraction) = self.registry_config.flash_loan_max_fraction {
+                let reserve = self
+                    .liquidity_pool
+                    .get_vault_amounts()
+                    .get(&address)
+                    .copied()
+                    .unwrap_or_default();
+                assert!(
+                    loan_amount <= reserve * max_fraction,
+                    "Flash loan exceeds the maximum loan size!"
+                );
+            }
+
             // Calculate the loan fee and add it to the borrowed amount to determine the total amount due.
             let fee = (PreciseDecimal::from(loan_amount) * self.flash_loan_fee_rate)
                 .ceil_to(divisibility);
@@ -604,11 +1006,14 @@ mod pool {
                 "Insufficient repayment given for your loan!"
             );
 
-            // Separate the fee from the repayment amount and deposit it as protocol fees.
-            self.deposit_protocol_fees(loan_repayment.take(terms.fee));
+            // Separate the protocol's share of the fee from the repayment amount and deposit it as protocol fees.
+            let divisibility = self.input_divisibility(self.swap_type(terms.address));
+            let protocol_fee = (terms.fee * self.registry_config.flash_loan_fee_protocol_share)
+                .floor_to(divisibility);
+            self.deposit_protocol_fees(loan_repayment.take(protocol_fee));
 
-            // Calculate the principal amount to be returned to the appropriate vault.
-            let loan_amount = terms.due_amount - terms.fee;
+            // Calculate the principal amount plus the LPs' share of the fee to be returned to the appropriate vault.
+            let loan_amount = terms.due_amount - protocol_fee;
 
             // Return the principal amount to the correct vault based on the token address.
             self.deposit(loan_repayment.take(loan_amount));
@@ -619,6 +1024,102 @@ mod pool {
             loan_repayment
         }
 
+        /// Pauses swaps, flash loans and adding liquidity. Only callable by the guardian.
+        ///
+        /// Removing liquidity and repaying flash loans stay possible, so that liquidity providers can always exit.
+        pub fn pause(&mut self) {
+            self.set_paused(true);
+        }
+
+        /// Resumes swaps, flash loans and adding liquidity. Only callable by the guardian.
+        pub fn unpause(&mut self) {
+            self.set_paused(false);
+        }
+
+        /// Proposes new input and flash loan fee rates. Only callable by the guardian.
+        ///
+        /// The proposed rates can be applied via `apply_fee_rates` after `FEE_RATES_UPDATE_TIMELOCK` has passed,
+        /// giving liquidity providers and traders time to react. A new proposal replaces any pending one.
+        ///
+        /// # Arguments
+        /// * `input_fee_rate` - The new input fee rate, between zero and `INPUT_FEE_RATE_MAX`.
+        /// * `flash_loan_fee_rate` - The new flash loan fee rate, between zero and `FLASH_LOAN_FEE_RATE_MAX`.
+        ///
+        /// # Returns
+        /// * `u64` - The Unix timestamp in seconds from which the proposal can be applied.
+        ///
+        /// # Panics
+        /// * If `input_fee_rate` or `flash_loan_fee_rate` are not within valid ranges.
+        pub fn propose_fee_rates(
+            &mut self,
+            input_fee_rate: Decimal,
+            flash_loan_fee_rate: Decimal,
+        ) -> u64 {
+            assert_input_fee_rate_is_valid(input_fee_rate);
+            assert_flash_loan_fee_rate_is_valid(flash_loan_fee_rate);
+            self.set_pending_fee_rates(input_fee_rate, flash_loan_fee_rate)
+        }
+
+        /// Stores a new fee rates proposal, replacing any pending one, and emits a `FeeRatesProposalEvent`.
+        ///
+        /// # Returns
+        /// * `u64` - The Unix timestamp in seconds from which the proposal can be applied.
+        fn set_pending_fee_rates(
+            &mut self,
+            input_fee_rate: Decimal,
+            flash_loan_fee_rate: Decimal,
+        ) -> u64 {
+            let effective_at = Clock::time_in_seconds() + FEE_RATES_UPDATE_TIMELOCK;
+            self.pending_fee_rates = Some(FeeRatesProposal {
+                input_fee_rate,
+                flash_loan_fee_rate,
+                effective_at,
+            });
+            Runtime::emit_event(FeeRatesProposalEvent {
+                input_fee_rate,
+                flash_loan_fee_rate,
+                effective_at,
+            });
+            effective_at
+        }
+
+        /// Cancels the pending fee rates proposal. Only callable by the guardian.
+        ///
+        /// # Panics
+        /// * If no fee rates proposal is pending.
+        pub fn cancel_fee_rates(&mut self) {
+            let proposal = self
+                .pending_fee_rates
+                .take()
+                .expect("No fee rates proposal pending!");
+            Runtime::emit_event(FeeRatesCancelEvent {
+                input_fee_rate: proposal.input_fee_rate,
+                flash_loan_fee_rate: proposal.flash_loan_fee_rate,
+            });
+        }
+
+        /// Applies the pending fee rates proposal once its timelock has passed. Callable by anyone.
+        ///
+        /// # Panics
+        /// * If no fee rates proposal is pending or its timelock has not passed yet.
+        pub fn apply_fee_rates(&mut self) {
+            let proposal = self
+                .pending_fee_rates
+                .take()
+                .expect("No fee rates proposal pending!");
+            assert!(
+                Clock::time_in_seconds() >= proposal.effective_at,
+                "Fee rates proposal is still timelocked!"
+            );
+
+            self.set_input_fee_rate(proposal.input_fee_rate);
+            self.set_flash_loan_fee_rate(proposal.flash_loan_fee_rate);
+            Runtime::emit_event(FeeRatesUpdateEvent {
+                input_fee_rate: proposal.input_fee_rate,
+                flash_loan_fee_rate: proposal.flash_loan_fee_rate,
+            });
+        }
+
         /// Synchronizes the pool's state with the registry to potentially update the protocol fees.
         ///
         /// This method is crucial for maintaining the pool's alignment with the broader protocol's fee structure,
@@ -627,26 +1128,156 @@ mod pool {
         ///
         /// If the current time is less than `next_sync_time`, the function exits early to throttle the frequency of updates,
         /// which helps in reducing unnecessary computations and state changes.
+        ///
+        /// The registry responds either with a versioned `RegistrySyncResponse` or, for older registries, with the
+        /// tuple `(fee_protocol_share, next_sync_time)`, which is converted via `RegistryConfig::legacy`.
+        ///
+        /// While the guardian defers the sync (see `defer_registry_sync`), the function exits early as well and the
+        /// protocol fees stay in the pool until a sync succeeds.
         pub fn sync_registry(&mut self) {
-            if Clock::time_in_seconds() < self.next_sync_time {
+            let now = Clock::time_in_seconds();
+            if now < self.registry_config.next_sync_time {
+                return;
+            }
+            if self
+                .registry_sync_deferred_until
+                .map_or(false, |deferred_until| now < deferred_until)
+            {
                 return;
             }
+            self.registry_sync_deferred_until = None;
 
             // Calls the `sync` method on the registry component, passing the current pool address and the total protocol fees collected since the last sync.
-            let (fee_protocol_share, next_sync_time) =
-                self.registry
-                    .call::<(ComponentAddress, Bucket, Bucket), (Decimal, u64)>(
+            // In `LpTokens` mode the registry's `sync_lp_tokens` method additionally receives the LP tokens minted for the protocol.
+            let response = match self.protocol_fee_mode {
+                ProtocolFeeMode::Skim => self
+                    .registry
+                    .call::<(ComponentAddress, Bucket, Bucket), ScryptoValue>(
                         "sync",
                         &(
                             self.pool_address,
                             self.x_protocol_fee.take_all(),
                             self.y_protocol_fee.take_all(),
                         ),
-                    );
+                    ),
+                ProtocolFeeMode::LpTokens => {
+                    let lp_fee = self.mint_protocol_fee();
+                    self.registry
+                        .call::<(ComponentAddress, Bucket, Bucket, Bucket), ScryptoValue>(
+                            "sync_lp_tokens",
+                            &(
+                                self.pool_address,
+                                self.x_protocol_fee.take_all(),
+                                self.y_protocol_fee.take_all(),
+                                lp_fee,
+                            ),
+                        )
+                }
+            };
 
-            // Updates the pool's state with the new protocol fee share and the next allowed sync time.
-            self.set_fee_protocol_share(fee_protocol_share);
-            self.next_sync_time = next_sync_time;
+            // Updates the pool's state with the new configuration, including the next allowed sync time.
+            self.registry_config = RegistryConfig::from_sync_response(response);
+        }
+
+        /// Synchronizes with the registry during swaps, flash loans and adding liquidity once the grace period after
+        /// `next_sync_time` has passed.
+        ///
+        /// Within the grace period these operations continue with the last registry configuration, so that a broken
+        /// registry does not halt them immediately, even in pools without a guardian. Anyone can sync earlier via
+        /// `sync_registry` or `retry_registry_sync`. The grace period is read from the `registry_sync_grace_period`
+        /// metadata of the pool package at instantiation. It is required if a fallback registry is set and otherwise
+        /// zero if not set.
+        fn sync_registry_after_grace_period(&mut self) {
+            let sync_due_at = self
+                .registry_config
+                .next_sync_time
+                .saturating_add(self.registry_sync_grace_period);
+            if Clock::time_in_seconds() < sync_due_at {
+                return;
+            }
+            self.sync_registry();
+        }
+
+        /// Ends a deferral of the registry sync early and retries the sync. Callable by anyone.
+        ///
+        /// This allows resuming the collection of protocol fees as soon as the registry works again,
+        /// without waiting for the deferral to expire.
+        pub fn retry_registry_sync(&mut self) {
+            self.registry_sync_deferred_until = None;
+            self.sync_registry();
+        }
+
+        /// Defers the registry sync for the given number of seconds. Only callable by the guardian.
+        ///
+        /// If the registry is broken, every sync and therefore every swap, flash loan and liquidity addition fails.
+        /// During the deferral these continue with the last known registry configuration and the protocol fees are
+        /// kept in the pool.
+        ///
+        /// # Arguments
+        /// * `seconds` - The duration of the deferral, at most `REGISTRY_SYNC_DEFERRAL_MAX`.
+        ///
+        /// # Returns
+        /// * `u64` - The Unix timestamp in seconds until which the sync is deferred.
+        ///
+        /// # Panics
+        /// * If `seconds` exceeds `REGISTRY_SYNC_DEFERRAL_MAX`.
+        pub fn defer_registry_sync(&mut self, seconds: u64) -> u64 {
+            assert!(
+                seconds <= REGISTRY_SYNC_DEFERRAL_MAX,
+                "Registry sync can be deferred by at most {REGISTRY_SYNC_DEFERRAL_MAX} seconds!"
+            );
+            let deferred_until = Clock::time_in_seconds() + seconds;
+            self.registry_sync_deferred_until = Some(deferred_until);
+            Runtime::emit_event(RegistrySyncDeferEvent { deferred_until });
+            deferred_until
+        }
+
+        /// Switches the pool permanently to its fallback registry. Only callable by the guardian.
+        ///
+        /// The fallback registry is read from the `registry_fallback` metadata of the pool package at instantiation.
+        /// Any deferral is lifted, so that the pool syncs with the fallback registry on the next swap.
+        ///
+        /// # Panics
+        /// * If the pool has no fallback registry.
+        pub fn fail_over_registry(&mut self) {
+            let registry = self
+                .registry_fallback
+                .take()
+                .expect("No fallback registry available!");
+            self.registry = registry;
+            self.registry_sync_deferred_until = None;
+            self.registry_config.next_sync_time = 0;
+
+            let registry_address = registry.address();
+            Runtime::global_component().set_metadata("registry", registry_address);
+            Runtime::emit_event(RegistryFailoverEvent { registry_address });
+        }
+
+        /// Switches the pool permanently to its fallback registry if the registry has not been synced for
+        /// `REGISTRY_FAILOVER_STALENESS_MIN` (7 days) past `next_sync_time`. Callable by anyone.
+        ///
+        /// Once the grace period has passed every swap syncs with the registry, so a registry that is stale for that
+        /// long is either broken or the pool has not been used at all. This makes the fallback registry usable for
+        /// pools without a guardian or with an unresponsive one.
+        ///
+        /// Note that such pools cannot swap with a broken registry between the end of the grace period and the
+        /// failover. This is why the `registry_sync_grace_period` metadata is required for pools with a fallback
+        /// registry: a grace period of zero blocks swaps for the full `REGISTRY_FAILOVER_STALENESS_MIN`, while
+        /// `REGISTRY_SYNC_GRACE_PERIOD_MAX` keeps them working for the first day.
+        ///
+        /// # Panics
+        /// * If the registry is not stale yet.
+        /// * If the pool has no fallback registry.
+        pub fn fail_over_stale_registry(&mut self) {
+            let stale_at = self
+                .registry_config
+                .next_sync_time
+                .saturating_add(REGISTRY_FAILOVER_STALENESS_MIN);
+            assert!(
+                Clock::time_in_seconds() >= stale_at,
+                "Registry can only be failed over by anyone after it is stale for {REGISTRY_FAILOVER_STALENESS_MIN} seconds!"
+            );
+            self.fail_over_registry();
         }
 
         /// Sets the metadata for the LP tokens from the liquidity pool to be displayed in the Wallet.
@@ -818,8 +1449,11 @@ mod pool {
 
         /// Retrieves the current input fee rate of the pool
         ///
+        /// `BeforeSwap` hooks can override this rate for individual swaps, so like the `input_fee_rate` metadata it
+        /// reflects the base rate rather than the rate charged for a particular swap.
+        ///
         /// # Returns
-        /// * The current input fee rate of the pool
+        /// * The current base input fee rate of the pool
         pub fn input_fee_rate(&self) -> Decimal {
             self.input_fee_rate
         }
@@ -829,7 +1463,7 @@ mod pool {
         /// # Returns
         /// * The protocol's share of the fees as a `Decimal`.
         pub fn fee_protocol_share(&self) -> Decimal {
-            self.fee_protocol_share
+            self.registry_config.fee_protocol_share
         }
 
         /// Retrieve the flash loan fee rate of the pool.
@@ -858,6 +1492,38 @@ mod pool {
             self.registry
         }
 
+        /// Retrieves the fallback registry the guardian can switch the pool to via `fail_over_registry`.
+        ///
+        /// # Returns
+        /// * `Option<ComponentAddress>` - The fallback registry or `None` if none is available (anymore).
+        pub fn registry_fallback(&self) -> Option<ComponentAddress> {
+            self.registry_fallback.map(|registry| registry.address())
+        }
+
+        /// Returns until when the guardian deferred the registry sync.
+        ///
+        /// # Returns
+        /// * `Option<u64>` - The Unix timestamp in seconds or `None` if the sync is not deferred.
+        pub fn registry_sync_deferred_until(&self) -> Option<u64> {
+            self.registry_sync_deferred_until
+        }
+
+        /// Returns how long swaps continue with the last registry configuration after `next_sync_time`.
+        ///
+        /// # Returns
+        /// * `u64` - The grace period in seconds, read from the pool package metadata at instantiation.
+        pub fn registry_sync_grace_period(&self) -> u64 {
+            self.registry_sync_grace_period
+        }
+
+        /// Returns how the protocol's share of the fees is collected.
+        ///
+        /// # Returns
+        /// * `ProtocolFeeMode` - `Skim` or `LpTokens`, read from the pool package metadata at instantiation.
+        pub fn protocol_fee_mode(&self) -> ProtocolFeeMode {
+            self.protocol_fee_mode
+        }
+
         /// Retrieves the resource address of the transient token used within flash loans.
         ///
         /// # Returns
@@ -873,14 +1539,152 @@ mod pool {
         /// # Returns
         /// * `u64` - The Unix timestamp indicating when the next synchronization with the registry is scheduled.
         pub fn next_sync_time(&self) -> u64 {
-            self.next_sync_time
+            self.registry_config.next_sync_time
+        }
+
+        /// Returns the configuration received from the registry during the last sync.
+        ///
+        /// # Returns
+        /// * `RegistryConfig` - The current registry configuration of the pool.
+        pub fn current_config(&self) -> RegistryConfig {
+            self.registry_config.clone()
+        }
+
+        /// Returns whether the pool is currently paused by its guardian or the registry.
+        ///
+        /// # Returns
+        /// * `bool` - `true` if swaps, flash loans and adding liquidity are paused.
+        pub fn paused(&self) -> bool {
+            self.paused || self.registry_config.is_paused()
+        }
+
+        /// Returns the guardian of the pool, which is allowed to pause and unpause it.
+        ///
+        /// # Returns
+        /// * `Option<ComponentAddress>` - The guardian component or `None` if the pool has no guardian.
+        pub fn guardian(&self) -> Option<ComponentAddress> {
+            self.guardian
+        }
+
+        /// Returns the fee rates proposed by the guardian which have not been applied yet.
+        ///
+        /// # Returns
+        /// * `Option<FeeRatesProposal>` - The pending proposal or `None` if no proposal is pending.
+        pub fn pending_fee_rates(&self) -> Option<FeeRatesProposal> {
+            self.pending_fee_rates.clone()
         }
 
         // PRIVATE
 
-        /// Sets the input fee rate for the pool.
+        fn set_paused(&mut self, paused: bool) {
+            self.paused = paused;
+            Runtime::emit_event(PauseEvent { paused });
+        }
+
+        /// Returns the protocol's share of the input fee to be taken out of each swap, which is zero in `LpTokens` mode.
+        fn swap_fee_protocol_share(&self) -> Decimal {
+            match self.protocol_fee_mode {
+                ProtocolFeeMode::Skim => self.registry_config.fee_protocol_share,
+                ProtocolFeeMode::LpTokens => Decimal::ZERO,
+            }
+        }
+
+        /// Mints LP tokens worth the protocol's share of the invariant growth since the last checkpoint.
+        ///
+        /// The LP tokens are minted by withdrawing the protocol's fraction of the reserves and contributing it back
+        /// to the liquidity pool, which leaves the reserves unchanged while diluting the LPs by exactly that fraction.
+        ///
+        /// # Returns
+        /// A `Bucket` containing the minted LP tokens, empty if the invariant did not grow.
+        fn mint_protocol_fee(&mut self) -> Bucket {
+            let lp_address = self.lp_address();
+            let (x_vault, y_vault) = self.vault_amounts();
+            let lp_total_supply = self.lp_total_supply();
+            let checkpoint = match self.protocol_fee_checkpoint {
+                Some(checkpoint) if lp_total_supply > Decimal::ZERO => checkpoint,
+                _ => {
+                    self.checkpoint_protocol_fee();
+                    return Bucket::new(lp_address);
+                }
+            };
+
+            let growth = |vault: Decimal, vault_per_lp_last: PreciseDecimal| {
+                (PreciseDecimal::from(vault) / lp_total_supply / vault_per_lp_last)
+                    .checked_truncate(RoundingMode::ToZero)
+                    .unwrap()
+            };
+            let fraction = protocol_fee_fraction(
+                growth(x_vault, checkpoint.0),
+                growth(y_vault, checkpoint.1),
+                self.x_share,
+                self.registry_config.fee_protocol_share,
+            );
+
+            let mut lp_fee = Bucket::new(lp_address);
+            if fraction > Decimal::ZERO {
+                let x_bucket = self.withdraw(self.x_address, x_vault * fraction);
+                let y_bucket = self.withdraw(self.y_address, y_vault * fraction);
+                let (lp_token, remainder) = self
+                    .liquidity_pool
+                    .contribute((x_bucket.as_fungible(), y_bucket.as_fungible()));
+                if let Some(remainder) = remainder {
+                    self.deposit(remainder.into());
+                }
+                lp_fee.put(lp_token.into());
+            }
+
+            self.checkpoint_protocol_fee();
+            lp_fee
+        }
+
+        /// Stores the reserves per LP token as reference for the invariant growth collected as protocol fees.
+        fn checkpoint_protocol_fee(&mut self) {
+            let lp_total_supply = self.lp_total_supply();
+            if lp_total_supply == Decimal::ZERO {
+                self.protocol_fee_checkpoint = None;
+                return;
+            }
+            let (x_vault, y_vault) = self.vault_amounts();
+            self.protocol_fee_checkpoint = Some((
+                PreciseDecimal::from(x_vault) / lp_total_supply,
+                PreciseDecimal::from(y_vault) / lp_total_supply,
+            ));
+        }
+
+        fn assert_not_paused(&self) {
+            assert!(!self.paused(), "Pool is paused!");
+        }
+
+        /// Proposes the input fee rate returned by `AfterSwap` hooks as new base rate.
+        ///
+        /// Only rates changed by the hooks, i.e. differing from the rate charged for the swap, are proposed. The
+        /// proposal keeps the flash loan fee rate of a pending proposal, or else the current one, and is skipped if a
+        /// proposal with the same input fee rate is already pending, so repeated swaps don't restart the timelock.
+        ///
+        /// # Panics
+        /// Panics if the `input_fee_rate` is not valid as determined by `assert_input_fee_rate_is_valid`.
+        fn propose_input_fee_rate_from_hooks(
+            &mut self,
+            input_fee_rate: Decimal,
+            charged_input_fee_rate: Decimal,
+        ) {
+            assert_input_fee_rate_is_valid(input_fee_rate);
+            if input_fee_rate == charged_input_fee_rate {
+                return;
+            }
+            let flash_loan_fee_rate = match &self.pending_fee_rates {
+                Some(proposal) if proposal.input_fee_rate == input_fee_rate => return,
+                Some(proposal) => proposal.flash_loan_fee_rate,
+                None => self.flash_loan_fee_rate,
+            };
+            self.set_pending_fee_rates(input_fee_rate, flash_loan_fee_rate);
+        }
+
+        /// Sets the base input fee rate of the pool, applied via `apply_fee_rates`.
         ///
         /// Updates the pool's `input_fee_rate` after validating it, ensuring correct fee calculations for transactions.
+        /// If the rate changes, the `input_fee_rate` metadata is updated to reflect the base rate. It therefore shows
+        /// the base rate rather than the rate charged for a swap, which `BeforeSwap` hooks can override per swap.
         ///
         /// # Arguments
         /// * `input_fee_rate` - A `Decimal` representing the new fee rate to be applied.
@@ -890,18 +1694,23 @@ mod pool {
         /// Panics if the `input_fee_rate` is not valid as determined by `assert_input_fee_rate_is_valid`.
         fn set_input_fee_rate(&mut self, input_fee_rate: Decimal) {
             assert_input_fee_rate_is_valid(input_fee_rate);
-            self.input_fee_rate = input_fee_rate;
+            if input_fee_rate != self.input_fee_rate {
+                self.input_fee_rate = input_fee_rate;
+                Runtime::global_component().set_metadata("input_fee_rate", input_fee_rate);
+            }
         }
 
-        /// Sets the protocol fee share for the pool.
-        ///
-        /// This method updates the `fee_protocol_share` state of the pool. It ensures that the value is within the allowed range [0, `FEE_PROTOCOL_SHARE_MAX`].
-        /// The clamping is crucial to prevent setting a fee share that exceeds the maximum allowed limit, which could lead to incorrect fee calculations.
+        /// Sets the flash loan fee rate for the pool and updates the `flash_loan_fee_rate` metadata accordingly.
         ///
-        /// # Arguments
-        /// * `fee_protocol_share` - A `Decimal` representing the new protocol fee share to be set.
-        fn set_fee_protocol_share(&mut self, fee_protocol_share: Decimal) {
-            self.fee_protocol_share = fee_protocol_share.clamp(dec!(0), FEE_PROTOCOL_SHARE_MAX);
+        /// # Panics
+        /// Panics if the `flash_loan_fee_rate` is not valid as determined by `assert_flash_loan_fee_rate_is_valid`.
+        fn set_flash_loan_fee_rate(&mut self, flash_loan_fee_rate: Decimal) {
+            assert_flash_loan_fee_rate_is_valid(flash_loan_fee_rate);
+            if flash_loan_fee_rate != self.flash_loan_fee_rate {
+                self.flash_loan_fee_rate = flash_loan_fee_rate;
+                Runtime::global_component()
+                    .set_metadata("flash_loan_fee_rate", flash_loan_fee_rate);
+            }
         }
 
         /// Withdraws a specified amount of a resource from the liquidity pool.
@@ -1059,6 +1868,10 @@ mod pool {
                 HookCall::AfterInstantiate => &self.hook_calls.after_instantiate,
                 HookCall::BeforeSwap => &self.hook_calls.before_swap,
                 HookCall::AfterSwap => &self.hook_calls.after_swap,
+                HookCall::BeforeAddLiquidity => &self.hook_calls.before_add_liquidity,
+                HookCall::AfterAddLiquidity => &self.hook_calls.after_add_liquidity,
+                HookCall::BeforeRemoveLiquidity => &self.hook_calls.before_remove_liquidity,
+                HookCall::AfterRemoveLiquidity => &self.hook_calls.after_remove_liquidity,
             };
             execute_hooks(&hooks, &self.hook_badges, hook_args)
         }
@@ -1135,6 +1948,111 @@ mod pool {
         pub fn last_observation_index(&self) -> Option<u16> {
             self.oracle.last_observation_index()
         }
+
+        /// Returns a snapshot of the complete state of the pool.
+        ///
+        /// This combines the values of all getters, the protocol fees held by the pool and its hooks,
+        /// allowing integrators, migrations and audits to read a consistent state in a single call.
+        ///
+        /// # Returns
+        /// A `PoolState` containing every configuration and live value of the pool.
+        pub fn state(&self) -> PoolState {
+            let (x_reserve, y_reserve) = self.vault_amounts();
+            PoolState {
+                pool_address: self.pool_address,
+                x_address: self.x_address,
+                y_address: self.y_address,
+                x_divisibility: self.x_divisibility,
+                y_divisibility: self.y_divisibility,
+                lp_address: self.lp_address(),
+                flash_loan_address: self.flash_loan_address(),
+                liquidity_pool: self.liquidity_pool.address(),
+                x_share: self.x_share,
+                ratio: self.ratio,
+                x_reserve,
+                y_reserve,
+                lp_total_supply: self.lp_total_supply(),
+                price_sqrt: self.price_sqrt(),
+                input_fee_rate: self.input_fee_rate,
+                flash_loan_fee_rate: self.flash_loan_fee_rate,
+                pending_fee_rates: self.pending_fee_rates.clone(),
+                protocol_fee_mode: self.protocol_fee_mode,
+                x_protocol_fee: self.x_protocol_fee.amount(),
+                y_protocol_fee: self.y_protocol_fee.amount(),
+                protocol_fee_checkpoint: self.protocol_fee_checkpoint,
+                registry: self.registry.address(),
+                registry_fallback: self.registry_fallback(),
+                registry_config: self.registry_config.clone(),
+                registry_sync_deferred_until: self.registry_sync_deferred_until,
+                registry_sync_grace_period: self.registry_sync_grace_period,
+                hooks: self.hook_states(),
+                guardian: self.guardian,
+                paused: self.paused(),
+                observations_limit: self.observations_limit(),
+                observations_stored: self.observations_stored(),
+                oldest_observation_at: self.oldest_observation_at(),
+                last_observation_index: self.last_observation_index(),
+            }
+        }
+
+        /// Lists the hooks of the pool with the calls they are registered for.
+        ///
+        /// Hooks are ordered by their first call in the lifecycle, followed by hooks without any calls.
+        fn hook_states(&self) -> Vec<PoolHook> {
+            let hook_calls = [
+                (
+                    HookCall::BeforeInstantiate,
+                    &self.hook_calls.before_instantiate.1,
+                ),
+                (
+                    HookCall::AfterInstantiate,
+                    &self.hook_calls.after_instantiate.1,
+                ),
+                (HookCall::BeforeSwap, &self.hook_calls.before_swap.1),
+                (HookCall::AfterSwap, &self.hook_calls.after_swap.1),
+                (
+                    HookCall::BeforeAddLiquidity,
+                    &self.hook_calls.before_add_liquidity.1,
+                ),
+                (
+                    HookCall::AfterAddLiquidity,
+                    &self.hook_calls.after_add_liquidity.1,
+                ),
+                (
+                    HookCall::BeforeRemoveLiquidity,
+                    &self.hook_calls.before_remove_liquidity.1,
+                ),
+                (
+                    HookCall::AfterRemoveLiquidity,
+                    &self.hook_calls.after_remove_liquidity.1,
+                ),
+            ];
+            let mut hooks: IndexMap<ComponentAddress, Vec<HookCall>> = IndexMap::new();
+            for (call, components) in hook_calls {
+                for component in components {
+                    hooks
+                        .entry(component.address())
+                        .or_default()
+                        .push(call.clone());
+                }
+            }
+
+            let mut hooks_without_calls: Vec<ComponentAddress> = self
+                .hook_badges
+                .keys()
+                .filter(|component| !hooks.contains_key(*component))
+                .cloned()
+                .collect();
+            hooks_without_calls.sort();
+            for component in hooks_without_calls {
+                hooks.insert(component, Vec::new());
+            }
+
+            hooks
+                .into_iter()
+                .map(|(component, calls)| PoolHook { component, calls })
+                .collect()
+        }
     }
 }
 
@@ -1145,38 +2063,63 @@ pub struct FlashLoan {
     pub fee: Decimal,
 }
 
-#[derive(ScryptoSbor, ScryptoEvent)]
-struct InstantiateEvent {
-    pool_address: ComponentAddress,
-    lp_address: ResourceAddress,
-    x_address: ResourceAddress,
-    y_address: ResourceAddress,
-    x_share: Decimal,
-    input_fee_rate: Decimal,
-    flash_loan_address: ResourceAddress,
-    flash_loan_fee_rate: Decimal,
-    registry_address: ComponentAddress,
-    liquidity_pool_address: ComponentAddress,
-    hooks: Vec<ComponentAddress>,
-    dapp_definition: ComponentAddress,
+/// Fee rates proposed by the guardian, which can be applied from `effective_at` (Unix timestamp in seconds) on.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct FeeRatesProposal {
+    pub input_fee_rate: Decimal,
+    pub flash_loan_fee_rate: Decimal,
+    pub effective_at: u64,
 }
 
-#[derive(ScryptoSbor, ScryptoEvent)]
-struct SwapEvent {
-    input_address: ResourceAddress,
-    input_amount: Decimal,
-    input_gross_amount: Decimal,
-    input_fee_lp: Decimal,
-    input_fee_protocol: Decimal,
-    output_address: ResourceAddress,
-    output_amount: Decimal,
-    output_return_amount: Decimal,
-    price_sqrt: PreciseDecimal,
+/// Snapshot of the complete state of a pool, returned by `state`.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct PoolState {
+    pub pool_address: ComponentAddress,
+    pub x_address: ResourceAddress,
+    pub y_address: ResourceAddress,
+    pub x_divisibility: u8,
+    pub y_divisibility: u8,
+    pub lp_address: ResourceAddress,
+    pub flash_loan_address: ResourceAddress,
+    pub liquidity_pool: ComponentAddress,
+    /// Share of token X in the pool's total value.
+    pub x_share: Decimal,
+    /// Ratio of the token weights (x_share / y_share) used in the swap math.
+    pub ratio: Decimal,
+    pub x_reserve: Decimal,
+    pub y_reserve: Decimal,
+    pub lp_total_supply: Decimal,
+    pub price_sqrt: Option<PreciseDecimal>,
+    pub input_fee_rate: Decimal,
+    pub flash_loan_fee_rate: Decimal,
+    pub pending_fee_rates: Option<FeeRatesProposal>,
+    pub protocol_fee_mode: ProtocolFeeMode,
+    /// Protocol fees of token X held by the pool until the next registry sync.
+    pub x_protocol_fee: Decimal,
+    /// Protocol fees of token Y held by the pool until the next registry sync.
+    pub y_protocol_fee: Decimal,
+    /// Reserves per LP token at the last protocol fee checkpoint in `LpTokens` mode.
+    pub protocol_fee_checkpoint: Option<(PreciseDecimal, PreciseDecimal)>,
+    pub registry: ComponentAddress,
+    pub registry_fallback: Option<ComponentAddress>,
+    /// Configuration received from the registry during the last sync, including the `fee_protocol_share`.
+    pub registry_config: RegistryConfig,
+    pub registry_sync_deferred_until: Option<u64>,
+    /// Seconds after `next_sync_time` during which swaps continue without syncing with the registry.
+    pub registry_sync_grace_period: u64,
+    pub hooks: Vec<PoolHook>,
+    pub guardian: Option<ComponentAddress>,
+    /// Whether the pool is paused by its guardian or the registry.
+    pub paused: bool,
+    pub observations_limit: u16,
+    pub observations_stored: u16,
+    pub oldest_observation_at: Option<u64>,
+    pub last_observation_index: Option<u16>,
 }
 
-#[derive(ScryptoSbor, ScryptoEvent)]
-struct FlashLoanEvent {
-    address: ResourceAddress,
-    due_amount: Decimal,
-    fee: Decimal,
+/// A hook of a pool with the calls it is registered for, in lifecycle order.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct PoolHook {
+    pub component: ComponentAddress,
+    pub calls: Vec<HookCall>,
 }
@@ -0,0 +1,27 @@
+use scrypto::prelude::*;
+
+pub use ociswap_pool_events::ProtocolFeeMode;
+
+/// Reads the protocol fee mode configured for the pool package.
+pub trait ProtocolFeeModeMetadata {
+    fn from_package_metadata() -> Self;
+}
+
+impl ProtocolFeeModeMetadata for ProtocolFeeMode {
+    /// Reads the protocol fee mode from the `protocol_fee_mode` metadata of the pool package,
+    /// which is either `skim` or `lp_tokens`. Defaults to `Skim` if not set.
+    ///
+    /// # Panics
+    /// * If the metadata contains any other value.
+    fn from_package_metadata() -> Self {
+        let package: Global<Package> = Runtime::package_address().into();
+        let mode: Option<String> = package
+            .get_metadata("protocol_fee_mode")
+            .expect("Failed to get protocol fee mode from metadata");
+        match mode.as_deref() {
+            None | Some("skim") => ProtocolFeeMode::Skim,
+            Some("lp_tokens") => ProtocolFeeMode::LpTokens,
+            Some(mode) => panic!("Invalid protocol fee mode {mode} in metadata!"),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::constants::*;
+use scrypto::prelude::*;
+
+/// Pool configuration returned by the registry on every sync, wrapped in a `RegistrySyncResponse`.
+///
+/// Older registries only return the tuple `(fee_protocol_share, next_sync_time)`,
+/// which is converted via `RegistryConfig::legacy`.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct RegistryConfig {
+    /// Share of the input fees collected as protocol fees, clamped to [0, `FEE_PROTOCOL_SHARE_MAX`].
+    pub fee_protocol_share: Decimal,
+    /// Unix timestamp in seconds from which on the pool syncs with the registry again.
+    pub next_sync_time: u64,
+    /// Share of the flash loan fees collected as protocol fees, clamped to [0, 1]. The remainder goes to the LPs.
+    pub flash_loan_fee_protocol_share: Decimal,
+    /// Pauses swaps, flash loans and adding liquidity if set to `true`, independent of the pool's guardian.
+    /// Each of these operations syncs before checking the flag, so the pause applies from the first sync after it.
+    pub paused: Option<bool>,
+    /// Maximum fraction of the token reserves which can be borrowed in a single flash loan, clamped to [0, 1].
+    pub flash_loan_max_fraction: Option<Decimal>,
+}
+
+/// Versioned response of the registry's `sync` and `sync_lp_tokens` methods.
+///
+/// Configuration changes are introduced as a new version instead of changing an existing one, so the pool never
+/// has to guess the format of a response from its shape.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub enum RegistrySyncResponse {
+    V1(RegistryConfig),
+}
+
+impl RegistrySyncResponse {
+    pub fn into_config(self) -> RegistryConfig {
+        match self {
+            RegistrySyncResponse::V1(config) => config,
+        }
+    }
+}
+
+impl RegistryConfig {
+    /// Converts the response of a registry using the original sync protocol,
+    /// which collects all flash loan fees as protocol fees and neither pauses nor limits the pool.
+    pub fn legacy(fee_protocol_share: Decimal, next_sync_time: u64) -> Self {
+        Self {
+            fee_protocol_share,
+            next_sync_time,
+            flash_loan_fee_protocol_share: Decimal::ONE,
+            paused: None,
+            flash_loan_max_fraction: None,
+        }
+    }
+
+    /// Decodes the response of the registry's `sync` method, which is either a versioned `RegistrySyncResponse`
+    /// or, for registries using the original sync protocol, the unversioned tuple `(fee_protocol_share, next_sync_time)`.
+    ///
+    /// # Panics
+    /// * If the response is of an unsupported version or neither format.
+    pub fn from_sync_response(response: ScryptoValue) -> Self {
+        let encoded = scrypto_encode(&response).unwrap();
+        let config = match response {
+            ScryptoValue::Enum { .. } => scrypto_decode::<RegistrySyncResponse>(&encoded)
+                .expect("Unsupported registry sync response version!")
+                .into_config(),
+            _ => {
+                let (fee_protocol_share, next_sync_time) =
+                    scrypto_decode::<(Decimal, u64)>(&encoded)
+                        .expect("Invalid registry sync response!");
+                Self::legacy(fee_protocol_share, next_sync_time)
+            }
+        };
+        config.clamped()
+    }
+
+    /// Clamps all shares and fractions to their allowed ranges.
+    pub fn clamped(self) -> Self {
+        Self {
+            fee_protocol_share: self
+                .fee_protocol_share
+                .clamp(Decimal::ZERO, FEE_PROTOCOL_SHARE_MAX),
+            flash_loan_fee_protocol_share: self
+                .flash_loan_fee_protocol_share
+                .clamp(Decimal::ZERO, Decimal::ONE),
+            flash_loan_max_fraction: self
+                .flash_loan_max_fraction
+                .map(|fraction| fraction.clamp(Decimal::ZERO, Decimal::ONE)),
+            ..self
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.unwrap_or(false)
+    }
+}
+
+/// Reads the grace period after `next_sync_time` during which swaps continue without syncing with the registry
+/// from the `registry_sync_grace_period` metadata of the pool package.
+///
+/// The grace period is required if the pool package has a fallback registry, since a guardian-less pool can only
+/// fail over to it via `fail_over_stale_registry` once the registry is stale for `REGISTRY_FAILOVER_STALENESS_MIN`.
+/// Without a grace period every swap in between fails if the registry is broken. Otherwise it defaults to zero.
+///
+/// # Arguments
+/// * `has_registry_fallback` - Whether the pool package has a fallback registry.
+///
+/// # Panics
+/// * If the grace period is not set but the pool package has a fallback registry.
+/// * If the grace period exceeds `REGISTRY_SYNC_GRACE_PERIOD_MAX`.
+pub fn registry_sync_grace_period_from_metadata(has_registry_fallback: bool) -> u64 {
+    let package: Global<Package> = Runtime::package_address().into();
+    let grace_period: Option<u64> = package
+        .get_metadata("registry_sync_grace_period")
+        .expect("Failed to get registry sync grace period from metadata");
+    assert!(
+        grace_period.is_some() || !has_registry_fallback,
+        "Registry sync grace period is required if a fallback registry is set!"
+    );
+    let grace_period = grace_period.unwrap_or(0);
+    assert!(
+        grace_period <= REGISTRY_SYNC_GRACE_PERIOD_MAX,
+        "Registry sync grace period can be at most {REGISTRY_SYNC_GRACE_PERIOD_MAX} seconds!"
+    );
+    grace_period
+}
@@ -0,0 +1,301 @@
+use crate::constants::*;
+use crate::pool::pool::Pool;
+use crate::pool_math::*;
+use common::time::*;
+use scrypto::prelude::*;
+
+#[blueprint]
+#[events(SplitSwapEvent, BatchSwapEvent)]
+mod router {
+    struct Router {}
+
+    impl Router {
+        /// Swaps the input tokens along a path of pools, using the output of each pool as the input of the next one.
+        ///
+        /// Intermediate amounts are not checked, instead the slippage of the whole route is limited by a single minimum
+        /// output amount.
+        ///
+        /// ## Arguments
+        /// - `input_bucket`: Tokens to be swapped in the first pool of the path.
+        /// - `path`: Ordered pool components to swap through. Each pool has to contain the output token of the previous one.
+        /// - `min_output_amount`: Minimum amount of output tokens to be returned by the last pool.
+        /// - `deadline`: Unix timestamp in seconds after which the swap is rejected.
+        ///
+        /// ## Returns
+        /// - A `Bucket` containing the output tokens of the last pool.
+        ///
+        /// ## Panics
+        /// - If the path is empty.
+        /// - If the deadline has passed.
+        /// - If the output amount is below `min_output_amount`.
+        /// - If any swap of the path fails (see `Pool::swap`).
+        pub fn swap_path(
+            input_bucket: Bucket,
+            path: Vec<ComponentAddress>,
+            min_output_amount: Decimal,
+            deadline: u64,
+        ) -> Bucket {
+            assert_deadline(deadline);
+            assert!(!path.is_empty(), "[Router]: Path is empty.");
+
+            let output_bucket = path.into_iter().fold(input_bucket, |bucket, pool_address| {
+                let mut pool: Global<Pool> = pool_address.into();
+                pool.swap(bucket)
+            });
+            assert!(
+                output_bucket.amount() >= min_output_amount,
+                "[Router]: Output amount {:?} is below the minimum output amount {:?}.",
+                output_bucket.amount(),
+                min_output_amount
+            );
+            output_bucket
+        }
+
+        /// Swaps the input tokens in multiple pools of the same pair, splitting the input amount between them to minimise
+        /// the price impact.
+        ///
+        /// The split is calculated from the current reserves (via `total_liquidity`), input fee rates and weights of the
+        /// pools, such that the marginal output of all pools receiving a part is equal (see `split_input_amount`).
+        /// Pools whose spot price after fees is worse than the resulting marginal output receive nothing.
+        ///
+        /// The split uses the base input fee rate of each pool. `BeforeSwap` hooks, e.g. dynamic fee hooks, can charge a
+        /// different rate per swap, which the router cannot know in advance, so the split is not optimal for such
+        /// pools. `min_output_amount` still bounds the total output.
+        ///
+        /// ## Arguments
+        /// - `input_bucket`: Tokens to be swapped.
+        /// - `pools`: Candidate pool components, all containing the input token and the same output token.
+        /// - `min_output_amount`: Minimum amount of output tokens to be returned in total.
+        /// - `deadline`: Unix timestamp in seconds after which the swap is rejected.
+        ///
+        /// ## Returns
+        /// - A `Bucket` containing the output tokens of all pools.
+        ///
+        /// ## Panics
+        /// - If no or more than `SPLIT_ROUTE_POOLS_MAX` pools are given, or a pool is given twice.
+        /// - If the pools do not all contain the input token and the same output token.
+        /// - If the deadline has passed.
+        /// - If the output amount is below `min_output_amount`.
+        /// - If any swap fails (see `Pool::swap`).
+        pub fn swap_split(
+            mut input_bucket: Bucket,
+            pools: Vec<ComponentAddress>,
+            min_output_amount: Decimal,
+            deadline: u64,
+        ) -> Bucket {
+            assert_deadline(deadline);
+            assert!(!pools.is_empty(), "[Router]: No pools given.");
+            assert!(
+                pools.len() <= SPLIT_ROUTE_POOLS_MAX,
+                "[Router]: More than {} pools given.",
+                SPLIT_ROUTE_POOLS_MAX
+            );
+            assert!(
+                pools.iter().collect::<IndexSet<_>>().len() == pools.len(),
+                "[Router]: Pools need to be unique."
+            );
+
+            let (input_address, input_amount) =
+                (input_bucket.resource_address(), input_bucket.amount());
+            let pools: Vec<Global<Pool>> = pools.into_iter().map(|pool| pool.into()).collect();
+            let (output_address, input_divisibility) = pool_output(&pools[0], input_address);
+            let split_pools: Vec<SplitPool> = pools
+                .iter()
+                .map(|pool| {
+                    assert!(
+                        pool_output(pool, input_address).0 == output_address,
+                        "[Router]: Pools need to contain the same pair."
+                    );
+                    split_pool(pool, input_address, output_address)
+                })
+                .collect();
+            let input_amounts = split_input_amount(input_amount, &split_pools, input_divisibility);
+
+            // The last pool receiving a part is passed the remaining input, absorbing any rounding difference.
+            let last_index = input_amounts
+                .iter()
+                .rposition(|amount| *amount > Decimal::ZERO)
+                .unwrap_or(0);
+            let mut output_bucket = Bucket::new(output_address);
+            let mut parts = Vec::new();
+            for (index, mut pool) in pools.into_iter().enumerate() {
+                if index != last_index && input_amounts[index] == Decimal::ZERO {
+                    continue;
+                }
+                let part_bucket = if index == last_index {
+                    input_bucket.take(input_bucket.amount())
+                } else {
+                    input_bucket.take(input_amounts[index])
+                };
+                let part_input_amount = part_bucket.amount();
+                let part_output_bucket = pool.swap(part_bucket);
+                parts.push(SplitSwapPart {
+                    pool_address: pool.address(),
+                    input_amount: part_input_amount,
+                    output_amount: part_output_bucket.amount(),
+                });
+                output_bucket.put(part_output_bucket);
+            }
+            input_bucket.drop_empty();
+
+            assert!(
+                output_bucket.amount() >= min_output_amount,
+                "[Router]: Output amount {:?} is below the minimum output amount {:?}.",
+                output_bucket.amount(),
+                min_output_amount
+            );
+            Runtime::emit_event(SplitSwapEvent {
+                input_address,
+                input_amount,
+                output_address,
+                output_amount: output_bucket.amount(),
+                parts,
+            });
+            output_bucket
+        }
+
+        /// Executes multiple independent swaps in a single call, e.g. to rebalance several pairs at once.
+        ///
+        /// ## Arguments
+        /// - `swaps`: Legs of the batch, each consisting of the pool component, the tokens to be swapped in the pool
+        ///   and the minimum amount of output tokens to be returned by the pool.
+        ///
+        /// ## Returns
+        /// - The output buckets of the legs, in the order of `swaps`.
+        ///
+        /// ## Panics
+        /// - If the output amount of any leg is below its minimum output amount, reverting all legs.
+        /// - If any swap fails (see `Pool::swap`).
+        pub fn swap_batch(swaps: Vec<(ComponentAddress, Bucket, Decimal)>) -> Vec<Bucket> {
+            let mut legs = Vec::new();
+            let output_buckets = swaps
+                .into_iter()
+                .enumerate()
+                .map(|(index, (pool_address, input_bucket, min_output_amount))| {
+                    let mut pool: Global<Pool> = pool_address.into();
+                    let (input_address, input_amount) =
+                        (input_bucket.resource_address(), input_bucket.amount());
+                    let output_bucket = pool.swap(input_bucket);
+                    assert!(
+                        output_bucket.amount() >= min_output_amount,
+                        "[Router]: Output amount {:?} of swap {} is below the minimum output amount {:?}.",
+                        output_bucket.amount(),
+                        index,
+                        min_output_amount
+                    );
+                    legs.push(BatchSwapLeg {
+                        pool_address,
+                        input_address,
+                        input_amount,
+                        output_address: output_bucket.resource_address(),
+                        output_amount: output_bucket.amount(),
+                    });
+                    output_bucket
+                })
+                .collect();
+            Runtime::emit_event(BatchSwapEvent { legs });
+            output_buckets
+        }
+
+        /// Quotes the output amount of `swap_path` by chaining the quotes of the pools along the path.
+        ///
+        /// ## Arguments
+        /// - `input_address`: Address of the tokens to be swapped in the first pool of the path.
+        /// - `input_amount`: Amount of tokens to be swapped in the first pool of the path.
+        /// - `path`: Ordered pool components to swap through.
+        ///
+        /// ## Returns
+        /// - The amount of output tokens returned by the last pool (see `Pool::quote`).
+        pub fn quote_path(
+            input_address: ResourceAddress,
+            input_amount: Decimal,
+            path: Vec<ComponentAddress>,
+        ) -> Decimal {
+            assert!(!path.is_empty(), "[Router]: Path is empty.");
+
+            let (_, output_amount) = path.into_iter().fold(
+                (input_address, input_amount),
+                |(input_address, input_amount), pool_address| {
+                    let pool: Global<Pool> = pool_address.into();
+                    let output_address = if input_address == pool.x_address() {
+                        pool.y_address()
+                    } else {
+                        pool.x_address()
+                    };
+                    (output_address, pool.quote(input_address, input_amount))
+                },
+            );
+            output_amount
+        }
+    }
+}
+
+/// Returns the output token of a swap of `input_address` in the pool and the divisibility of the input token.
+fn pool_output(pool: &Global<Pool>, input_address: ResourceAddress) -> (ResourceAddress, u8) {
+    let (x_address, y_address) = (pool.x_address(), pool.y_address());
+    if input_address == x_address {
+        (y_address, pool.x_divisibility())
+    } else if input_address == y_address {
+        (x_address, pool.y_divisibility())
+    } else {
+        panic!("[Router]: Input token is not part of the pool.")
+    }
+}
+
+/// Reads the reserves, input fee rate and weight of the pool for a swap from `input_address` to `output_address`.
+fn split_pool(
+    pool: &Global<Pool>,
+    input_address: ResourceAddress,
+    output_address: ResourceAddress,
+) -> SplitPool {
+    let reserves = pool.total_liquidity();
+    let x_share = pool.x_share();
+    let (input_share, output_share) = if input_address == pool.x_address() {
+        (x_share, dec!(1) - x_share)
+    } else {
+        (dec!(1) - x_share, x_share)
+    };
+    SplitPool {
+        input_vault: *reserves.get(&input_address).unwrap(),
+        output_vault: *reserves.get(&output_address).unwrap(),
+        input_fee_rate: pool.input_fee_rate(),
+        weight: input_share / output_share,
+    }
+}
+
+fn assert_deadline(deadline: u64) {
+    assert!(
+        Clock::time_in_seconds() <= deadline,
+        "[Router]: Deadline has passed."
+    );
+}
+
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct SplitSwapPart {
+    pub pool_address: ComponentAddress,
+    pub input_amount: Decimal,
+    pub output_amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct SplitSwapEvent {
+    input_address: ResourceAddress,
+    input_amount: Decimal,
+    output_address: ResourceAddress,
+    output_amount: Decimal,
+    parts: Vec<SplitSwapPart>,
+}
+
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct BatchSwapLeg {
+    pub pool_address: ComponentAddress,
+    pub input_address: ResourceAddress,
+    pub input_amount: Decimal,
+    pub output_address: ResourceAddress,
+    pub output_amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct BatchSwapEvent {
+    legs: Vec<BatchSwapLeg>,
+}
@@ -32,3 +32,17 @@ pub fn assert_hooks_bucket_output_and_address(
         hook_type_name
     );
 }
+
+/// Reduces a caller-supplied credential proof to the global id of the single non fungible it contains.
+///
+/// The proof itself is guaranteed to be genuine by the engine, checking the credential's resource address
+/// is up to the hooks the credential is forwarded to.
+pub fn credential_id(credential: Proof) -> NonFungibleGlobalId {
+    let credential = credential.skip_checking().as_non_fungible();
+    let credential_id = NonFungibleGlobalId::new(
+        credential.resource_address(),
+        credential.non_fungible_local_id(),
+    );
+    credential.drop();
+    credential_id
+}
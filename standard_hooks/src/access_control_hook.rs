@@ -0,0 +1,198 @@
+use ociswap_pool_hooks::*;
+use scrypto::prelude::*;
+
+#[blueprint]
+#[events(AccessUpdateEvent)]
+mod access_control_hook {
+    enable_method_auth! {
+        roles {
+            hook_admin => updatable_by: [];
+        },
+        methods {
+            calls                       => PUBLIC;
+            after_instantiate           => restrict_to: [hook_admin];
+            before_swap                 => restrict_to: [hook_admin];
+            before_add_liquidity        => restrict_to: [hook_admin];
+            before_remove_liquidity     => restrict_to: [hook_admin];
+            allow_credential_resource   => restrict_to: [OWNER];
+            deny_credential_resource    => restrict_to: [OWNER];
+            allow_credential            => restrict_to: [OWNER];
+            deny_credential             => restrict_to: [OWNER];
+            is_authorised               => PUBLIC;
+            credential_resources        => PUBLIC;
+            pool_address                => PUBLIC;
+        }
+    }
+
+    struct AccessControlHook {
+        pool_address: Option<ComponentAddress>,
+        credential_resources: IndexSet<ResourceAddress>,
+        allowlist: KeyValueStore<NonFungibleGlobalId, ()>,
+    }
+
+    impl AccessControlHook {
+        /// Instantiates a new access control hook for permissioned pools.
+        ///
+        /// Swaps and liquidity changes are only accepted if they are made via the pool's `*_with_credential` methods
+        /// with a credential that is either issued by one of the `credential_resources` (badge gating)
+        /// or has been individually added to the allowlist by the owner.
+        ///
+        /// ## Arguments
+        /// - `owner_badge`: Resource address of the badge allowed to manage credentials.
+        /// - `credential_resources`: Non fungible resources whose holders are allowed to trade.
+        ///
+        /// ## Returns
+        /// - A tuple containing:
+        ///   - A global reference to the instantiated `AccessControlHook`.
+        ///   - The hook badge, which has to be passed to the pool on instantiation.
+        pub fn instantiate(
+            owner_badge: ResourceAddress,
+            credential_resources: IndexSet<ResourceAddress>,
+        ) -> (Global<AccessControlHook>, FungibleBucket) {
+            let hook_badge = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init {
+                        "name" => "Access Control Hook badge", locked;
+                    }
+                })
+                .mint_initial_supply(1);
+
+            let hook_component = (Self {
+                pool_address: None,
+                credential_resources,
+                allowlist: KeyValueStore::new(),
+            })
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::Fixed(rule!(require(owner_badge))))
+            .roles(roles!(
+                hook_admin => rule!(require(hook_badge.resource_address()));
+            ))
+            .globalize();
+
+            (hook_component, hook_badge)
+        }
+
+        pub fn calls(&self) -> Vec<HookCall> {
+            vec![
+                HookCall::AfterInstantiate,
+                HookCall::BeforeSwap,
+                HookCall::BeforeAddLiquidity,
+                HookCall::BeforeRemoveLiquidity,
+            ]
+        }
+
+        /// Binds the hook to the pool it has been instantiated with.
+        pub fn after_instantiate(
+            &mut self,
+            state: AfterInstantiateState,
+        ) -> (AfterInstantiateState,) {
+            assert!(
+                self.pool_address.is_none(),
+                "[AccessControlHook]: Hook is already bound to a pool."
+            );
+            self.pool_address = Some(state.pool_address);
+            (state,)
+        }
+
+        pub fn before_swap(
+            &mut self,
+            before_swap_state: BeforeSwapState,
+            input_bucket: Bucket,
+        ) -> (BeforeSwapState, Bucket) {
+            self.assert_authorised(
+                before_swap_state.pool_address,
+                &before_swap_state.credential,
+            );
+            (before_swap_state, input_bucket)
+        }
+
+        pub fn before_add_liquidity(
+            &mut self,
+            state: BeforeAddLiquidityState,
+        ) -> (BeforeAddLiquidityState,) {
+            self.assert_authorised(state.pool_address, &state.credential);
+            (state,)
+        }
+
+        pub fn before_remove_liquidity(
+            &mut self,
+            state: BeforeRemoveLiquidityState,
+        ) -> (BeforeRemoveLiquidityState,) {
+            self.assert_authorised(state.pool_address, &state.credential);
+            (state,)
+        }
+
+        /// Allows all holders of a non fungible of `credential_resource`. Only callable by the owner.
+        pub fn allow_credential_resource(&mut self, credential_resource: ResourceAddress) {
+            self.credential_resources.insert(credential_resource);
+        }
+
+        /// Stops allowing holders of a non fungible of `credential_resource`. Only callable by the owner.
+        /// Individually allowlisted credentials of the resource stay allowed.
+        pub fn deny_credential_resource(&mut self, credential_resource: ResourceAddress) {
+            self.credential_resources.shift_remove(&credential_resource);
+        }
+
+        /// Adds a single credential to the allowlist. Only callable by the owner.
+        pub fn allow_credential(&mut self, credential: NonFungibleGlobalId) {
+            self.allowlist.insert(credential.clone(), ());
+            Runtime::emit_event(AccessUpdateEvent {
+                credential,
+                allowed: true,
+            });
+        }
+
+        /// Removes a single credential from the allowlist. Only callable by the owner.
+        pub fn deny_credential(&mut self, credential: NonFungibleGlobalId) {
+            self.allowlist.remove(&credential);
+            Runtime::emit_event(AccessUpdateEvent {
+                credential,
+                allowed: false,
+            });
+        }
+
+        /// Returns whether the given credential is accepted by the hook.
+        /// Calls without a credential are never authorised.
+        pub fn is_authorised(&self, credential: Option<NonFungibleGlobalId>) -> bool {
+            match credential {
+                None => false,
+                Some(credential) => {
+                    self.credential_resources
+                        .contains(&credential.resource_address())
+                        || self.allowlist.get(&credential).is_some()
+                }
+            }
+        }
+
+        pub fn credential_resources(&self) -> IndexSet<ResourceAddress> {
+            self.credential_resources.clone()
+        }
+
+        pub fn pool_address(&self) -> Option<ComponentAddress> {
+            self.pool_address
+        }
+
+        fn assert_authorised(
+            &self,
+            pool_address: ComponentAddress,
+            credential: &Option<NonFungibleGlobalId>,
+        ) {
+            assert_eq!(
+                Some(pool_address),
+                self.pool_address,
+                "[AccessControlHook]: Call originates from an unknown pool."
+            );
+            assert!(
+                self.is_authorised(credential.clone()),
+                "[AccessControlHook]: Missing or unauthorised credential."
+            );
+        }
+    }
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct AccessUpdateEvent {
+    credential: NonFungibleGlobalId,
+    allowed: bool,
+}
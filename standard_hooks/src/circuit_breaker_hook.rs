@@ -0,0 +1,291 @@
+use common::time::*;
+use ociswap_pool_hooks::*;
+use oracle::ObservationInterval;
+use scrypto::prelude::*;
+
+#[blueprint]
+#[events(CircuitBreakerTripEvent, CircuitBreakerResumeEvent)]
+mod circuit_breaker_hook {
+    enable_method_auth! {
+        roles {
+            hook_admin => updatable_by: [];
+            guardian => updatable_by: [];
+        },
+        methods {
+            calls               => PUBLIC;
+            after_instantiate   => restrict_to: [hook_admin];
+            after_swap          => restrict_to: [hook_admin];
+            refresh_reference   => PUBLIC;
+            halt                => restrict_to: [guardian];
+            resume              => restrict_to: [guardian];
+            set_parameters      => restrict_to: [guardian];
+            parameters          => PUBLIC;
+            reference_price_sqrt => PUBLIC;
+            halted_until        => PUBLIC;
+            is_halted           => PUBLIC;
+            pool_address        => PUBLIC;
+        }
+    }
+
+    struct CircuitBreakerHook {
+        pool_address: Option<ComponentAddress>,
+        parameters: CircuitBreakerParameters,
+        reference_price_sqrt: Option<PreciseDecimal>,
+        halted_until: Option<u64>,
+    }
+
+    impl CircuitBreakerHook {
+        /// Instantiates a new circuit breaker hook.
+        ///
+        /// After every swap the post-swap `price_sqrt` is compared against a reference price, the pool's TWAP over
+        /// the last `window_seconds`. If the price deviates by more than `max_deviation`, the swap is rejected.
+        ///
+        /// Since hooks cannot call back into the pool during a swap, the reference TWAP is read from the pool's oracle
+        /// in the permissionless `refresh_reference` method, which should be called regularly (e.g. by a keeper).
+        /// As long as no reference exists, swaps are not checked.
+        ///
+        /// A rejected swap is reverted entirely, including any events. Therefore the breaker trips, i.e. halts all swaps
+        /// for `cooldown_seconds` and emits a `CircuitBreakerTripEvent`, whenever `refresh_reference` finds the current
+        /// pool price deviating beyond the bound from the TWAP, or when the guardian halts the pool manually.
+        ///
+        /// ## Arguments
+        /// - `guardian_badge`: Resource address of the badge allowed to halt, resume and configure the breaker.
+        /// - `parameters`: Deviation bound, TWAP window and cool-down configuration.
+        ///
+        /// ## Returns
+        /// - A tuple containing:
+        ///   - A global reference to the instantiated `CircuitBreakerHook`.
+        ///   - The hook badge, which has to be passed to the pool on instantiation.
+        pub fn instantiate(
+            guardian_badge: ResourceAddress,
+            parameters: CircuitBreakerParameters,
+        ) -> (Global<CircuitBreakerHook>, FungibleBucket) {
+            parameters.assert_valid();
+
+            let hook_badge = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init {
+                        "name" => "Circuit Breaker Hook badge", locked;
+                    }
+                })
+                .mint_initial_supply(1);
+
+            let hook_component = (Self {
+                pool_address: None,
+                parameters,
+                reference_price_sqrt: None,
+                halted_until: None,
+            })
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::None)
+            .roles(roles!(
+                hook_admin => rule!(require(hook_badge.resource_address()));
+                guardian => rule!(require(guardian_badge));
+            ))
+            .globalize();
+
+            (hook_component, hook_badge)
+        }
+
+        pub fn calls(&self) -> Vec<HookCall> {
+            vec![HookCall::AfterInstantiate, HookCall::AfterSwap]
+        }
+
+        /// Binds the hook to the pool it has been instantiated with.
+        pub fn after_instantiate(
+            &mut self,
+            state: AfterInstantiateState,
+        ) -> (AfterInstantiateState,) {
+            assert!(
+                self.pool_address.is_none(),
+                "[CircuitBreakerHook]: Hook is already bound to a pool."
+            );
+            self.pool_address = Some(state.pool_address);
+            (state,)
+        }
+
+        /// Rejects the swap if the breaker is halted or the post-swap price deviates too far from the reference.
+        pub fn after_swap(
+            &mut self,
+            after_swap_state: AfterSwapState,
+            output_bucket: Bucket,
+        ) -> (AfterSwapState, Bucket) {
+            assert_eq!(
+                Some(after_swap_state.pool_address),
+                self.pool_address,
+                "[CircuitBreakerHook]: Swap originates from an unknown pool."
+            );
+            assert!(!self.is_halted(), "[CircuitBreakerHook]: Swaps are halted.");
+            if let Some(reference_price_sqrt) = self.reference_price_sqrt {
+                let deviation = price_deviation(after_swap_state.price_sqrt, reference_price_sqrt);
+                assert!(
+                    deviation <= self.parameters.max_deviation,
+                    "[CircuitBreakerHook]: Price deviates too far from the reference price."
+                );
+            }
+            (after_swap_state, output_bucket)
+        }
+
+        /// Updates the reference price to the pool's TWAP over the last `window_seconds`
+        /// and trips the breaker if the current pool price deviates beyond the bound.
+        ///
+        /// If the oracle does not yet cover a full window, the reference price is kept.
+        ///
+        /// # Returns
+        /// * The reference `price_sqrt` used for the following swaps.
+        pub fn refresh_reference(&mut self) -> Option<PreciseDecimal> {
+            let pool_address = self
+                .pool_address
+                .expect("[CircuitBreakerHook]: Hook is not bound to a pool yet.");
+            let pool: Global<AnyComponent> = pool_address.into();
+
+            let now = Clock::time_in_seconds();
+            let window_start = match now.checked_sub(self.parameters.window_seconds) {
+                Some(window_start) => window_start,
+                None => return self.reference_price_sqrt,
+            };
+            match pool.call::<(), Option<u64>>("oldest_observation_at", &()) {
+                Some(oldest_observation_at) if oldest_observation_at <= window_start => {}
+                _ => return self.reference_price_sqrt,
+            };
+
+            let observation_intervals = pool.call::<(Vec<(u64, u64)>,), Vec<ObservationInterval>>(
+                "observation_intervals",
+                &(vec![(window_start, now)],),
+            );
+            let twap_price_sqrt = PreciseDecimal::from(observation_intervals[0].price_sqrt);
+            self.reference_price_sqrt = Some(twap_price_sqrt);
+
+            if !self.is_halted() {
+                if let Some(price_sqrt) = pool.call::<(), Option<PreciseDecimal>>("price_sqrt", &())
+                {
+                    let deviation = price_deviation(price_sqrt, twap_price_sqrt);
+                    if deviation > self.parameters.max_deviation {
+                        self.trip(TripTrigger::Deviation, Some(price_sqrt), Some(deviation));
+                    }
+                }
+            }
+
+            self.reference_price_sqrt
+        }
+
+        /// Halts all swaps for the cool-down period. Only callable by the guardian.
+        pub fn halt(&mut self) {
+            self.trip(TripTrigger::Guardian, None, None);
+        }
+
+        /// Resumes swaps before the cool-down period has passed. Only callable by the guardian.
+        pub fn resume(&mut self) {
+            self.halted_until = None;
+            Runtime::emit_event(CircuitBreakerResumeEvent {
+                pool_address: self.pool_address,
+            });
+        }
+
+        /// Updates the breaker parameters. Only callable by the guardian.
+        ///
+        /// # Panics
+        /// * If `parameters` are invalid (see `CircuitBreakerParameters::assert_valid`).
+        pub fn set_parameters(&mut self, parameters: CircuitBreakerParameters) {
+            parameters.assert_valid();
+            self.parameters = parameters;
+        }
+
+        pub fn parameters(&self) -> CircuitBreakerParameters {
+            self.parameters.clone()
+        }
+
+        pub fn reference_price_sqrt(&self) -> Option<PreciseDecimal> {
+            self.reference_price_sqrt
+        }
+
+        pub fn halted_until(&self) -> Option<u64> {
+            self.halted_until
+        }
+
+        pub fn is_halted(&self) -> bool {
+            self.halted_until.map_or(false, |halted_until| {
+                Clock::time_in_seconds() < halted_until
+            })
+        }
+
+        pub fn pool_address(&self) -> Option<ComponentAddress> {
+            self.pool_address
+        }
+
+        fn trip(
+            &mut self,
+            trigger: TripTrigger,
+            price_sqrt: Option<PreciseDecimal>,
+            deviation: Option<Decimal>,
+        ) {
+            let halted_until = Clock::time_in_seconds() + self.parameters.cooldown_seconds;
+            self.halted_until = Some(halted_until);
+            Runtime::emit_event(CircuitBreakerTripEvent {
+                pool_address: self.pool_address,
+                trigger,
+                reference_price_sqrt: self.reference_price_sqrt,
+                price_sqrt,
+                deviation,
+                halted_until,
+            });
+        }
+    }
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct CircuitBreakerParameters {
+    pub max_deviation: Decimal,
+    pub window_seconds: u64,
+    pub cooldown_seconds: u64,
+}
+
+impl CircuitBreakerParameters {
+    /// Asserts that the deviation bound is positive and the TWAP window is not empty.
+    pub fn assert_valid(&self) {
+        assert!(
+            self.max_deviation > Decimal::ZERO,
+            "[CircuitBreakerHook]: Maximum deviation must be positive."
+        );
+        assert!(
+            self.window_seconds > 0,
+            "[CircuitBreakerHook]: TWAP window must not be empty."
+        );
+    }
+}
+
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq)]
+pub enum TripTrigger {
+    Deviation,
+    Guardian,
+}
+
+/// Calculates the absolute relative deviation of the price from the reference price.
+///
+/// Both prices are given as `price_sqrt`, hence the price ratio is the square of their ratio.
+pub fn price_deviation(
+    price_sqrt: PreciseDecimal,
+    reference_price_sqrt: PreciseDecimal,
+) -> Decimal {
+    let price_sqrt_ratio = price_sqrt / reference_price_sqrt;
+    let deviation = (price_sqrt_ratio * price_sqrt_ratio - PreciseDecimal::ONE)
+        .checked_abs()
+        .unwrap();
+    Decimal::try_from(deviation).expect("[CircuitBreakerHook]: Deviation overflow.")
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct CircuitBreakerTripEvent {
+    pool_address: Option<ComponentAddress>,
+    trigger: TripTrigger,
+    reference_price_sqrt: Option<PreciseDecimal>,
+    price_sqrt: Option<PreciseDecimal>,
+    deviation: Option<Decimal>,
+    halted_until: u64,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct CircuitBreakerResumeEvent {
+    pool_address: Option<ComponentAddress>,
+}
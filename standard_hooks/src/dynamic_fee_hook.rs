@@ -0,0 +1,277 @@
+use common::time::*;
+use common::utils::assert_fee_rate_within_bounds;
+use ociswap_pool_hooks::constants::INPUT_FEE_RATE_MAX;
+use ociswap_pool_hooks::*;
+use oracle::ObservationInterval;
+use scrypto::prelude::*;
+use std::cmp::min;
+
+#[blueprint]
+#[events(InputFeeRateUpdateEvent)]
+mod dynamic_fee_hook {
+    enable_method_auth! {
+        roles {
+            hook_admin => updatable_by: [];
+        },
+        methods {
+            calls                   => PUBLIC;
+            after_instantiate       => restrict_to: [hook_admin];
+            before_swap             => restrict_to: [hook_admin];
+            update_input_fee_rate   => PUBLIC;
+            set_fee_parameters      => restrict_to: [OWNER];
+            fee_parameters          => PUBLIC;
+            input_fee_rate          => PUBLIC;
+            volatility              => PUBLIC;
+            pool_address            => PUBLIC;
+        }
+    }
+
+    struct DynamicFeeHook {
+        pool_address: Option<ComponentAddress>,
+        fee_parameters: DynamicFeeParameters,
+        input_fee_rate: Decimal,
+        volatility: Decimal,
+    }
+
+    impl DynamicFeeHook {
+        /// Instantiates a new dynamic fee hook.
+        ///
+        /// The hook adjusts the input fee rate of the pool it is attached to between a configurable floor and ceiling,
+        /// based on the realised volatility of the pool's oracle. The hook starts with the fee floor until the first
+        /// successful `update_input_fee_rate`.
+        ///
+        /// ## Arguments
+        /// - `owner_badge`: Resource address of the badge allowed to change the fee parameters.
+        /// - `fee_parameters`: Initial fee floor, ceiling and volatility window configuration.
+        ///
+        /// ## Returns
+        /// - A tuple containing:
+        ///   - A global reference to the instantiated `DynamicFeeHook`.
+        ///   - The hook badge, which has to be passed to the pool on instantiation.
+        ///
+        /// ## Panics
+        /// - If `fee_parameters` are invalid (see `DynamicFeeParameters::assert_valid`).
+        pub fn instantiate(
+            owner_badge: ResourceAddress,
+            fee_parameters: DynamicFeeParameters,
+        ) -> (Global<DynamicFeeHook>, FungibleBucket) {
+            fee_parameters.assert_valid();
+
+            let hook_badge = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init {
+                        "name" => "Dynamic Fee Hook badge", locked;
+                    }
+                })
+                .mint_initial_supply(1);
+
+            let hook_component = (Self {
+                pool_address: None,
+                input_fee_rate: fee_parameters.fee_floor,
+                volatility: Decimal::ZERO,
+                fee_parameters,
+            })
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::Fixed(rule!(require(owner_badge))))
+            .roles(roles!(
+                hook_admin => rule!(require(hook_badge.resource_address()));
+            ))
+            .globalize();
+
+            (hook_component, hook_badge)
+        }
+
+        pub fn calls(&self) -> Vec<HookCall> {
+            vec![HookCall::AfterInstantiate, HookCall::BeforeSwap]
+        }
+
+        /// Binds the hook to the pool it has been instantiated with.
+        ///
+        /// A hook badge can only be handed to a single pool, so the hook serves exactly one pool.
+        pub fn after_instantiate(
+            &mut self,
+            state: AfterInstantiateState,
+        ) -> (AfterInstantiateState,) {
+            assert!(
+                self.pool_address.is_none(),
+                "[DynamicFeeHook]: Hook is already bound to a pool."
+            );
+            self.pool_address = Some(state.pool_address);
+            (state,)
+        }
+
+        /// Applies the last computed input fee rate to the upcoming swap.
+        pub fn before_swap(
+            &mut self,
+            mut before_swap_state: BeforeSwapState,
+            input_bucket: Bucket,
+        ) -> (BeforeSwapState, Bucket) {
+            assert_eq!(
+                Some(before_swap_state.pool_address),
+                self.pool_address,
+                "[DynamicFeeHook]: Swap originates from an unknown pool."
+            );
+            before_swap_state.input_fee_rate = self.input_fee_rate;
+            (before_swap_state, input_bucket)
+        }
+
+        /// Recomputes the input fee rate from the realised volatility of the pool's oracle.
+        ///
+        /// Hooks are executed while the pool is still processing the swap, which prevents calling back into the
+        /// pool from within `before_swap`. Therefore the oracle is read in this separate, permissionless method
+        /// and the resulting fee rate is applied to all following swaps.
+        ///
+        /// The volatility is the mean absolute relative price change between consecutive oracle intervals
+        /// of `interval_seconds` length, looking back at most `intervals` intervals. The fee rate is then linearly
+        /// interpolated between `fee_floor` (no volatility) and `fee_ceiling` (volatility at or above `volatility_ceiling`).
+        /// If the oracle does not yet hold at least two full intervals, the current fee rate is kept.
+        ///
+        /// # Returns
+        /// * The input fee rate which is applied to the following swaps.
+        pub fn update_input_fee_rate(&mut self) -> Decimal {
+            let pool_address = self
+                .pool_address
+                .expect("[DynamicFeeHook]: Hook is not bound to a pool yet.");
+            let pool: Global<AnyComponent> = pool_address.into();
+
+            let oldest_observation_at =
+                match pool.call::<(), Option<u64>>("oldest_observation_at", &()) {
+                    Some(oldest_observation_at) => oldest_observation_at,
+                    None => return self.input_fee_rate,
+                };
+
+            let now = Clock::time_in_seconds();
+            let interval_seconds = self.fee_parameters.interval_seconds;
+            let available_intervals = now.saturating_sub(oldest_observation_at) / interval_seconds;
+            let intervals_count = min(available_intervals, self.fee_parameters.intervals as u64);
+            if intervals_count < 2 {
+                return self.input_fee_rate;
+            }
+
+            let intervals: Vec<(u64, u64)> = (0..intervals_count)
+                .rev()
+                .map(|i| {
+                    let end = now - i * interval_seconds;
+                    (end - interval_seconds, end)
+                })
+                .collect();
+            let observation_intervals = pool.call::<(Vec<(u64, u64)>,), Vec<ObservationInterval>>(
+                "observation_intervals",
+                &(intervals,),
+            );
+
+            self.volatility = realised_volatility(&observation_intervals);
+            self.input_fee_rate = self.fee_parameters.input_fee_rate(self.volatility);
+
+            Runtime::emit_event(InputFeeRateUpdateEvent {
+                pool_address,
+                volatility: self.volatility,
+                input_fee_rate: self.input_fee_rate,
+            });
+
+            self.input_fee_rate
+        }
+
+        /// Updates the fee parameters of the hook. Only callable by the owner.
+        ///
+        /// The currently applied input fee rate is clamped into the new bounds immediately,
+        /// the volatility based rate is recomputed on the next `update_input_fee_rate`.
+        ///
+        /// # Panics
+        /// * If `fee_parameters` are invalid (see `DynamicFeeParameters::assert_valid`).
+        pub fn set_fee_parameters(&mut self, fee_parameters: DynamicFeeParameters) {
+            fee_parameters.assert_valid();
+            self.input_fee_rate = self
+                .input_fee_rate
+                .clamp(fee_parameters.fee_floor, fee_parameters.fee_ceiling);
+            self.fee_parameters = fee_parameters;
+        }
+
+        pub fn fee_parameters(&self) -> DynamicFeeParameters {
+            self.fee_parameters.clone()
+        }
+
+        pub fn input_fee_rate(&self) -> Decimal {
+            self.input_fee_rate
+        }
+
+        pub fn volatility(&self) -> Decimal {
+            self.volatility
+        }
+
+        pub fn pool_address(&self) -> Option<ComponentAddress> {
+            self.pool_address
+        }
+    }
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct DynamicFeeParameters {
+    pub fee_floor: Decimal,
+    pub fee_ceiling: Decimal,
+    pub volatility_ceiling: Decimal,
+    pub interval_seconds: u64,
+    pub intervals: u16,
+}
+
+impl DynamicFeeParameters {
+    /// Asserts that `0 <= fee_floor <= fee_ceiling <= INPUT_FEE_RATE_MAX`, that the volatility ceiling is positive
+    /// and that the oracle window consists of at least two non-empty intervals.
+    pub fn assert_valid(&self) {
+        assert_fee_rate_within_bounds(self.fee_floor, INPUT_FEE_RATE_MAX, "fee floor");
+        assert_fee_rate_within_bounds(self.fee_ceiling, INPUT_FEE_RATE_MAX, "fee ceiling");
+        assert!(
+            self.fee_floor <= self.fee_ceiling,
+            "[DynamicFeeHook]: Fee floor must not be larger than the fee ceiling."
+        );
+        assert!(
+            self.volatility_ceiling > Decimal::ZERO,
+            "[DynamicFeeHook]: Volatility ceiling must be positive."
+        );
+        assert!(
+            self.interval_seconds > 0,
+            "[DynamicFeeHook]: Interval length must be positive."
+        );
+        assert!(
+            self.intervals >= 2,
+            "[DynamicFeeHook]: At least two intervals are required to measure volatility."
+        );
+    }
+
+    /// Linearly interpolates the input fee rate between floor and ceiling for the given volatility.
+    pub fn input_fee_rate(&self, volatility: Decimal) -> Decimal {
+        let volatility_fraction = min(volatility / self.volatility_ceiling, Decimal::ONE);
+        self.fee_floor + (self.fee_ceiling - self.fee_floor) * volatility_fraction
+    }
+}
+
+/// Calculates the mean absolute relative price change between consecutive observation intervals.
+///
+/// The oracle returns the average `price_sqrt` of each interval, hence the price ratio between two intervals
+/// is the square of their `price_sqrt` ratio.
+pub fn realised_volatility(observation_intervals: &[ObservationInterval]) -> Decimal {
+    if observation_intervals.len() < 2 {
+        return Decimal::ZERO;
+    }
+    let price_changes: PreciseDecimal = observation_intervals
+        .windows(2)
+        .map(|pair| {
+            let previous = PreciseDecimal::from(pair[0].price_sqrt);
+            let current = PreciseDecimal::from(pair[1].price_sqrt);
+            let price_sqrt_ratio = current / previous;
+            (price_sqrt_ratio * price_sqrt_ratio - PreciseDecimal::ONE)
+                .checked_abs()
+                .unwrap()
+        })
+        .fold(PreciseDecimal::ZERO, |sum, change| sum + change);
+    let volatility = price_changes / PreciseDecimal::from((observation_intervals.len() - 1) as u64);
+    Decimal::try_from(volatility).expect("[DynamicFeeHook]: Volatility overflow.")
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct InputFeeRateUpdateEvent {
+    pool_address: ComponentAddress,
+    volatility: Decimal,
+    input_fee_rate: Decimal,
+}
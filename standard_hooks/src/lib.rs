@@ -0,0 +1,6 @@
+pub mod access_control_hook;
+pub mod circuit_breaker_hook;
+pub mod dynamic_fee_hook;
+pub mod limit_order_hook;
+pub mod liquidity_rewards_hook;
+pub mod referral_hook;
@@ -0,0 +1,550 @@
+use common::math::DivisibilityRounding;
+use common::pools::SwapType;
+use ociswap_pool_hooks::*;
+use scrypto::prelude::*;
+
+#[blueprint]
+#[events(LimitOrderPlaceEvent, LimitOrderFillEvent, LimitOrderCancelEvent)]
+mod limit_order_hook {
+    enable_method_auth! {
+        roles {
+            hook_admin => updatable_by: [];
+        },
+        methods {
+            calls               => PUBLIC;
+            after_instantiate   => restrict_to: [hook_admin];
+            after_swap          => restrict_to: [hook_admin];
+            place_order         => PUBLIC;
+            cancel_order        => PUBLIC;
+            claim               => PUBLIC;
+            fill_order          => PUBLIC;
+            settle_fill         => PUBLIC;
+            order               => PUBLIC;
+            is_fillable         => PUBLIC;
+            last_price_sqrt     => PUBLIC;
+            order_address       => PUBLIC;
+            fill_ticket_address => PUBLIC;
+            pool_address        => PUBLIC;
+        }
+    }
+
+    struct LimitOrderHook {
+        pool_address: Option<ComponentAddress>,
+        x_address: ResourceAddress,
+        y_address: ResourceAddress,
+        x_vault: Vault,
+        y_vault: Vault,
+        order_manager: NonFungibleResourceManager,
+        fill_ticket_manager: NonFungibleResourceManager,
+        orders: KeyValueStore<NonFungibleLocalId, LimitOrder>,
+        last_price_sqrt: Option<PreciseDecimal>,
+        active_fill: Option<ActiveFill>,
+    }
+
+    impl LimitOrderHook {
+        /// Instantiates a new limit order hook for the given token pair.
+        ///
+        /// Users place orders by depositing one of the two tokens together with a target `price_sqrt`.
+        /// Whenever a swap in the pool moves the price across the target, the order becomes fillable.
+        /// Since hooks cannot call back into the pool during a swap, the conversion itself is executed by a keeper
+        /// within a single transaction, following the same pattern as flash loans:
+        /// 1. `fill_order` hands out the order tokens together with a transient fill ticket.
+        /// 2. The keeper swaps the tokens in the pool.
+        /// 3. `settle_fill` takes the swap output and the fill ticket, asserting that the limit price has been honoured.
+        ///
+        /// The hook records the output of the keeper's swap in `after_swap` and `settle_fill` requires the full output,
+        /// so any execution surplus above the limit price is credited to the order owner, not kept by the keeper.
+        ///
+        /// ## Arguments
+        /// - `x_address`: ResourceAddress of token X of the pool.
+        /// - `y_address`: ResourceAddress of token Y of the pool.
+        ///
+        /// ## Returns
+        /// - A tuple containing:
+        ///   - A global reference to the instantiated `LimitOrderHook`.
+        ///   - The hook badge, which has to be passed to the pool on instantiation.
+        pub fn instantiate(
+            x_address: ResourceAddress,
+            y_address: ResourceAddress,
+        ) -> (Global<LimitOrderHook>, FungibleBucket) {
+            let (address_reservation, component_address) =
+                Runtime::allocate_component_address(LimitOrderHook::blueprint_id());
+            let component_rule = rule!(require(global_caller(component_address)));
+
+            let hook_badge = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init {
+                        "name" => "Limit Order Hook badge", locked;
+                    }
+                })
+                .mint_initial_supply(1);
+
+            let order_manager =
+                ResourceBuilder::new_ruid_non_fungible::<LimitOrderReceipt>(OwnerRole::None)
+                    .metadata(metadata! {
+                        init {
+                            "name" => "Ociswap Limit Order", locked;
+                        }
+                    })
+                    .mint_roles(mint_roles!(
+                        minter => component_rule.clone();
+                        minter_updater => rule!(deny_all);
+                    ))
+                    .burn_roles(burn_roles!(
+                        burner => component_rule.clone();
+                        burner_updater => rule!(deny_all);
+                    ))
+                    .create_with_no_initial_supply();
+
+            let fill_ticket_manager =
+                ResourceBuilder::new_ruid_non_fungible::<FillTicket>(OwnerRole::None)
+                    .mint_roles(mint_roles!(
+                        minter => component_rule.clone();
+                        minter_updater => rule!(deny_all);
+                    ))
+                    .burn_roles(burn_roles!(
+                        burner => component_rule;
+                        burner_updater => rule!(deny_all);
+                    ))
+                    .deposit_roles(deposit_roles!(
+                        depositor => rule!(deny_all);
+                        depositor_updater => rule!(deny_all);
+                    ))
+                    .create_with_no_initial_supply();
+
+            let hook_component = (Self {
+                pool_address: None,
+                x_address,
+                y_address,
+                x_vault: Vault::new(x_address),
+                y_vault: Vault::new(y_address),
+                order_manager,
+                fill_ticket_manager,
+                orders: KeyValueStore::new(),
+                last_price_sqrt: None,
+                active_fill: None,
+            })
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::None)
+            .roles(roles!(
+                hook_admin => rule!(require(hook_badge.resource_address()));
+            ))
+            .with_address(address_reservation)
+            .globalize();
+
+            (hook_component, hook_badge)
+        }
+
+        pub fn calls(&self) -> Vec<HookCall> {
+            vec![HookCall::AfterInstantiate, HookCall::AfterSwap]
+        }
+
+        /// Binds the hook to the pool it has been instantiated with.
+        pub fn after_instantiate(
+            &mut self,
+            state: AfterInstantiateState,
+        ) -> (AfterInstantiateState,) {
+            assert!(
+                self.pool_address.is_none(),
+                "[LimitOrderHook]: Hook is already bound to a pool."
+            );
+            assert!(
+                state.x_address == self.x_address && state.y_address == self.y_address,
+                "[LimitOrderHook]: Pool tokens do not match the hook tokens."
+            );
+            self.pool_address = Some(state.pool_address);
+            (state,)
+        }
+
+        /// Records the post-swap price which determines whether resting orders can be filled.
+        /// During a fill, additionally records the output of the first swap selling exactly the order tokens,
+        /// which `settle_fill` requires in full.
+        pub fn after_swap(
+            &mut self,
+            after_swap_state: AfterSwapState,
+            output_bucket: Bucket,
+        ) -> (AfterSwapState, Bucket) {
+            assert_eq!(
+                Some(after_swap_state.pool_address),
+                self.pool_address,
+                "[LimitOrderHook]: Swap originates from an unknown pool."
+            );
+            self.last_price_sqrt = Some(after_swap_state.price_sqrt);
+
+            if let Some(active_fill) = self.active_fill.as_mut() {
+                let input_gross_amount = after_swap_state.input_amount
+                    + after_swap_state.input_fee_lp
+                    + after_swap_state.input_fee_protocol;
+                if active_fill.swap_output_amount.is_none()
+                    && after_swap_state.input_address == active_fill.input_address
+                    && input_gross_amount == active_fill.input_amount
+                {
+                    active_fill.swap_output_amount = Some(output_bucket.amount());
+                }
+            }
+            (after_swap_state, output_bucket)
+        }
+
+        /// Places a new limit order.
+        ///
+        /// Selling token X is filled once the pool's `price_sqrt` rises to or above `target_price_sqrt`,
+        /// selling token Y is filled once it falls to or below `target_price_sqrt`.
+        ///
+        /// # Arguments
+        /// * `input_bucket`: Tokens X or Y to sell.
+        /// * `target_price_sqrt`: Square root of the limit price of token X in terms of token Y.
+        ///
+        /// # Returns
+        /// * The order NFT required to claim the proceeds or cancel the order.
+        pub fn place_order(
+            &mut self,
+            input_bucket: Bucket,
+            target_price_sqrt: PreciseDecimal,
+        ) -> Bucket {
+            assert!(
+                input_bucket.amount() > Decimal::ZERO,
+                "[LimitOrderHook]: Order amount must be positive."
+            );
+            assert!(
+                target_price_sqrt > PreciseDecimal::ZERO,
+                "[LimitOrderHook]: Target price must be positive."
+            );
+            let swap_type = self.swap_type(input_bucket.resource_address());
+            let amount = input_bucket.amount();
+
+            self.vault(input_bucket.resource_address())
+                .put(input_bucket);
+
+            let order_receipt = self
+                .order_manager
+                .mint_ruid_non_fungible(LimitOrderReceipt {
+                    swap_type,
+                    target_price_sqrt,
+                });
+            let order_id = order_receipt.non_fungible_local_id();
+            self.orders.insert(
+                order_id.clone(),
+                LimitOrder {
+                    swap_type,
+                    target_price_sqrt,
+                    remaining_amount: amount,
+                    proceeds_amount: Decimal::ZERO,
+                    pending_fill: false,
+                },
+            );
+
+            Runtime::emit_event(LimitOrderPlaceEvent {
+                order_id,
+                swap_type,
+                amount,
+                target_price_sqrt,
+            });
+
+            order_receipt.into()
+        }
+
+        /// Cancels an order, returning the unfilled tokens together with all unclaimed proceeds.
+        ///
+        /// # Returns
+        /// A tuple containing:
+        /// * The unfilled input tokens.
+        /// * The unclaimed proceeds in the other token.
+        pub fn cancel_order(&mut self, order_receipt: NonFungibleBucket) -> (Bucket, Bucket) {
+            assert_eq!(
+                order_receipt.resource_address(),
+                self.order_manager.address(),
+                "[LimitOrderHook]: Invalid order receipt."
+            );
+            let order_id = order_receipt.non_fungible_local_id();
+            let order = self
+                .orders
+                .remove(&order_id)
+                .expect("[LimitOrderHook]: Order does not exist.");
+            assert!(
+                !order.pending_fill,
+                "[LimitOrderHook]: Order is currently being filled."
+            );
+
+            let (input_address, output_address) = self.order_addresses(order.swap_type);
+            let remaining = self.vault(input_address).take(order.remaining_amount);
+            let proceeds = self.vault(output_address).take(order.proceeds_amount);
+            self.order_manager.burn(order_receipt);
+
+            Runtime::emit_event(LimitOrderCancelEvent {
+                order_id,
+                remaining_amount: order.remaining_amount,
+                proceeds_amount: order.proceeds_amount,
+            });
+
+            (remaining, proceeds)
+        }
+
+        /// Claims the proceeds filled so far, keeping the order open for further fills.
+        ///
+        /// # Returns
+        /// * The proceeds in the token bought by the order.
+        pub fn claim(&mut self, order_proof: NonFungibleProof) -> Bucket {
+            let order_id = order_proof
+                .check(self.order_manager.address())
+                .non_fungible_local_id();
+            let mut order = self
+                .orders
+                .get_mut(&order_id)
+                .expect("[LimitOrderHook]: Order does not exist.");
+            let proceeds_amount = order.proceeds_amount;
+            let swap_type = order.swap_type;
+            order.proceeds_amount = Decimal::ZERO;
+            drop(order);
+
+            let (_, output_address) = self.order_addresses(swap_type);
+            self.vault(output_address).take(proceeds_amount)
+        }
+
+        /// Starts a (partial) fill of an order which has been crossed by the last swap in the pool.
+        /// Callable by anyone, the fill has to be completed within the same transaction via `settle_fill`.
+        /// Only one fill can be in progress at a time.
+        ///
+        /// # Arguments
+        /// * `order_id`: The local id of the order NFT.
+        /// * `amount`: The amount of input tokens to fill, at most the remaining amount of the order.
+        ///
+        /// # Returns
+        /// A tuple containing:
+        /// * The input tokens of the order, to be swapped by the caller.
+        /// * A transient fill ticket which has to be passed to `settle_fill` with the swap output.
+        pub fn fill_order(
+            &mut self,
+            order_id: NonFungibleLocalId,
+            amount: Decimal,
+        ) -> (Bucket, Bucket) {
+            assert!(
+                self.active_fill.is_none(),
+                "[LimitOrderHook]: Another fill is in progress."
+            );
+            assert!(
+                self.is_fillable(order_id.clone()),
+                "[LimitOrderHook]: Order has not been crossed by the pool price."
+            );
+            let mut order = self
+                .orders
+                .get_mut(&order_id)
+                .expect("[LimitOrderHook]: Order does not exist.");
+            assert!(
+                amount > Decimal::ZERO && amount <= order.remaining_amount,
+                "[LimitOrderHook]: Invalid fill amount."
+            );
+            order.remaining_amount -= amount;
+            order.pending_fill = true;
+            let swap_type = order.swap_type;
+            let target_price_sqrt = order.target_price_sqrt;
+            drop(order);
+
+            let (input_address, output_address) = self.order_addresses(swap_type);
+            let output_divisibility = ResourceManager::from_address(output_address)
+                .resource_type()
+                .divisibility()
+                .unwrap();
+            let min_output_amount = limit_output_amount(amount, target_price_sqrt, swap_type)
+                .ceil_to(output_divisibility);
+
+            self.active_fill = Some(ActiveFill {
+                input_address,
+                input_amount: amount,
+                swap_output_amount: None,
+            });
+            let fill_ticket = self.fill_ticket_manager.mint_ruid_non_fungible(FillTicket {
+                order_id,
+                input_amount: amount,
+                output_address,
+                min_output_amount,
+            });
+
+            (self.vault(input_address).take(amount), fill_ticket.into())
+        }
+
+        /// Completes a fill started with `fill_order`, crediting the proceeds to the order.
+        ///
+        /// # Arguments
+        /// * `proceeds`: The full output of swapping the order tokens in the pool.
+        /// * `fill_ticket`: The transient fill ticket returned by `fill_order`.
+        ///
+        /// # Panics
+        /// * If the order tokens have not been swapped in the pool.
+        /// * If the proceeds are in the wrong token, less than the output of that swap or worth less than the limit
+        ///   price.
+        pub fn settle_fill(&mut self, proceeds: Bucket, fill_ticket: NonFungibleBucket) {
+            assert_eq!(
+                fill_ticket.resource_address(),
+                self.fill_ticket_manager.address(),
+                "[LimitOrderHook]: Invalid fill ticket."
+            );
+            let ticket: FillTicket = fill_ticket.non_fungible::<FillTicket>().data();
+            assert_eq!(
+                proceeds.resource_address(),
+                ticket.output_address,
+                "[LimitOrderHook]: Proceeds are in the wrong token."
+            );
+            assert!(
+                proceeds.amount() >= ticket.min_output_amount,
+                "[LimitOrderHook]: Proceeds are below the limit price."
+            );
+            let swap_output_amount = self
+                .active_fill
+                .take()
+                .and_then(|active_fill| active_fill.swap_output_amount)
+                .expect("[LimitOrderHook]: Order tokens have not been swapped in the pool.");
+            assert!(
+                proceeds.amount() >= swap_output_amount,
+                "[LimitOrderHook]: Proceeds are below the swap output."
+            );
+
+            let proceeds_amount = proceeds.amount();
+            let mut order = self.orders.get_mut(&ticket.order_id).unwrap();
+            order.proceeds_amount += proceeds_amount;
+            order.pending_fill = false;
+            let remaining_amount = order.remaining_amount;
+            drop(order);
+
+            self.vault(ticket.output_address).put(proceeds);
+            self.fill_ticket_manager.burn(fill_ticket);
+
+            Runtime::emit_event(LimitOrderFillEvent {
+                order_id: ticket.order_id,
+                input_amount: ticket.input_amount,
+                output_amount: proceeds_amount,
+                remaining_amount,
+            });
+        }
+
+        pub fn order(&self, order_id: NonFungibleLocalId) -> Option<LimitOrder> {
+            self.orders.get(&order_id).map(|order| order.clone())
+        }
+
+        /// Returns whether the post-swap price of the last swap crossed the target price of the order.
+        pub fn is_fillable(&self, order_id: NonFungibleLocalId) -> bool {
+            let order = match self.orders.get(&order_id) {
+                Some(order) => order,
+                None => return false,
+            };
+            match (self.last_price_sqrt, order.swap_type) {
+                (None, _) => false,
+                (Some(price_sqrt), SwapType::SellX) => price_sqrt >= order.target_price_sqrt,
+                (Some(price_sqrt), SwapType::BuyX) => price_sqrt <= order.target_price_sqrt,
+            }
+        }
+
+        pub fn last_price_sqrt(&self) -> Option<PreciseDecimal> {
+            self.last_price_sqrt
+        }
+
+        pub fn order_address(&self) -> ResourceAddress {
+            self.order_manager.address()
+        }
+
+        pub fn fill_ticket_address(&self) -> ResourceAddress {
+            self.fill_ticket_manager.address()
+        }
+
+        pub fn pool_address(&self) -> Option<ComponentAddress> {
+            self.pool_address
+        }
+
+        fn swap_type(&self, input_address: ResourceAddress) -> SwapType {
+            if input_address == self.x_address {
+                return SwapType::SellX;
+            }
+            assert_eq!(
+                input_address, self.y_address,
+                "[LimitOrderHook]: Token does not belong to the pool."
+            );
+            SwapType::BuyX
+        }
+
+        fn order_addresses(&self, swap_type: SwapType) -> (ResourceAddress, ResourceAddress) {
+            match swap_type {
+                SwapType::SellX => (self.x_address, self.y_address),
+                SwapType::BuyX => (self.y_address, self.x_address),
+            }
+        }
+
+        fn vault(&mut self, resource_address: ResourceAddress) -> &mut Vault {
+            if resource_address == self.x_address {
+                &mut self.x_vault
+            } else {
+                &mut self.y_vault
+            }
+        }
+    }
+}
+
+#[derive(ScryptoSbor, NonFungibleData)]
+pub struct LimitOrderReceipt {
+    pub swap_type: SwapType,
+    pub target_price_sqrt: PreciseDecimal,
+}
+
+#[derive(ScryptoSbor, NonFungibleData)]
+pub struct FillTicket {
+    pub order_id: NonFungibleLocalId,
+    pub input_amount: Decimal,
+    pub output_address: ResourceAddress,
+    pub min_output_amount: Decimal,
+}
+
+/// The fill in progress between `fill_order` and `settle_fill`.
+#[derive(ScryptoSbor, Clone, Debug, PartialEq)]
+pub struct ActiveFill {
+    pub input_address: ResourceAddress,
+    pub input_amount: Decimal,
+    /// The output of the swap selling the order tokens, recorded in `after_swap`.
+    pub swap_output_amount: Option<Decimal>,
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct LimitOrder {
+    pub swap_type: SwapType,
+    pub target_price_sqrt: PreciseDecimal,
+    pub remaining_amount: Decimal,
+    pub proceeds_amount: Decimal,
+    pub pending_fill: bool,
+}
+
+/// Calculates the output amount of converting `input_amount` exactly at the limit price.
+///
+/// The price of token X in terms of token Y is `target_price_sqrt^2`. Selling X therefore yields
+/// `input_amount * price`, selling Y yields `input_amount / price`.
+pub fn limit_output_amount(
+    input_amount: Decimal,
+    target_price_sqrt: PreciseDecimal,
+    swap_type: SwapType,
+) -> PreciseDecimal {
+    let price = target_price_sqrt * target_price_sqrt;
+    match swap_type {
+        SwapType::SellX => PreciseDecimal::from(input_amount) * price,
+        SwapType::BuyX => PreciseDecimal::from(input_amount) / price,
+    }
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct LimitOrderPlaceEvent {
+    order_id: NonFungibleLocalId,
+    swap_type: SwapType,
+    amount: Decimal,
+    target_price_sqrt: PreciseDecimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct LimitOrderFillEvent {
+    order_id: NonFungibleLocalId,
+    input_amount: Decimal,
+    output_amount: Decimal,
+    remaining_amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct LimitOrderCancelEvent {
+    order_id: NonFungibleLocalId,
+    remaining_amount: Decimal,
+    proceeds_amount: Decimal,
+}
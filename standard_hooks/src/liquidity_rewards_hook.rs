@@ -0,0 +1,484 @@
+use common::math::DivisibilityRounding;
+use common::time::*;
+use ociswap_pool_hooks::*;
+use scrypto::prelude::*;
+
+#[blueprint]
+#[events(
+    LiquidityStakeEvent,
+    LiquidityUnstakeEvent,
+    RewardsClaimEvent,
+    RewardRateUpdateEvent
+)]
+mod liquidity_rewards_hook {
+    enable_method_auth! {
+        roles {
+            hook_admin => updatable_by: [];
+            reward_admin => updatable_by: [];
+        },
+        methods {
+            calls                   => PUBLIC;
+            after_instantiate       => restrict_to: [hook_admin];
+            after_swap              => restrict_to: [hook_admin];
+            before_add_liquidity    => restrict_to: [hook_admin];
+            after_add_liquidity     => restrict_to: [hook_admin];
+            before_remove_liquidity => restrict_to: [hook_admin];
+            after_remove_liquidity  => restrict_to: [hook_admin];
+            stake                   => PUBLIC;
+            unstake                 => PUBLIC;
+            claim                   => PUBLIC;
+            checkpoint              => PUBLIC;
+            deposit_rewards         => restrict_to: [reward_admin];
+            withdraw_rewards        => restrict_to: [reward_admin];
+            set_reward_rate         => restrict_to: [reward_admin];
+            claimable_rewards       => PUBLIC;
+            position                => PUBLIC;
+            reward_rate             => PUBLIC;
+            reward_per_lp           => PUBLIC;
+            available_rewards       => PUBLIC;
+            total_staked            => PUBLIC;
+            position_address        => PUBLIC;
+            pool_address            => PUBLIC;
+        }
+    }
+
+    struct LiquidityRewardsHook {
+        pool_address: Option<ComponentAddress>,
+        lp_vault: Option<Vault>,
+        reward_vault: Vault,
+        reward_divisibility: u8,
+        position_manager: NonFungibleResourceManager,
+        positions: KeyValueStore<NonFungibleLocalId, LiquidityPosition>,
+        reward_rate: Decimal,
+        reward_per_lp: PreciseDecimal,
+        reserved_rewards: Decimal,
+        lp_total_supply: Decimal,
+        total_staked: Decimal,
+        last_update: u64,
+    }
+
+    impl LiquidityRewardsHook {
+        /// Instantiates a new liquidity mining hook distributing `reward_address` tokens to LPs of a pool.
+        ///
+        /// Rewards are emitted at `reward_rate` tokens per second and are split across the pool's total LP supply
+        /// (`Pool::lp_total_supply`), i.e. every unit of liquidity accrues the same amount of rewards over time.
+        /// Only staked LP tokens, represented by a position NFT, can claim their share. Rewards attributable
+        /// to unstaked liquidity are not reserved and stay withdrawable by the reward admin.
+        ///
+        /// The reward accumulator is checkpointed on every swap and liquidity change in the pool via hooks,
+        /// as well as on every stake, unstake and claim.
+        ///
+        /// ## Arguments
+        /// - `reward_admin_badge`: Resource address of the badge allowed to fund the hook and set the reward rate.
+        /// - `reward_address`: Resource address of the reward token.
+        ///
+        /// ## Returns
+        /// - A tuple containing:
+        ///   - A global reference to the instantiated `LiquidityRewardsHook`.
+        ///   - The hook badge, which has to be passed to the pool on instantiation.
+        pub fn instantiate(
+            reward_admin_badge: ResourceAddress,
+            reward_address: ResourceAddress,
+        ) -> (Global<LiquidityRewardsHook>, FungibleBucket) {
+            let reward_divisibility = ResourceManager::from_address(reward_address)
+                .resource_type()
+                .divisibility()
+                .expect("[LiquidityRewardsHook]: Reward token must be fungible.");
+
+            let (address_reservation, component_address) =
+                Runtime::allocate_component_address(LiquidityRewardsHook::blueprint_id());
+            let component_rule = rule!(require(global_caller(component_address)));
+
+            let hook_badge = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init {
+                        "name" => "Liquidity Rewards Hook badge", locked;
+                    }
+                })
+                .mint_initial_supply(1);
+
+            let position_manager =
+                ResourceBuilder::new_ruid_non_fungible::<LiquidityPositionReceipt>(OwnerRole::None)
+                    .metadata(metadata! {
+                        init {
+                            "name" => "Ociswap Liquidity Mining Position", locked;
+                        }
+                    })
+                    .mint_roles(mint_roles!(
+                        minter => component_rule.clone();
+                        minter_updater => rule!(deny_all);
+                    ))
+                    .burn_roles(burn_roles!(
+                        burner => component_rule;
+                        burner_updater => rule!(deny_all);
+                    ))
+                    .create_with_no_initial_supply();
+
+            let hook_component = (Self {
+                pool_address: None,
+                lp_vault: None,
+                reward_vault: Vault::new(reward_address),
+                reward_divisibility,
+                position_manager,
+                positions: KeyValueStore::new(),
+                reward_rate: Decimal::ZERO,
+                reward_per_lp: PreciseDecimal::ZERO,
+                reserved_rewards: Decimal::ZERO,
+                lp_total_supply: Decimal::ZERO,
+                total_staked: Decimal::ZERO,
+                last_update: Clock::time_in_seconds(),
+            })
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::None)
+            .roles(roles!(
+                hook_admin => rule!(require(hook_badge.resource_address()));
+                reward_admin => rule!(require(reward_admin_badge));
+            ))
+            .with_address(address_reservation)
+            .globalize();
+
+            (hook_component, hook_badge)
+        }
+
+        pub fn calls(&self) -> Vec<HookCall> {
+            vec![
+                HookCall::AfterInstantiate,
+                HookCall::AfterSwap,
+                HookCall::BeforeAddLiquidity,
+                HookCall::AfterAddLiquidity,
+                HookCall::BeforeRemoveLiquidity,
+                HookCall::AfterRemoveLiquidity,
+            ]
+        }
+
+        /// Binds the hook to the pool it has been instantiated with.
+        pub fn after_instantiate(
+            &mut self,
+            state: AfterInstantiateState,
+        ) -> (AfterInstantiateState,) {
+            assert!(
+                self.pool_address.is_none(),
+                "[LiquidityRewardsHook]: Hook is already bound to a pool."
+            );
+            self.pool_address = Some(state.pool_address);
+            (state,)
+        }
+
+        pub fn after_swap(
+            &mut self,
+            after_swap_state: AfterSwapState,
+            output_bucket: Bucket,
+        ) -> (AfterSwapState, Bucket) {
+            self.assert_pool(after_swap_state.pool_address);
+            self.accrue();
+            (after_swap_state, output_bucket)
+        }
+
+        /// Accrues rewards with the LP supply before the liquidity change.
+        pub fn before_add_liquidity(
+            &mut self,
+            state: BeforeAddLiquidityState,
+        ) -> (BeforeAddLiquidityState,) {
+            self.assert_pool(state.pool_address);
+            self.lp_total_supply = state.lp_total_supply;
+            self.accrue();
+            (state,)
+        }
+
+        /// Continues accruing with the LP supply after the liquidity change.
+        pub fn after_add_liquidity(
+            &mut self,
+            state: AfterAddLiquidityState,
+        ) -> (AfterAddLiquidityState,) {
+            self.assert_pool(state.pool_address);
+            self.lp_total_supply = state.lp_total_supply;
+            (state,)
+        }
+
+        /// Accrues rewards with the LP supply before the liquidity change.
+        pub fn before_remove_liquidity(
+            &mut self,
+            state: BeforeRemoveLiquidityState,
+        ) -> (BeforeRemoveLiquidityState,) {
+            self.assert_pool(state.pool_address);
+            self.lp_total_supply = state.lp_total_supply;
+            self.accrue();
+            (state,)
+        }
+
+        /// Continues accruing with the LP supply after the liquidity change.
+        pub fn after_remove_liquidity(
+            &mut self,
+            state: AfterRemoveLiquidityState,
+        ) -> (AfterRemoveLiquidityState,) {
+            self.assert_pool(state.pool_address);
+            self.lp_total_supply = state.lp_total_supply;
+            (state,)
+        }
+
+        /// Stakes LP tokens of the pool, starting to accrue rewards for them.
+        ///
+        /// # Returns
+        /// * The position NFT required to claim rewards and unstake the LP tokens.
+        pub fn stake(&mut self, lp_tokens: Bucket) -> Bucket {
+            self.checkpoint();
+            let lp_amount = lp_tokens.amount();
+            assert!(
+                lp_amount > Decimal::ZERO,
+                "[LiquidityRewardsHook]: Nothing to stake."
+            );
+            self.lp_vault().put(lp_tokens);
+            self.total_staked += lp_amount;
+
+            let position_receipt =
+                self.position_manager
+                    .mint_ruid_non_fungible(LiquidityPositionReceipt {
+                        pool_address: self.pool_address.unwrap(),
+                    });
+            let position_id = position_receipt.non_fungible_local_id();
+            self.positions.insert(
+                position_id.clone(),
+                LiquidityPosition {
+                    lp_amount,
+                    reward_per_lp_checkpoint: self.reward_per_lp,
+                },
+            );
+
+            Runtime::emit_event(LiquidityStakeEvent {
+                position_id,
+                lp_amount,
+            });
+
+            position_receipt.into()
+        }
+
+        /// Unstakes a position, returning the LP tokens together with all unclaimed rewards.
+        ///
+        /// # Returns
+        /// A tuple containing:
+        /// * The staked LP tokens.
+        /// * The unclaimed rewards.
+        pub fn unstake(&mut self, position_receipt: NonFungibleBucket) -> (Bucket, Bucket) {
+            assert_eq!(
+                position_receipt.resource_address(),
+                self.position_manager.address(),
+                "[LiquidityRewardsHook]: Invalid position receipt."
+            );
+            self.checkpoint();
+            let position_id = position_receipt.non_fungible_local_id();
+            let rewards = self.take_rewards(&position_id);
+            let position = self.positions.remove(&position_id).unwrap();
+            self.total_staked -= position.lp_amount;
+            let lp_tokens = self.lp_vault().take(position.lp_amount);
+            self.position_manager.burn(position_receipt);
+
+            Runtime::emit_event(LiquidityUnstakeEvent {
+                position_id,
+                lp_amount: position.lp_amount,
+            });
+
+            (lp_tokens, rewards)
+        }
+
+        /// Claims all rewards accrued by a position so far, keeping the LP tokens staked.
+        pub fn claim(&mut self, position_proof: NonFungibleProof) -> Bucket {
+            let position_id = position_proof
+                .check(self.position_manager.address())
+                .non_fungible_local_id();
+            self.checkpoint();
+            self.take_rewards(&position_id)
+        }
+
+        /// Accrues rewards up to now and refreshes the LP total supply from the pool.
+        ///
+        /// The hook points keep the accumulator up to date for swaps and liquidity changes made via the pool.
+        /// LP tokens redeemed directly at the underlying native pool bypass the hooks, so this permissionless
+        /// method allows anyone to sync the LP supply.
+        pub fn checkpoint(&mut self) {
+            let pool_address = self
+                .pool_address
+                .expect("[LiquidityRewardsHook]: Hook is not bound to a pool yet.");
+            self.accrue();
+            let pool: Global<AnyComponent> = pool_address.into();
+            self.lp_total_supply = pool.call::<(), Decimal>("lp_total_supply", &());
+        }
+
+        /// Funds the hook with reward tokens. Only callable by the reward admin.
+        pub fn deposit_rewards(&mut self, rewards: Bucket) {
+            self.accrue();
+            self.reward_vault.put(rewards);
+        }
+
+        /// Withdraws reward tokens which are not reserved for stakers yet. Only callable by the reward admin.
+        pub fn withdraw_rewards(&mut self, amount: Decimal) -> Bucket {
+            self.accrue();
+            assert!(
+                amount <= self.available_rewards(),
+                "[LiquidityRewardsHook]: Rewards are reserved for stakers."
+            );
+            self.reward_vault.take(amount)
+        }
+
+        /// Sets the reward tokens emitted per second across the whole LP supply. Only callable by the reward admin.
+        /// Rewards accrued with the previous rate are checkpointed before the update.
+        pub fn set_reward_rate(&mut self, reward_rate: Decimal) {
+            assert!(
+                reward_rate >= Decimal::ZERO,
+                "[LiquidityRewardsHook]: Reward rate must not be negative."
+            );
+            self.accrue();
+            self.reward_rate = reward_rate;
+            Runtime::emit_event(RewardRateUpdateEvent { reward_rate });
+        }
+
+        /// Returns the rewards claimable by a position as of the last checkpoint.
+        pub fn claimable_rewards(&self, position_id: NonFungibleLocalId) -> Decimal {
+            let position = self
+                .positions
+                .get(&position_id)
+                .expect("[LiquidityRewardsHook]: Position does not exist.");
+            self.pending_rewards(&position)
+        }
+
+        pub fn position(&self, position_id: NonFungibleLocalId) -> Option<LiquidityPosition> {
+            self.positions
+                .get(&position_id)
+                .map(|position| position.clone())
+        }
+
+        pub fn reward_rate(&self) -> Decimal {
+            self.reward_rate
+        }
+
+        pub fn reward_per_lp(&self) -> PreciseDecimal {
+            self.reward_per_lp
+        }
+
+        /// Returns the reward tokens which are not reserved for stakers.
+        pub fn available_rewards(&self) -> Decimal {
+            self.reward_vault.amount() - self.reserved_rewards
+        }
+
+        pub fn total_staked(&self) -> Decimal {
+            self.total_staked
+        }
+
+        pub fn position_address(&self) -> ResourceAddress {
+            self.position_manager.address()
+        }
+
+        pub fn pool_address(&self) -> Option<ComponentAddress> {
+            self.pool_address
+        }
+
+        /// Advances the reward accumulator to the current time.
+        ///
+        /// The rewards emitted since the last update are split across the LP total supply. Only the share of the
+        /// staked LP tokens is reserved, capped by the available reward budget.
+        fn accrue(&mut self) {
+            let now = Clock::time_in_seconds();
+            let elapsed = now.saturating_sub(self.last_update);
+            self.last_update = now;
+            if elapsed == 0
+                || self.lp_total_supply == Decimal::ZERO
+                || self.total_staked == Decimal::ZERO
+            {
+                return;
+            }
+
+            let lp_total_supply = PreciseDecimal::from(self.lp_total_supply);
+            let total_staked = PreciseDecimal::from(self.total_staked);
+            let available_rewards = PreciseDecimal::from(self.available_rewards());
+
+            let mut emitted =
+                PreciseDecimal::from(self.reward_rate) * PreciseDecimal::from(elapsed);
+            let mut reserved = emitted * total_staked / lp_total_supply;
+            if reserved > available_rewards {
+                reserved = available_rewards;
+                emitted = available_rewards * lp_total_supply / total_staked;
+            }
+
+            self.reward_per_lp += emitted / lp_total_supply;
+            self.reserved_rewards += reserved.ceil_to(self.reward_divisibility);
+        }
+
+        fn pending_rewards(&self, position: &LiquidityPosition) -> Decimal {
+            (PreciseDecimal::from(position.lp_amount)
+                * (self.reward_per_lp - position.reward_per_lp_checkpoint))
+                .floor_to(self.reward_divisibility)
+        }
+
+        /// Takes the pending rewards of a position out of the reward vault and moves its checkpoint to now.
+        fn take_rewards(&mut self, position_id: &NonFungibleLocalId) -> Bucket {
+            let rewards_amount = self.claimable_rewards(position_id.clone());
+            let mut position = self.positions.get_mut(position_id).unwrap();
+            position.reward_per_lp_checkpoint = self.reward_per_lp;
+            drop(position);
+
+            self.reserved_rewards = (self.reserved_rewards - rewards_amount).max(Decimal::ZERO);
+            let rewards = self.reward_vault.take(rewards_amount);
+
+            Runtime::emit_event(RewardsClaimEvent {
+                position_id: position_id.clone(),
+                amount: rewards_amount,
+            });
+
+            rewards
+        }
+
+        fn lp_vault(&mut self) -> &mut Vault {
+            if self.lp_vault.is_none() {
+                let pool: Global<AnyComponent> = self
+                    .pool_address
+                    .expect("[LiquidityRewardsHook]: Hook is not bound to a pool yet.")
+                    .into();
+                let lp_address = pool.call::<(), ResourceAddress>("lp_address", &());
+                self.lp_vault = Some(Vault::new(lp_address));
+            }
+            self.lp_vault.as_mut().unwrap()
+        }
+
+        fn assert_pool(&self, pool_address: ComponentAddress) {
+            assert_eq!(
+                Some(pool_address),
+                self.pool_address,
+                "[LiquidityRewardsHook]: Call originates from an unknown pool."
+            );
+        }
+    }
+}
+
+#[derive(ScryptoSbor, NonFungibleData)]
+pub struct LiquidityPositionReceipt {
+    pub pool_address: ComponentAddress,
+}
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct LiquidityPosition {
+    pub lp_amount: Decimal,
+    pub reward_per_lp_checkpoint: PreciseDecimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct LiquidityStakeEvent {
+    position_id: NonFungibleLocalId,
+    lp_amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct LiquidityUnstakeEvent {
+    position_id: NonFungibleLocalId,
+    lp_amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct RewardsClaimEvent {
+    position_id: NonFungibleLocalId,
+    amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct RewardRateUpdateEvent {
+    reward_rate: Decimal,
+}
@@ -0,0 +1,276 @@
+use common::math::DivisibilityRounding;
+use ociswap_pool_hooks::constants::HOOKS_MIN_REMAINING_BUCKET_FRACTION;
+use ociswap_pool_hooks::*;
+use scrypto::prelude::*;
+
+#[blueprint]
+#[events(ReferralFeeEvent, ReferralPayoutEvent, ReferralFeeRateUpdateEvent)]
+mod referral_hook {
+    enable_method_auth! {
+        roles {
+            hook_admin => updatable_by: [];
+        },
+        methods {
+            calls                   => PUBLIC;
+            after_instantiate       => restrict_to: [hook_admin];
+            after_swap              => restrict_to: [hook_admin];
+            payout                  => PUBLIC;
+            claim                   => PUBLIC;
+            set_referral_fee_rate   => restrict_to: [OWNER];
+            referral_fee_rate       => PUBLIC;
+            referrer_account        => PUBLIC;
+            pool_address            => PUBLIC;
+        }
+    }
+
+    struct ReferralHook {
+        pool_address: Option<ComponentAddress>,
+        x_vault: Vault,
+        y_vault: Vault,
+        referral_fee_rate: Decimal,
+        referrers: KeyValueStore<ComponentAddress, ReferrerAccount>,
+    }
+
+    impl ReferralHook {
+        /// Instantiates a new referral hook.
+        ///
+        /// Swaps made via the pool's `swap_with_referrer` method pay `referral_fee_rate` of their output to the referrer.
+        /// The fees are accounted per referrer and kept in the hook until they are paid out.
+        /// Swaps without a referrer are not affected.
+        ///
+        /// ## Arguments
+        /// - `owner_badge`: Resource address of the badge allowed to change the referral fee rate.
+        /// - `x_address`: ResourceAddress of token X of the pool.
+        /// - `y_address`: ResourceAddress of token Y of the pool.
+        /// - `referral_fee_rate`: Fraction of the swap output paid to the referrer.
+        ///
+        /// ## Returns
+        /// - A tuple containing:
+        ///   - A global reference to the instantiated `ReferralHook`.
+        ///   - The hook badge, which has to be passed to the pool on instantiation.
+        ///
+        /// ## Panics
+        /// - If `referral_fee_rate` is not within `[0, 1 - HOOKS_MIN_REMAINING_BUCKET_FRACTION]`.
+        pub fn instantiate(
+            owner_badge: ResourceAddress,
+            x_address: ResourceAddress,
+            y_address: ResourceAddress,
+            referral_fee_rate: Decimal,
+        ) -> (Global<ReferralHook>, FungibleBucket) {
+            assert_referral_fee_rate_is_valid(referral_fee_rate);
+
+            let hook_badge = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata(metadata! {
+                    init {
+                        "name" => "Referral Hook badge", locked;
+                    }
+                })
+                .mint_initial_supply(1);
+
+            let hook_component = (Self {
+                pool_address: None,
+                x_vault: Vault::new(x_address),
+                y_vault: Vault::new(y_address),
+                referral_fee_rate,
+                referrers: KeyValueStore::new(),
+            })
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::Fixed(rule!(require(owner_badge))))
+            .roles(roles!(
+                hook_admin => rule!(require(hook_badge.resource_address()));
+            ))
+            .globalize();
+
+            (hook_component, hook_badge)
+        }
+
+        pub fn calls(&self) -> Vec<HookCall> {
+            vec![HookCall::AfterInstantiate, HookCall::AfterSwap]
+        }
+
+        /// Binds the hook to the pool it has been instantiated with.
+        pub fn after_instantiate(
+            &mut self,
+            state: AfterInstantiateState,
+        ) -> (AfterInstantiateState,) {
+            assert!(
+                self.pool_address.is_none(),
+                "[ReferralHook]: Hook is already bound to a pool."
+            );
+            assert!(
+                state.x_address == self.x_vault.resource_address()
+                    && state.y_address == self.y_vault.resource_address(),
+                "[ReferralHook]: Pool tokens do not match the hook tokens."
+            );
+            self.pool_address = Some(state.pool_address);
+            (state,)
+        }
+
+        /// Diverts the referral fee from the swap output to the referrer's account in the hook.
+        pub fn after_swap(
+            &mut self,
+            after_swap_state: AfterSwapState,
+            mut output_bucket: Bucket,
+        ) -> (AfterSwapState, Bucket) {
+            assert_eq!(
+                Some(after_swap_state.pool_address),
+                self.pool_address,
+                "[ReferralHook]: Swap originates from an unknown pool."
+            );
+            let referrer = match after_swap_state.referrer {
+                Some(referrer) => referrer,
+                None => return (after_swap_state, output_bucket),
+            };
+
+            let divisibility = ResourceManager::from_address(output_bucket.resource_address())
+                .resource_type()
+                .divisibility()
+                .unwrap();
+            let fee_amount =
+                (output_bucket.amount() * self.referral_fee_rate).floor_to(divisibility);
+            if fee_amount == Decimal::ZERO {
+                return (after_swap_state, output_bucket);
+            }
+
+            let resource_address = output_bucket.resource_address();
+            self.vault(resource_address)
+                .put(output_bucket.take(fee_amount));
+            let is_x = resource_address == self.x_vault.resource_address();
+            if self.referrers.get(&referrer).is_none() {
+                self.referrers.insert(referrer, ReferrerAccount::default());
+            }
+            self.referrers
+                .get_mut(&referrer)
+                .unwrap()
+                .credit(is_x, fee_amount);
+
+            Runtime::emit_event(ReferralFeeEvent {
+                referrer,
+                resource_address,
+                amount: fee_amount,
+            });
+
+            (after_swap_state, output_bucket)
+        }
+
+        /// Pays out all accumulated fees of an account referrer. Callable by anyone.
+        ///
+        /// # Panics
+        /// * If the referrer is not an account or rejects the deposit.
+        pub fn payout(&mut self, referrer: ComponentAddress) {
+            let fees = self.take_fees(referrer);
+            let mut account: Global<Account> = referrer.into();
+            account.try_deposit_batch_or_abort(fees, None);
+        }
+
+        /// Claims all accumulated fees of a referrer component. Only callable by the referrer itself,
+        /// which allows referrers that are not accounts to collect their fees.
+        ///
+        /// # Returns
+        /// A vector containing the fees in token X and Y.
+        pub fn claim(&mut self, referrer: ComponentAddress) -> Vec<Bucket> {
+            Runtime::assert_access_rule(rule!(require(global_caller(referrer))));
+            self.take_fees(referrer)
+        }
+
+        /// Sets the fraction of the swap output paid to referrers. Only callable by the owner.
+        ///
+        /// # Panics
+        /// * If `referral_fee_rate` is not within `[0, 1 - HOOKS_MIN_REMAINING_BUCKET_FRACTION]`.
+        pub fn set_referral_fee_rate(&mut self, referral_fee_rate: Decimal) {
+            assert_referral_fee_rate_is_valid(referral_fee_rate);
+            self.referral_fee_rate = referral_fee_rate;
+            Runtime::emit_event(ReferralFeeRateUpdateEvent { referral_fee_rate });
+        }
+
+        pub fn referral_fee_rate(&self) -> Decimal {
+            self.referral_fee_rate
+        }
+
+        pub fn referrer_account(&self, referrer: ComponentAddress) -> Option<ReferrerAccount> {
+            self.referrers.get(&referrer).map(|account| account.clone())
+        }
+
+        pub fn pool_address(&self) -> Option<ComponentAddress> {
+            self.pool_address
+        }
+
+        fn take_fees(&mut self, referrer: ComponentAddress) -> Vec<Bucket> {
+            let mut account = self
+                .referrers
+                .get_mut(&referrer)
+                .expect("[ReferralHook]: Unknown referrer.");
+            let (x_amount, y_amount) = (account.x_claimable, account.y_claimable);
+            account.x_claimable = Decimal::ZERO;
+            account.y_claimable = Decimal::ZERO;
+            drop(account);
+
+            Runtime::emit_event(ReferralPayoutEvent {
+                referrer,
+                x_amount,
+                y_amount,
+            });
+
+            vec![self.x_vault.take(x_amount), self.y_vault.take(y_amount)]
+        }
+
+        fn vault(&mut self, resource_address: ResourceAddress) -> &mut Vault {
+            if resource_address == self.x_vault.resource_address() {
+                &mut self.x_vault
+            } else {
+                &mut self.y_vault
+            }
+        }
+    }
+}
+
+/// Fees accumulated by a referrer. The claimable amounts are reset on payout,
+/// the earned amounts keep track of all fees ever credited.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, Default, PartialEq)]
+pub struct ReferrerAccount {
+    pub x_claimable: Decimal,
+    pub y_claimable: Decimal,
+    pub x_earned: Decimal,
+    pub y_earned: Decimal,
+}
+
+impl ReferrerAccount {
+    fn credit(&mut self, is_x: bool, amount: Decimal) {
+        if is_x {
+            self.x_claimable += amount;
+            self.x_earned += amount;
+        } else {
+            self.y_claimable += amount;
+            self.y_earned += amount;
+        }
+    }
+}
+
+/// Asserts that the referral fee keeps the output within the limit hooks may take from a swap.
+pub fn assert_referral_fee_rate_is_valid(referral_fee_rate: Decimal) {
+    assert!(
+        referral_fee_rate >= Decimal::ZERO
+            && referral_fee_rate <= Decimal::ONE - HOOKS_MIN_REMAINING_BUCKET_FRACTION,
+        "[ReferralHook]: Referral fee rate must be between 0 and 1 - HOOKS_MIN_REMAINING_BUCKET_FRACTION."
+    );
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct ReferralFeeEvent {
+    referrer: ComponentAddress,
+    resource_address: ResourceAddress,
+    amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct ReferralPayoutEvent {
+    referrer: ComponentAddress,
+    x_amount: Decimal,
+    y_amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct ReferralFeeRateUpdateEvent {
+    referral_fee_rate: Decimal,
+}
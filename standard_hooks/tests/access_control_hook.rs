@@ -0,0 +1,180 @@
+mod helper;
+
+#[cfg(test)]
+mod test_access_control_hook {
+    use super::*;
+    use helper::*;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+
+    struct Credentials {
+        allowed: ResourceAddress,
+        other: ResourceAddress,
+    }
+
+    fn instantiate_helper() -> (StandardHookTestHelper, Credentials) {
+        let mut helper = StandardHookTestHelper::new();
+        let credentials = Credentials {
+            allowed: helper.create_credential_resource_execute(),
+            other: helper.create_credential_resource_execute(),
+        };
+        helper.instantiate_access_control_hook(indexset!(credentials.allowed));
+        helper.instantiate_hook_execute();
+        helper.instantiate_pool_with_hook();
+        helper.pool.add_liquidity_with_credential(
+            helper.pool.x_address(),
+            dec!(10000),
+            helper.pool.y_address(),
+            dec!(10000),
+            credentials.allowed,
+            NonFungibleLocalId::integer(1),
+        );
+        helper.execute_expect_success(false);
+        (helper, credentials)
+    }
+
+    #[test]
+    fn test_swap_without_credential() {
+        let (mut helper, _) = instantiate_helper();
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_with_credential() {
+        let (mut helper, credentials) = instantiate_helper();
+        helper.pool.swap_with_credential(
+            helper.pool.x_address(),
+            dec!(1),
+            credentials.allowed,
+            NonFungibleLocalId::integer(2),
+        );
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_swap_with_unknown_credential() {
+        let (mut helper, credentials) = instantiate_helper();
+        helper.pool.swap_with_credential(
+            helper.pool.x_address(),
+            dec!(1),
+            credentials.other,
+            NonFungibleLocalId::integer(1),
+        );
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_with_allowlisted_credential() {
+        let (mut helper, credentials) = instantiate_helper();
+        helper.create_owner_proof();
+        helper.allow_credential(NonFungibleGlobalId::new(
+            credentials.other,
+            NonFungibleLocalId::integer(1),
+        ));
+        helper.execute_expect_success(false);
+
+        helper.pool.swap_with_credential(
+            helper.pool.x_address(),
+            dec!(1),
+            credentials.other,
+            NonFungibleLocalId::integer(1),
+        );
+        helper.execute_expect_success(false);
+
+        helper.pool.swap_with_credential(
+            helper.pool.x_address(),
+            dec!(1),
+            credentials.other,
+            NonFungibleLocalId::integer(2),
+        );
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_with_revoked_credential() {
+        let (mut helper, credentials) = instantiate_helper();
+        let credential =
+            NonFungibleGlobalId::new(credentials.other, NonFungibleLocalId::integer(1));
+        helper.create_owner_proof();
+        helper.allow_credential(credential.clone());
+        helper.execute_expect_success(false);
+        helper.create_owner_proof();
+        helper.deny_credential(credential);
+        helper.execute_expect_success(false);
+
+        helper.pool.swap_with_credential(
+            helper.pool.x_address(),
+            dec!(1),
+            credentials.other,
+            NonFungibleLocalId::integer(1),
+        );
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_with_denied_credential_resource() {
+        let (mut helper, credentials) = instantiate_helper();
+        helper.create_owner_proof();
+        helper.deny_credential_resource(credentials.allowed);
+        helper.execute_expect_success(false);
+
+        helper.pool.swap_with_credential(
+            helper.pool.x_address(),
+            dec!(1),
+            credentials.allowed,
+            NonFungibleLocalId::integer(1),
+        );
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_allow_credential_without_owner_badge() {
+        let (mut helper, credentials) = instantiate_helper();
+        helper.allow_credential(NonFungibleGlobalId::new(
+            credentials.other,
+            NonFungibleLocalId::integer(1),
+        ));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_add_liquidity_without_credential() {
+        let (mut helper, _) = instantiate_helper();
+        helper.pool.add_liquidity_default(dec!(10), dec!(10));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_add_liquidity_with_unknown_credential() {
+        let (mut helper, credentials) = instantiate_helper();
+        helper.pool.add_liquidity_with_credential(
+            helper.pool.x_address(),
+            dec!(10),
+            helper.pool.y_address(),
+            dec!(10),
+            credentials.other,
+            NonFungibleLocalId::integer(1),
+        );
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_remove_liquidity_without_credential() {
+        let (mut helper, _) = instantiate_helper();
+        helper.pool.remove_liquidity_default(dec!(1));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_remove_liquidity_with_credential() {
+        let (mut helper, credentials) = instantiate_helper();
+        helper.pool.remove_liquidity_with_credential(
+            helper.pool.lp_address.unwrap(),
+            dec!(1),
+            credentials.allowed,
+            NonFungibleLocalId::integer(1),
+        );
+        helper.execute_expect_success(false);
+    }
+}
@@ -0,0 +1,144 @@
+mod helper;
+
+#[cfg(test)]
+mod test_circuit_breaker_hook {
+    use super::*;
+    use helper::*;
+    use pretty_assertions::assert_eq;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+    use standard_hooks::circuit_breaker_hook::{price_deviation, CircuitBreakerParameters};
+
+    fn instantiate_helper() -> StandardHookTestHelper {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_circuit_breaker_hook(circuit_breaker_parameters());
+        helper.instantiate_hook_execute();
+        helper.instantiate_pool_with_hook();
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10000), dec!(10000));
+        helper
+    }
+
+    /// Creates oracle history at a price of 1 and sets it as reference price.
+    fn instantiate_helper_with_reference() -> StandardHookTestHelper {
+        let mut helper = instantiate_helper();
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+        helper.pool.swap(helper.pool.y_address(), dec!(1));
+        helper.execute_expect_success(false);
+        helper.pool.advance_timestamp_by_seconds(120);
+        helper.refresh_reference().execute_expect_success(false);
+        helper
+    }
+
+    #[test]
+    fn test_instantiate_zero_max_deviation() {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_circuit_breaker_hook(CircuitBreakerParameters {
+            max_deviation: dec!(0),
+            ..circuit_breaker_parameters()
+        });
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_without_reference() {
+        let mut helper = instantiate_helper();
+        helper.pool.swap(helper.pool.y_address(), dec!(1000));
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_refresh_reference_without_history() {
+        let mut helper = instantiate_helper();
+        let receipt = helper.refresh_reference().execute_expect_success(false);
+        let outputs: Vec<Option<PreciseDecimal>> = receipt.outputs("refresh_reference");
+
+        assert_eq!(outputs, vec![None]);
+    }
+
+    #[test]
+    fn test_swap_within_bound() {
+        let mut helper = instantiate_helper_with_reference();
+        // moves the price by ~8%
+        helper.pool.swap(helper.pool.y_address(), dec!(400));
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_swap_beyond_bound() {
+        let mut helper = instantiate_helper_with_reference();
+        // moves the price by ~21%
+        helper.pool.swap(helper.pool.y_address(), dec!(1000));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_refresh_reference_trips() {
+        let mut helper = instantiate_helper_with_reference();
+        helper.pool.swap(helper.pool.y_address(), dec!(400));
+        helper.execute_expect_success(false);
+
+        helper.create_owner_proof();
+        helper.set_circuit_breaker_parameters(CircuitBreakerParameters {
+            max_deviation: dec!(0.05),
+            ..circuit_breaker_parameters()
+        });
+        helper.execute_expect_success(false);
+        helper.refresh_reference().execute_expect_success(false);
+        assert!(helper.is_halted());
+
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_cooldown_resumes_swaps() {
+        let mut helper = instantiate_helper_with_reference();
+        helper.create_owner_proof();
+        helper.hook_getter("halt");
+        helper.execute_expect_success(false);
+
+        helper.pool.advance_timestamp_by_seconds(299);
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_failure(false);
+
+        helper.pool.advance_timestamp_by_seconds(1);
+        assert!(!helper.is_halted());
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_guardian_halt_and_resume() {
+        let mut helper = instantiate_helper();
+        helper.create_owner_proof();
+        helper.hook_getter("halt");
+        helper.execute_expect_success(false);
+
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_failure(false);
+
+        helper.create_owner_proof();
+        helper.hook_getter("resume");
+        helper.execute_expect_success(false);
+
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_halt_without_guardian_badge() {
+        let mut helper = instantiate_helper();
+        helper.hook_getter("halt");
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_price_deviation() {
+        assert_eq!(price_deviation(pdec!(1), pdec!(1)), dec!(0));
+        assert_eq!(price_deviation(pdec!(1.1), pdec!(1)), dec!(0.21));
+        assert_eq!(price_deviation(pdec!(0.9), pdec!(1)), dec!(0.19));
+    }
+}
@@ -0,0 +1,208 @@
+mod helper;
+
+#[cfg(test)]
+mod test_dynamic_fee_hook {
+    use super::*;
+    use helper::*;
+    use ociswap_pool_test_helper::{
+        Actor, AttoDecimal, Simulation, SimulationConfig, SimulationStep,
+    };
+    use pretty_assertions::assert_eq;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+    use standard_hooks::dynamic_fee_hook::DynamicFeeParameters;
+
+    fn instantiate_helper(fee_parameters: DynamicFeeParameters) -> StandardHookTestHelper {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_dynamic_fee_hook(fee_parameters);
+        helper.instantiate_hook_execute();
+        helper.instantiate_pool_with_hook();
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10000), dec!(10000));
+        helper
+    }
+
+    fn swap_back_and_forth(helper: &mut StandardHookTestHelper, amount: Decimal) {
+        helper.pool.swap(helper.pool.x_address(), amount);
+        helper.execute_expect_success(false);
+        helper.pool.advance_timestamp_by_seconds(900);
+        helper.pool.swap(helper.pool.y_address(), amount);
+        helper.execute_expect_success(false);
+        helper.pool.advance_timestamp_by_seconds(900);
+    }
+
+    #[test]
+    fn test_instantiate_invalid_fee_ceiling() {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_dynamic_fee_hook(DynamicFeeParameters {
+            fee_ceiling: dec!(0.1) + Decimal::ATTO,
+            ..dynamic_fee_parameters()
+        });
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_instantiate_floor_above_ceiling() {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_dynamic_fee_hook(DynamicFeeParameters {
+            fee_floor: dec!(0.06),
+            ..dynamic_fee_parameters()
+        });
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_instantiate_single_interval() {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_dynamic_fee_hook(DynamicFeeParameters {
+            intervals: 1,
+            ..dynamic_fee_parameters()
+        });
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_applies_fee_floor() {
+        let mut helper = instantiate_helper(dynamic_fee_parameters());
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        let receipt = helper.execute_expect_success(false);
+
+        assert_eq!(
+            StandardHookTestHelper::swap_input_fee_rate(&receipt),
+            dec!(0.001)
+        );
+        // The hook overrides the input fee rate per swap, the base rate of the pool is unchanged.
+        assert_eq!(helper.pool_input_fee_rate(), dec!(0));
+    }
+
+    #[test]
+    fn test_update_without_history_keeps_fee_floor() {
+        let mut helper = instantiate_helper(dynamic_fee_parameters());
+        let receipt = helper.update_input_fee_rate().execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("update_input_fee_rate");
+
+        assert_eq!(outputs, vec![dec!(0.001)]);
+    }
+
+    #[test]
+    fn test_update_stable_price_keeps_fee_floor() {
+        let mut helper = instantiate_helper(dynamic_fee_parameters());
+        for _ in 0..3 {
+            swap_back_and_forth(&mut helper, Decimal::ATTO);
+        }
+        let receipt = helper.update_input_fee_rate().execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("update_input_fee_rate");
+
+        assert_eq!(outputs, vec![dec!(0.001)]);
+    }
+
+    #[test]
+    fn test_update_volatile_price_raises_fee() {
+        let mut helper = instantiate_helper(dynamic_fee_parameters());
+        for _ in 0..3 {
+            swap_back_and_forth(&mut helper, dec!(1000));
+        }
+        let receipt = helper.update_input_fee_rate().execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("update_input_fee_rate");
+        let input_fee_rate = outputs[0];
+
+        assert!(input_fee_rate > dec!(0.001), "{:?}", input_fee_rate);
+        assert!(input_fee_rate <= dec!(0.05), "{:?}", input_fee_rate);
+
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        let receipt = helper.execute_expect_success(false);
+        assert_eq!(
+            StandardHookTestHelper::swap_input_fee_rate(&receipt),
+            input_fee_rate
+        );
+        assert_eq!(helper.pool_input_fee_rate(), dec!(0));
+    }
+
+    #[test]
+    fn test_update_extreme_volatility_caps_at_fee_ceiling() {
+        let mut helper = instantiate_helper(DynamicFeeParameters {
+            volatility_ceiling: dec!(0.0001),
+            ..dynamic_fee_parameters()
+        });
+        for _ in 0..3 {
+            swap_back_and_forth(&mut helper, dec!(1000));
+        }
+        let receipt = helper.update_input_fee_rate().execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("update_input_fee_rate");
+
+        assert_eq!(outputs, vec![dec!(0.05)]);
+    }
+
+    #[test]
+    fn test_set_fee_parameters_clamps_current_fee() {
+        let mut helper = instantiate_helper(dynamic_fee_parameters());
+        helper.create_owner_proof();
+        helper.set_fee_parameters(DynamicFeeParameters {
+            fee_floor: dec!(0.003),
+            ..dynamic_fee_parameters()
+        });
+        helper.execute_expect_success(false);
+
+        let receipt = helper
+            .hook_getter("input_fee_rate")
+            .execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("input_fee_rate");
+
+        assert_eq!(outputs, vec![dec!(0.003)]);
+    }
+
+    #[test]
+    fn test_set_fee_parameters_without_owner_badge() {
+        let mut helper = instantiate_helper(dynamic_fee_parameters());
+        helper.set_fee_parameters(DynamicFeeParameters {
+            fee_floor: dec!(0.003),
+            ..dynamic_fee_parameters()
+        });
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_set_fee_parameters_above_input_fee_rate_max() {
+        let mut helper = instantiate_helper(dynamic_fee_parameters());
+        helper.create_owner_proof();
+        helper.set_fee_parameters(DynamicFeeParameters {
+            fee_ceiling: dec!(0.2),
+            ..dynamic_fee_parameters()
+        });
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_simulation_fee_rises_with_volatility() {
+        let mut helper = instantiate_helper(dynamic_fee_parameters());
+        let hook_address = helper.hook_address.unwrap();
+        let mut simulation = Simulation::new(
+            &mut helper.pool,
+            SimulationConfig {
+                seed: 3,
+                steps: 8,
+                step_seconds: 900,
+                reference_price: dec!(1),
+                reference_volatility: 0.05,
+            },
+            vec![
+                Actor::Keeper {
+                    component_address: hook_address,
+                    method: "update_input_fee_rate".to_string(),
+                },
+                Actor::Arbitrageur { min_deviation: 0.0 },
+            ],
+        );
+        let steps = simulation.run().to_vec();
+
+        // Prices stay close to 1, so amounts of both tokens can be added up
+        let (first, last) = (steps.first().unwrap(), steps.last().unwrap());
+        let fees = |step: &SimulationStep| {
+            step.x_fees_lp + step.y_fees_lp + step.x_fees_protocol + step.y_fees_protocol
+        };
+        let volume = |step: &SimulationStep| step.x_volume + step.y_volume;
+        let fee_rate = (fees(last) - fees(first)) / (volume(last) - volume(first));
+        assert!(fee_rate > dynamic_fee_parameters().fee_floor);
+    }
+}
@@ -0,0 +1,697 @@
+#![allow(dead_code)]
+
+use ociswap_pool_events::SwapEvent;
+use ociswap_pool_test_helper::*;
+use radix_engine::system::system_modules::execution_trace::ResourceSpecifier;
+use radix_transactions::prelude::ManifestBuilder;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+use standard_hooks::circuit_breaker_hook::CircuitBreakerParameters;
+use standard_hooks::dynamic_fee_hook::DynamicFeeParameters;
+use standard_hooks::limit_order_hook::LimitOrder;
+use standard_hooks::referral_hook::ReferrerAccount;
+use std::mem;
+
+pub struct StandardHookTestHelper {
+    pub pool: PoolTestHelper,
+
+    pub hook_address: Option<ComponentAddress>,
+    pub hook_badge_address: Option<ResourceAddress>,
+    pub order_address: Option<ResourceAddress>,
+    pub fill_ticket_address: Option<ResourceAddress>,
+    pub position_address: Option<ResourceAddress>,
+}
+
+impl TestHelperExecution for StandardHookTestHelper {
+    fn env(&mut self) -> &mut TestEnvironment {
+        &mut self.pool.registry.env
+    }
+}
+
+impl StandardHookTestHelper {
+    pub fn new() -> StandardHookTestHelper {
+        let packages: HashMap<&str, &str> = vec![
+            ("registry", "../registry"),
+            ("pool", ".."),
+            ("standard_hooks", "."),
+        ]
+        .into_iter()
+        .collect();
+        let pool = PoolTestHelper::new_with_packages(packages, true);
+
+        Self {
+            pool,
+            hook_address: None,
+            hook_badge_address: None,
+            order_address: None,
+            fill_ticket_address: None,
+            position_address: None,
+        }
+    }
+
+    /// Executes the pending hook instantiation and stores the hook component and badge address.
+    /// The hook badge is expected to be the first newly created resource of the transaction.
+    pub fn instantiate_hook_execute(&mut self) -> (ComponentAddress, ResourceAddress) {
+        let receipt = self.execute_expect_success(false);
+        let commit_result = receipt.execution_receipt.expect_commit_success();
+        let hook_address = commit_result.new_component_addresses()[0];
+        let hook_badge_address = commit_result.new_resource_addresses()[0];
+        self.hook_address = Some(hook_address);
+        self.hook_badge_address = Some(hook_badge_address);
+        (hook_address, hook_badge_address)
+    }
+
+    pub fn instantiate_pool_with_hook(&mut self) -> &mut StandardHookTestHelper {
+        let hook = (self.hook_address.unwrap(), self.hook_badge_address.unwrap());
+        self.pool.set_whitelist_registry();
+        self.pool.set_whitelist_hook("standard_hooks");
+        self.pool.instantiate_default_with_hooks(vec![hook], false);
+        self
+    }
+
+    pub fn hook_getter(&mut self, name: &str) -> &mut StandardHookTestHelper {
+        let hook_address = self.hook_address.unwrap();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder =
+            manifest_builder.call_method(hook_address, name, manifest_args!());
+        self.env().new_instruction(name, 1, 0);
+        self
+    }
+
+    pub fn create_owner_proof(&mut self) -> &mut StandardHookTestHelper {
+        let account = self.env().account;
+        let admin_badge_address = self.pool.admin_badge_address();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder.create_proof_from_account_of_amount(
+            account,
+            admin_badge_address,
+            dec!(1),
+        );
+        self.env().new_instruction("create_owner_proof", 1, 0);
+        self
+    }
+
+    pub fn pool_input_fee_rate(&mut self) -> Decimal {
+        let receipt = self
+            .pool
+            .input_fee_rate()
+            .registry
+            .execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("input_fee_rate");
+        outputs[0]
+    }
+
+    /// Returns the input fee rate charged by the swap of the receipt, derived from its `SwapEvent`.
+    pub fn swap_input_fee_rate(receipt: &Receipt) -> Decimal {
+        let event: SwapEvent = receipt
+            .execution_receipt
+            .expect_commit_success()
+            .application_events
+            .iter()
+            .find(|(EventTypeIdentifier(_, name), _)| name == "SwapEvent")
+            .map(|(_, payload)| scrypto_decode(payload).unwrap())
+            .expect("No SwapEvent emitted");
+        (event.input_fee_lp + event.input_fee_protocol) / event.input_gross_amount
+    }
+
+    // DYNAMIC FEE HOOK
+
+    pub fn instantiate_dynamic_fee_hook(
+        &mut self,
+        fee_parameters: DynamicFeeParameters,
+    ) -> &mut StandardHookTestHelper {
+        let package_address = self.env().package_address("standard_hooks");
+        let owner_badge = self.pool.admin_badge_address();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "DynamicFeeHook",
+            "instantiate",
+            manifest_args!(owner_badge, fee_parameters),
+        );
+        self.env().new_instruction("instantiate", 1, 0);
+        self
+    }
+
+    pub fn update_input_fee_rate(&mut self) -> &mut StandardHookTestHelper {
+        self.hook_getter("update_input_fee_rate")
+    }
+
+    pub fn set_fee_parameters(
+        &mut self,
+        fee_parameters: DynamicFeeParameters,
+    ) -> &mut StandardHookTestHelper {
+        let hook_address = self.hook_address.unwrap();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder.call_method(
+            hook_address,
+            "set_fee_parameters",
+            manifest_args!(fee_parameters),
+        );
+        self.env().new_instruction("set_fee_parameters", 1, 0);
+        self
+    }
+
+    // LIMIT ORDER HOOK
+
+    pub fn instantiate_limit_order_hook(&mut self) -> &mut StandardHookTestHelper {
+        let package_address = self.env().package_address("standard_hooks");
+        let x_address = self.pool.x_address();
+        let y_address = self.pool.y_address();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "LimitOrderHook",
+            "instantiate",
+            manifest_args!(x_address, y_address),
+        );
+        self.env().new_instruction("instantiate", 1, 0);
+        self
+    }
+
+    /// Instantiates the limit order hook and stores the addresses of the order and fill ticket resources.
+    pub fn instantiate_limit_order_hook_execute(&mut self) {
+        self.instantiate_limit_order_hook();
+        self.instantiate_hook_execute();
+        self.order_address = Some(self.hook_resource_address("order_address"));
+        self.fill_ticket_address = Some(self.hook_resource_address("fill_ticket_address"));
+    }
+
+    pub fn place_order(
+        &mut self,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        target_price_sqrt: PreciseDecimal,
+    ) -> &mut StandardHookTestHelper {
+        let account = self.env().account;
+        let hook_address = self.hook_address.unwrap();
+        let input_bucket_name = self.pool.registry.name("order_input_bucket");
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder
+            .withdraw_from_account(account, input_address, input_amount)
+            .take_from_worktop(input_address, input_amount, input_bucket_name.clone())
+            .with_name_lookup(|builder, lookup| {
+                let input_bucket = lookup.bucket(input_bucket_name);
+                builder.call_method(
+                    hook_address,
+                    "place_order",
+                    manifest_args!(input_bucket, target_price_sqrt),
+                )
+            });
+        self.env().new_instruction("place_order", 3, 2);
+        self
+    }
+
+    /// Places an order and returns the local id of the minted order NFT.
+    pub fn place_order_execute(
+        &mut self,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        target_price_sqrt: PreciseDecimal,
+    ) -> NonFungibleLocalId {
+        let receipt = self
+            .place_order(input_address, input_amount, target_price_sqrt)
+            .execute_expect_success(false);
+        let output_buckets = receipt.output_buckets("place_order");
+        if let ResourceSpecifier::Ids(_, order_ids) = &output_buckets[0][0] {
+            order_ids.first().unwrap().clone()
+        } else {
+            panic!("Expected ResourceSpecifier::Ids");
+        }
+    }
+
+    /// Fills an order by swapping the order tokens in the pool and settling the swap output with the hook,
+    /// all within the same transaction.
+    pub fn fill_order(
+        &mut self,
+        order_id: NonFungibleLocalId,
+        input_address: ResourceAddress,
+        output_address: ResourceAddress,
+        amount: Decimal,
+    ) -> &mut StandardHookTestHelper {
+        self.fill_order_internal(order_id, input_address, output_address, amount, None)
+    }
+
+    /// Fills an order like `fill_order`, but only settles `proceeds_amount` of the swap output and keeps the rest.
+    pub fn fill_order_with_proceeds(
+        &mut self,
+        order_id: NonFungibleLocalId,
+        input_address: ResourceAddress,
+        output_address: ResourceAddress,
+        amount: Decimal,
+        proceeds_amount: Decimal,
+    ) -> &mut StandardHookTestHelper {
+        self.fill_order_internal(
+            order_id,
+            input_address,
+            output_address,
+            amount,
+            Some(proceeds_amount),
+        )
+    }
+
+    fn fill_order_internal(
+        &mut self,
+        order_id: NonFungibleLocalId,
+        input_address: ResourceAddress,
+        output_address: ResourceAddress,
+        amount: Decimal,
+        proceeds_amount: Option<Decimal>,
+    ) -> &mut StandardHookTestHelper {
+        let hook_address = self.hook_address.unwrap();
+        let pool_address = self.pool.pool_address.unwrap();
+        let fill_ticket_address = self.fill_ticket_address.unwrap();
+        let input_bucket_name = self.pool.registry.name("fill_input_bucket");
+        let fill_ticket_name = self.pool.registry.name("fill_ticket");
+        let proceeds_name = self.pool.registry.name("fill_proceeds");
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder
+            .call_method(hook_address, "fill_order", manifest_args!(order_id, amount))
+            .take_all_from_worktop(input_address, input_bucket_name.clone())
+            .take_all_from_worktop(fill_ticket_address, fill_ticket_name.clone())
+            .with_name_lookup(|builder, lookup| {
+                let input_bucket = lookup.bucket(input_bucket_name);
+                builder.call_method(pool_address, "swap", manifest_args!(input_bucket))
+            })
+            .then(|builder| match proceeds_amount {
+                Some(proceeds_amount) => builder.take_from_worktop(
+                    output_address,
+                    proceeds_amount,
+                    proceeds_name.clone(),
+                ),
+                None => builder.take_all_from_worktop(output_address, proceeds_name.clone()),
+            })
+            .with_name_lookup(|builder, lookup| {
+                let proceeds = lookup.bucket(proceeds_name);
+                let fill_ticket = lookup.bucket(fill_ticket_name);
+                builder.call_method(
+                    hook_address,
+                    "settle_fill",
+                    manifest_args!(proceeds, fill_ticket),
+                )
+            });
+        self.env().new_instruction("fill_order", 6, 0);
+        self
+    }
+
+    pub fn cancel_order(&mut self, order_id: NonFungibleLocalId) -> &mut StandardHookTestHelper {
+        let order_address = self.order_address.unwrap();
+        self.call_with_non_fungible_bucket("cancel_order", order_address, order_id)
+    }
+
+    pub fn claim(&mut self, order_id: NonFungibleLocalId) -> &mut StandardHookTestHelper {
+        let order_address = self.order_address.unwrap();
+        self.call_with_non_fungible_proof("claim", order_address, order_id)
+    }
+
+    pub fn order(&mut self, order_id: NonFungibleLocalId) -> Option<LimitOrder> {
+        let hook_address = self.hook_address.unwrap();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder =
+            manifest_builder.call_method(hook_address, "order", manifest_args!(order_id));
+        self.env().new_instruction("order", 1, 0);
+        let receipt = self.execute_expect_success(false);
+        let outputs: Vec<Option<LimitOrder>> = receipt.outputs("order");
+        outputs[0].clone()
+    }
+
+    fn hook_resource_address(&mut self, name: &str) -> ResourceAddress {
+        let receipt = self.hook_getter(name).execute_expect_success(false);
+        let outputs: Vec<ResourceAddress> = receipt.outputs(name);
+        outputs[0]
+    }
+
+    // ACCESS CONTROL HOOK
+
+    /// Creates a new non fungible credential resource and deposits the credentials `#1#` and `#2#` to the account.
+    pub fn create_credential_resource_execute(&mut self) -> ResourceAddress {
+        let account = self.env().account;
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder
+            .create_non_fungible_resource(
+                OwnerRole::None,
+                NonFungibleIdType::Integer,
+                true,
+                NonFungibleResourceRoles::default(),
+                metadata!(),
+                Some(vec![
+                    (
+                        NonFungibleLocalId::integer(1),
+                        TestCredential {
+                            name: "Credential 1".into(),
+                        },
+                    ),
+                    (
+                        NonFungibleLocalId::integer(2),
+                        TestCredential {
+                            name: "Credential 2".into(),
+                        },
+                    ),
+                ]),
+            )
+            .try_deposit_entire_worktop_or_abort(account, None);
+        self.env()
+            .new_instruction("create_credential_resource", 2, 0);
+        let receipt = self.execute_expect_success(false);
+        receipt
+            .execution_receipt
+            .expect_commit_success()
+            .new_resource_addresses()[0]
+    }
+
+    pub fn instantiate_access_control_hook(
+        &mut self,
+        credential_resources: IndexSet<ResourceAddress>,
+    ) -> &mut StandardHookTestHelper {
+        let package_address = self.env().package_address("standard_hooks");
+        let owner_badge = self.pool.admin_badge_address();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "AccessControlHook",
+            "instantiate",
+            manifest_args!(owner_badge, credential_resources),
+        );
+        self.env().new_instruction("instantiate", 1, 0);
+        self
+    }
+
+    pub fn allow_credential(
+        &mut self,
+        credential: NonFungibleGlobalId,
+    ) -> &mut StandardHookTestHelper {
+        self.hook_method("allow_credential", credential)
+    }
+
+    pub fn deny_credential(
+        &mut self,
+        credential: NonFungibleGlobalId,
+    ) -> &mut StandardHookTestHelper {
+        self.hook_method("deny_credential", credential)
+    }
+
+    pub fn deny_credential_resource(
+        &mut self,
+        credential_resource: ResourceAddress,
+    ) -> &mut StandardHookTestHelper {
+        self.hook_method("deny_credential_resource", credential_resource)
+    }
+
+    fn hook_method<A: ManifestEncode>(
+        &mut self,
+        name: &str,
+        argument: A,
+    ) -> &mut StandardHookTestHelper {
+        let hook_address = self.hook_address.unwrap();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder =
+            manifest_builder.call_method(hook_address, name, manifest_args!(argument));
+        self.env().new_instruction(name, 1, 0);
+        self
+    }
+
+    // LIQUIDITY REWARDS HOOK
+
+    /// Instantiates the liquidity rewards hook with the admin badge as reward admin badge
+    /// and stores the address of the position resource.
+    pub fn instantiate_liquidity_rewards_hook_execute(&mut self, reward_address: ResourceAddress) {
+        let package_address = self.env().package_address("standard_hooks");
+        let reward_admin_badge = self.pool.admin_badge_address();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "LiquidityRewardsHook",
+            "instantiate",
+            manifest_args!(reward_admin_badge, reward_address),
+        );
+        self.env().new_instruction("instantiate", 1, 0);
+        self.instantiate_hook_execute();
+        self.position_address = Some(self.hook_resource_address("position_address"));
+    }
+
+    pub fn deposit_rewards(
+        &mut self,
+        reward_address: ResourceAddress,
+        amount: Decimal,
+    ) -> &mut StandardHookTestHelper {
+        let account = self.env().account;
+        let hook_address = self.hook_address.unwrap();
+        let rewards_name = self.pool.registry.name("rewards");
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder
+            .withdraw_from_account(account, reward_address, amount)
+            .take_from_worktop(reward_address, amount, rewards_name.clone())
+            .with_name_lookup(|builder, lookup| {
+                let rewards = lookup.bucket(rewards_name);
+                builder.call_method(hook_address, "deposit_rewards", manifest_args!(rewards))
+            });
+        self.env().new_instruction("deposit_rewards", 3, 2);
+        self
+    }
+
+    pub fn withdraw_rewards(&mut self, amount: Decimal) -> &mut StandardHookTestHelper {
+        self.hook_method("withdraw_rewards", amount)
+    }
+
+    pub fn set_reward_rate(&mut self, reward_rate: Decimal) -> &mut StandardHookTestHelper {
+        self.hook_method("set_reward_rate", reward_rate)
+    }
+
+    pub fn stake(&mut self, lp_amount: Decimal) -> &mut StandardHookTestHelper {
+        let account = self.env().account;
+        let hook_address = self.hook_address.unwrap();
+        let lp_address = self.pool.lp_address.unwrap();
+        let lp_bucket_name = self.pool.registry.name("stake_lp_bucket");
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder
+            .withdraw_from_account(account, lp_address, lp_amount)
+            .take_from_worktop(lp_address, lp_amount, lp_bucket_name.clone())
+            .with_name_lookup(|builder, lookup| {
+                let lp_bucket = lookup.bucket(lp_bucket_name);
+                builder.call_method(hook_address, "stake", manifest_args!(lp_bucket))
+            });
+        self.env().new_instruction("stake", 3, 2);
+        self
+    }
+
+    /// Stakes LP tokens and returns the local id of the minted position NFT.
+    pub fn stake_execute(&mut self, lp_amount: Decimal) -> NonFungibleLocalId {
+        let receipt = self.stake(lp_amount).execute_expect_success(false);
+        let output_buckets = receipt.output_buckets("stake");
+        if let ResourceSpecifier::Ids(_, position_ids) = &output_buckets[0][0] {
+            position_ids.first().unwrap().clone()
+        } else {
+            panic!("Expected ResourceSpecifier::Ids");
+        }
+    }
+
+    pub fn unstake(&mut self, position_id: NonFungibleLocalId) -> &mut StandardHookTestHelper {
+        let position_address = self.position_address.unwrap();
+        self.call_with_non_fungible_bucket("unstake", position_address, position_id)
+    }
+
+    pub fn claim_rewards(
+        &mut self,
+        position_id: NonFungibleLocalId,
+    ) -> &mut StandardHookTestHelper {
+        let position_address = self.position_address.unwrap();
+        self.call_with_non_fungible_proof("claim", position_address, position_id)
+    }
+
+    pub fn checkpoint(&mut self) -> &mut StandardHookTestHelper {
+        self.hook_getter("checkpoint")
+    }
+
+    pub fn claimable_rewards(&mut self, position_id: NonFungibleLocalId) -> Decimal {
+        let receipt = self
+            .hook_method("claimable_rewards", position_id)
+            .execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("claimable_rewards");
+        outputs[0]
+    }
+
+    pub fn available_rewards(&mut self) -> Decimal {
+        let receipt = self
+            .hook_getter("available_rewards")
+            .execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("available_rewards");
+        outputs[0]
+    }
+
+    pub fn lp_total_supply(&mut self) -> Decimal {
+        let receipt = self
+            .pool
+            .lp_total_supply()
+            .registry
+            .execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("lp_total_supply");
+        outputs[0]
+    }
+
+    // REFERRAL HOOK
+
+    pub fn instantiate_referral_hook(
+        &mut self,
+        referral_fee_rate: Decimal,
+    ) -> &mut StandardHookTestHelper {
+        let package_address = self.env().package_address("standard_hooks");
+        let owner_badge = self.pool.admin_badge_address();
+        let x_address = self.pool.x_address();
+        let y_address = self.pool.y_address();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "ReferralHook",
+            "instantiate",
+            manifest_args!(owner_badge, x_address, y_address, referral_fee_rate),
+        );
+        self.env().new_instruction("instantiate", 1, 0);
+        self
+    }
+
+    pub fn set_referral_fee_rate(
+        &mut self,
+        referral_fee_rate: Decimal,
+    ) -> &mut StandardHookTestHelper {
+        self.hook_method("set_referral_fee_rate", referral_fee_rate)
+    }
+
+    pub fn payout(&mut self, referrer: ComponentAddress) -> &mut StandardHookTestHelper {
+        self.hook_method("payout", referrer)
+    }
+
+    pub fn claim_referral_fees(
+        &mut self,
+        referrer: ComponentAddress,
+    ) -> &mut StandardHookTestHelper {
+        self.hook_method("claim", referrer)
+    }
+
+    pub fn referrer_account(&mut self, referrer: ComponentAddress) -> Option<ReferrerAccount> {
+        let receipt = self
+            .hook_method("referrer_account", referrer)
+            .execute_expect_success(false);
+        let outputs: Vec<Option<ReferrerAccount>> = receipt.outputs("referrer_account");
+        outputs[0].clone()
+    }
+
+    // CIRCUIT BREAKER HOOK
+
+    /// Instantiates the circuit breaker hook with the admin badge as guardian badge.
+    pub fn instantiate_circuit_breaker_hook(
+        &mut self,
+        parameters: CircuitBreakerParameters,
+    ) -> &mut StandardHookTestHelper {
+        let package_address = self.env().package_address("standard_hooks");
+        let guardian_badge = self.pool.admin_badge_address();
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "CircuitBreakerHook",
+            "instantiate",
+            manifest_args!(guardian_badge, parameters),
+        );
+        self.env().new_instruction("instantiate", 1, 0);
+        self
+    }
+
+    pub fn refresh_reference(&mut self) -> &mut StandardHookTestHelper {
+        self.hook_getter("refresh_reference")
+    }
+
+    pub fn set_circuit_breaker_parameters(
+        &mut self,
+        parameters: CircuitBreakerParameters,
+    ) -> &mut StandardHookTestHelper {
+        self.hook_method("set_parameters", parameters)
+    }
+
+    pub fn is_halted(&mut self) -> bool {
+        let receipt = self.hook_getter("is_halted").execute_expect_success(false);
+        let outputs: Vec<bool> = receipt.outputs("is_halted");
+        outputs[0]
+    }
+
+    fn call_with_non_fungible_bucket(
+        &mut self,
+        name: &str,
+        resource_address: ResourceAddress,
+        local_id: NonFungibleLocalId,
+    ) -> &mut StandardHookTestHelper {
+        let account = self.env().account;
+        let hook_address = self.hook_address.unwrap();
+        let bucket_name = self.pool.registry.name("non_fungible_bucket");
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder
+            .withdraw_non_fungibles_from_account(account, resource_address, vec![local_id.clone()])
+            .take_non_fungibles_from_worktop(resource_address, vec![local_id], bucket_name.clone())
+            .with_name_lookup(|builder, lookup| {
+                let bucket = lookup.bucket(bucket_name);
+                builder.call_method(hook_address, name, manifest_args!(bucket))
+            });
+        self.env().new_instruction(name, 3, 2);
+        self
+    }
+
+    fn call_with_non_fungible_proof(
+        &mut self,
+        name: &str,
+        resource_address: ResourceAddress,
+        local_id: NonFungibleLocalId,
+    ) -> &mut StandardHookTestHelper {
+        let account = self.env().account;
+        let hook_address = self.hook_address.unwrap();
+        let proof_name = self.pool.registry.name("non_fungible_proof");
+        let manifest_builder =
+            mem::replace(&mut self.env().manifest_builder, ManifestBuilder::new());
+        self.env().manifest_builder = manifest_builder
+            .create_proof_from_account_of_non_fungibles(account, resource_address, vec![local_id])
+            .pop_from_auth_zone(proof_name.clone())
+            .with_name_lookup(|builder, lookup| {
+                let proof = lookup.proof(proof_name);
+                builder.call_method(hook_address, name, manifest_args!(proof))
+            });
+        self.env().new_instruction(name, 3, 2);
+        self
+    }
+}
+
+pub fn dynamic_fee_parameters() -> DynamicFeeParameters {
+    DynamicFeeParameters {
+        fee_floor: dec!(0.001),
+        fee_ceiling: dec!(0.05),
+        volatility_ceiling: dec!(0.1),
+        interval_seconds: 900,
+        intervals: 3,
+    }
+}
+
+#[derive(ScryptoSbor, ManifestSbor, NonFungibleData)]
+pub struct TestCredential {
+    pub name: String,
+}
+
+pub fn circuit_breaker_parameters() -> CircuitBreakerParameters {
+    CircuitBreakerParameters {
+        max_deviation: dec!(0.1),
+        window_seconds: 60,
+        cooldown_seconds: 300,
+    }
+}
@@ -0,0 +1,222 @@
+mod helper;
+
+#[cfg(test)]
+mod test_limit_order_hook {
+    use super::*;
+    use common::pools::SwapType;
+    use helper::*;
+    use pretty_assertions::assert_eq;
+    use radix_engine::system::system_modules::execution_trace::ResourceSpecifier::Amount;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+    use standard_hooks::limit_order_hook::LimitOrder;
+
+    fn instantiate_helper() -> StandardHookTestHelper {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_limit_order_hook_execute();
+        helper.instantiate_pool_with_hook();
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10000), dec!(10000));
+        helper
+    }
+
+    /// Buys token X, which moves `price_sqrt` from 1 to 1.05.
+    fn raise_price(helper: &mut StandardHookTestHelper) {
+        helper.pool.swap(helper.pool.y_address(), dec!(500));
+        helper.execute_expect_success(false);
+    }
+
+    fn fill_sell_x(
+        helper: &mut StandardHookTestHelper,
+        order_id: NonFungibleLocalId,
+        amount: Decimal,
+    ) {
+        let x_address = helper.pool.x_address();
+        let y_address = helper.pool.y_address();
+        helper.fill_order(order_id, x_address, y_address, amount);
+    }
+
+    #[test]
+    fn test_place_order() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.01"));
+
+        assert_eq!(
+            helper.order(order_id),
+            Some(LimitOrder {
+                swap_type: SwapType::SellX,
+                target_price_sqrt: pdec!("1.01"),
+                remaining_amount: dec!(10),
+                proceeds_amount: dec!(0),
+                pending_fill: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_place_order_zero_target_price() {
+        let mut helper = instantiate_helper();
+        helper.place_order(helper.pool.x_address(), dec!(10), pdec!(0));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_fill_order_without_swap() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.01"));
+        fill_sell_x(&mut helper, order_id, dec!(10));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_fill_order_not_crossed() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.06"));
+        raise_price(&mut helper);
+        fill_sell_x(&mut helper, order_id, dec!(10));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_fill_order_crossed() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.01"));
+        raise_price(&mut helper);
+        fill_sell_x(&mut helper, order_id.clone(), dec!(10));
+        helper.execute_expect_success(false);
+
+        let order = helper.order(order_id.clone()).unwrap();
+        assert_eq!(order.remaining_amount, dec!(0));
+        assert!(!order.pending_fill);
+        // 10 X at the post-swap price of 1.05^2, the surplus above the limit price of 1.01^2 belongs to the owner
+        assert!(
+            order.proceeds_amount > dec!(11),
+            "{:?}",
+            order.proceeds_amount
+        );
+
+        let receipt = helper.claim(order_id.clone()).execute_expect_success(false);
+        let output_buckets = receipt.output_buckets("claim");
+        assert_eq!(
+            output_buckets,
+            vec![vec![Amount(helper.pool.y_address(), order.proceeds_amount)]]
+        );
+        assert_eq!(helper.order(order_id).unwrap().proceeds_amount, dec!(0));
+    }
+
+    #[test]
+    fn test_fill_order_buy_x_crossed() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.y_address(), dec!(10), pdec!("0.99"));
+        helper.pool.swap(helper.pool.x_address(), dec!(500));
+        helper.execute_expect_success(false);
+        let x_address = helper.pool.x_address();
+        let y_address = helper.pool.y_address();
+        helper.fill_order(order_id.clone(), y_address, x_address, dec!(10));
+        helper.execute_expect_success(false);
+
+        let order = helper.order(order_id).unwrap();
+        assert_eq!(order.remaining_amount, dec!(0));
+        // 10 Y at the limit price of 0.99^2
+        assert!(
+            order.proceeds_amount >= dec!(10) / dec!("0.9801"),
+            "{:?}",
+            order.proceeds_amount
+        );
+    }
+
+    #[test]
+    fn test_fill_order_partially() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.01"));
+        raise_price(&mut helper);
+        fill_sell_x(&mut helper, order_id.clone(), dec!(4));
+        helper.execute_expect_success(false);
+
+        let order = helper.order(order_id.clone()).unwrap();
+        assert_eq!(order.remaining_amount, dec!(6));
+        assert!(order.proceeds_amount > dec!(0));
+
+        fill_sell_x(&mut helper, order_id.clone(), dec!(6));
+        helper.execute_expect_success(false);
+
+        assert_eq!(helper.order(order_id).unwrap().remaining_amount, dec!(0));
+    }
+
+    #[test]
+    fn test_fill_order_more_than_remaining() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.01"));
+        raise_price(&mut helper);
+        fill_sell_x(&mut helper, order_id, dec!(11));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_fill_order_below_limit_price() {
+        let mut helper = instantiate_helper();
+        let order_id =
+            helper.place_order_execute(helper.pool.x_address(), dec!(1000), pdec!("1.04"));
+        raise_price(&mut helper);
+        // Swapping the whole order moves the price far below the limit, settlement has to fail.
+        fill_sell_x(&mut helper, order_id, dec!(1000));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_fill_order_keeper_keeps_surplus() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.01"));
+        raise_price(&mut helper);
+        let x_address = helper.pool.x_address();
+        let y_address = helper.pool.y_address();
+        // Settling only the limit amount of 10 X at 1.01^2 has to fail, the surplus belongs to the owner.
+        helper.fill_order_with_proceeds(order_id, x_address, y_address, dec!(10), dec!("10.201"));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_cancel_order_unfilled() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.01"));
+
+        let receipt = helper
+            .cancel_order(order_id.clone())
+            .execute_expect_success(false);
+        let output_buckets = receipt.output_buckets("cancel_order");
+
+        assert_eq!(
+            output_buckets,
+            vec![vec![
+                Amount(helper.pool.x_address(), dec!(10)),
+                Amount(helper.pool.y_address(), dec!(0))
+            ]]
+        );
+        assert_eq!(helper.order(order_id), None);
+    }
+
+    #[test]
+    fn test_cancel_order_partially_filled() {
+        let mut helper = instantiate_helper();
+        let order_id = helper.place_order_execute(helper.pool.x_address(), dec!(10), pdec!("1.01"));
+        raise_price(&mut helper);
+        fill_sell_x(&mut helper, order_id.clone(), dec!(4));
+        helper.execute_expect_success(false);
+        let proceeds_amount = helper.order(order_id.clone()).unwrap().proceeds_amount;
+
+        let receipt = helper
+            .cancel_order(order_id.clone())
+            .execute_expect_success(false);
+        let output_buckets = receipt.output_buckets("cancel_order");
+
+        assert_eq!(
+            output_buckets,
+            vec![vec![
+                Amount(helper.pool.x_address(), dec!(6)),
+                Amount(helper.pool.y_address(), proceeds_amount)
+            ]]
+        );
+        assert_eq!(helper.order(order_id), None);
+    }
+}
@@ -0,0 +1,200 @@
+mod helper;
+
+#[cfg(test)]
+mod test_liquidity_rewards_hook {
+    use super::*;
+    use helper::*;
+    use ociswap_pool_test_helper::AttoDecimal;
+    use pretty_assertions::assert_eq;
+    use radix_engine::system::system_modules::execution_trace::ResourceSpecifier::Amount;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+
+    const REWARD_ERROR_MARGIN: Decimal = dec!(0.000000000001);
+
+    fn instantiate_helper(reward_rate: Decimal, rewards: Decimal) -> StandardHookTestHelper {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_liquidity_rewards_hook_execute(helper.pool.u_address());
+        helper.instantiate_pool_with_hook();
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10000), dec!(10000));
+
+        helper.create_owner_proof();
+        helper.set_reward_rate(reward_rate);
+        helper.deposit_rewards(helper.pool.u_address(), rewards);
+        helper.execute_expect_success(false);
+        helper
+    }
+
+    fn assert_rewards_eq(actual: Decimal, expected: Decimal) {
+        assert!(
+            actual <= expected && actual >= expected - REWARD_ERROR_MARGIN,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_stake_all_liquidity() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.checkpoint().execute_expect_success(false);
+
+        assert_rewards_eq(helper.claimable_rewards(position_id), dec!(100));
+    }
+
+    #[test]
+    fn test_stake_half_liquidity() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply / 2);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.checkpoint().execute_expect_success(false);
+
+        // The rewards of the unstaked half are not reserved and stay with the reward admin.
+        assert_rewards_eq(helper.claimable_rewards(position_id), dec!(50));
+        assert!(helper.available_rewards() >= dec!(950));
+    }
+
+    #[test]
+    fn test_no_rewards_before_staking() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        helper.pool.advance_timestamp_by_seconds(100);
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply);
+        helper.checkpoint().execute_expect_success(false);
+
+        assert_eq!(helper.claimable_rewards(position_id), dec!(0));
+    }
+
+    #[test]
+    fn test_rewards_capped_by_budget() {
+        let mut helper = instantiate_helper(dec!(1), dec!(10));
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.checkpoint().execute_expect_success(false);
+
+        assert_rewards_eq(helper.claimable_rewards(position_id), dec!(10));
+        assert!(helper.available_rewards() <= Decimal::ATTO);
+    }
+
+    #[test]
+    fn test_swap_checkpoints_rewards() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+
+        assert_rewards_eq(helper.claimable_rewards(position_id), dec!(100));
+    }
+
+    #[test]
+    fn test_add_liquidity_dilutes_rewards() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10000), dec!(10000));
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.checkpoint().execute_expect_success(false);
+
+        // 100 seconds with all and 100 seconds with half of the liquidity staked
+        assert_rewards_eq(helper.claimable_rewards(position_id), dec!(150));
+    }
+
+    #[test]
+    fn test_remove_liquidity_concentrates_rewards() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply / 2);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.pool.remove_liquidity_default(lp_total_supply / 2);
+        helper.execute_expect_success(false);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.checkpoint().execute_expect_success(false);
+
+        // 100 seconds with half and 100 seconds with all of the liquidity staked
+        assert_rewards_eq(helper.claimable_rewards(position_id), dec!(150));
+    }
+
+    #[test]
+    fn test_claim_rewards() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply);
+        helper.pool.advance_timestamp_by_seconds(100);
+
+        let receipt = helper
+            .claim_rewards(position_id.clone())
+            .execute_expect_success(false);
+        let output_buckets = receipt.output_buckets("claim");
+        let claimed = match output_buckets[0][0] {
+            Amount(address, amount) => {
+                assert_eq!(address, helper.pool.u_address());
+                amount
+            }
+            _ => panic!("Expected ResourceSpecifier::Amount"),
+        };
+
+        assert_rewards_eq(claimed, dec!(100));
+        assert_eq!(helper.claimable_rewards(position_id), dec!(0));
+    }
+
+    #[test]
+    fn test_unstake() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        let position_id = helper.stake_execute(lp_total_supply);
+        helper.pool.advance_timestamp_by_seconds(100);
+
+        let receipt = helper.unstake(position_id).execute_expect_success(false);
+        let output_buckets = receipt.output_buckets("unstake");
+
+        assert_eq!(
+            output_buckets[0][0],
+            Amount(helper.pool.lp_address.unwrap(), lp_total_supply)
+        );
+        match output_buckets[0][1] {
+            Amount(_, amount) => assert_rewards_eq(amount, dec!(100)),
+            _ => panic!("Expected ResourceSpecifier::Amount"),
+        }
+    }
+
+    #[test]
+    fn test_withdraw_reserved_rewards() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        helper.stake_execute(lp_total_supply);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.create_owner_proof();
+        helper.withdraw_rewards(dec!(1000));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_withdraw_available_rewards() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        let lp_total_supply = helper.lp_total_supply();
+        helper.stake_execute(lp_total_supply);
+        helper.pool.advance_timestamp_by_seconds(100);
+        helper.create_owner_proof();
+        helper.withdraw_rewards(dec!(899));
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_set_reward_rate_without_reward_admin_badge() {
+        let mut helper = instantiate_helper(dec!(1), dec!(1000));
+        helper.set_reward_rate(dec!(2));
+        helper.execute_expect_failure(false);
+    }
+}
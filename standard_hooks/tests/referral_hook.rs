@@ -0,0 +1,168 @@
+mod helper;
+
+#[cfg(test)]
+mod test_referral_hook {
+    use super::*;
+    use helper::*;
+    use ociswap_pool_test_helper::AttoDecimal;
+    use pretty_assertions::assert_eq;
+    use radix_engine::system::system_modules::execution_trace::ResourceSpecifier::Amount;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+
+    fn instantiate_helper(referral_fee_rate: Decimal) -> StandardHookTestHelper {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_referral_hook(referral_fee_rate);
+        helper.instantiate_hook_execute();
+        helper.instantiate_pool_with_hook();
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10000), dec!(10000));
+        helper
+    }
+
+    fn swap_with_referrer(
+        helper: &mut StandardHookTestHelper,
+        input_address: ResourceAddress,
+    ) -> Decimal {
+        let referrer = helper.env().account;
+        let receipt = helper
+            .pool
+            .swap_with_referrer(input_address, dec!(100), referrer)
+            .registry
+            .execute_expect_success(false);
+        match receipt.output_buckets("swap_with_referrer")[0][0] {
+            Amount(_, amount) => amount,
+            _ => panic!("Expected ResourceSpecifier::Amount"),
+        }
+    }
+
+    #[test]
+    fn test_instantiate_referral_fee_rate_above_limit() {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_referral_hook(dec!(0.1) + Decimal::ATTO);
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_instantiate_negative_referral_fee_rate() {
+        let mut helper = StandardHookTestHelper::new();
+        helper.instantiate_referral_hook(-Decimal::ATTO);
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_without_referrer() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        helper.pool.swap(helper.pool.x_address(), dec!(100));
+        helper.execute_expect_success(false);
+
+        let referrer = helper.env().account;
+        assert_eq!(helper.referrer_account(referrer), None);
+    }
+
+    #[test]
+    fn test_swap_with_referrer() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        let output_amount = swap_with_referrer(&mut helper, helper.pool.x_address());
+
+        let referrer = helper.env().account;
+        let account = helper.referrer_account(referrer).unwrap();
+        let gross_output_amount = output_amount + account.y_claimable;
+
+        assert!(account.y_claimable > dec!(0));
+        assert!(account.y_claimable <= gross_output_amount * dec!(0.01));
+        assert!(account.y_claimable >= gross_output_amount * dec!(0.01) - Decimal::ATTO);
+        assert_eq!(account.x_claimable, dec!(0));
+        assert_eq!(account.y_earned, account.y_claimable);
+    }
+
+    #[test]
+    fn test_swap_with_referrer_both_directions() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        swap_with_referrer(&mut helper, helper.pool.x_address());
+        swap_with_referrer(&mut helper, helper.pool.y_address());
+
+        let referrer = helper.env().account;
+        let account = helper.referrer_account(referrer).unwrap();
+
+        assert!(account.x_claimable > dec!(0));
+        assert!(account.y_claimable > dec!(0));
+    }
+
+    #[test]
+    fn test_swap_with_referrer_zero_fee_rate() {
+        let mut helper = instantiate_helper(dec!(0));
+        swap_with_referrer(&mut helper, helper.pool.x_address());
+
+        let referrer = helper.env().account;
+        assert_eq!(helper.referrer_account(referrer), None);
+    }
+
+    #[test]
+    fn test_payout() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        swap_with_referrer(&mut helper, helper.pool.x_address());
+        let referrer = helper.env().account;
+        let earned = helper.referrer_account(referrer).unwrap().y_earned;
+
+        helper.payout(referrer);
+        helper.execute_expect_success(false);
+
+        assert_eq!(
+            helper.referrer_account(referrer),
+            Some(ReferrerAccount {
+                x_claimable: dec!(0),
+                y_claimable: dec!(0),
+                x_earned: dec!(0),
+                y_earned: earned,
+            })
+        );
+    }
+
+    #[test]
+    fn test_payout_unknown_referrer() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        let referrer = helper.env().account;
+        helper.payout(referrer);
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_claim_by_other_caller() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        swap_with_referrer(&mut helper, helper.pool.x_address());
+        let referrer = helper.env().account;
+        helper.claim_referral_fees(referrer);
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_set_referral_fee_rate() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        helper.create_owner_proof();
+        helper.set_referral_fee_rate(dec!(0.05));
+        helper.execute_expect_success(false);
+
+        let receipt = helper
+            .hook_getter("referral_fee_rate")
+            .execute_expect_success(false);
+        let outputs: Vec<Decimal> = receipt.outputs("referral_fee_rate");
+        assert_eq!(outputs, vec![dec!(0.05)]);
+    }
+
+    #[test]
+    fn test_set_referral_fee_rate_without_owner_badge() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        helper.set_referral_fee_rate(dec!(0.05));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_set_referral_fee_rate_above_limit() {
+        let mut helper = instantiate_helper(dec!(0.01));
+        helper.create_owner_proof();
+        helper.set_referral_fee_rate(dec!(0.2));
+        helper.execute_expect_failure(false);
+    }
+}
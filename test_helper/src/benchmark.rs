@@ -0,0 +1,176 @@
+//! Cost measurements of pool operations, compared against a baseline to detect cost regressions.
+//!
+//! Measurements are stored as CSV with one line per benchmark, so changes of the baseline are easy to review.
+
+use std::fmt;
+use std::fmt::Write as _;
+
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+
+const CSV_HEADER: &str = "name,execution_cost_units,finalization_cost_units,storage_cost_xrd";
+
+/// The costs of a single transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostMeasurement {
+    pub execution_cost_units: u32,
+    pub finalization_cost_units: u32,
+    /// The cost of the state stored by the transaction in XRD.
+    pub storage_cost: Decimal,
+}
+
+impl CostMeasurement {
+    pub fn from_receipt(receipt: &Receipt) -> Self {
+        let fee_summary = &receipt.execution_receipt.fee_summary;
+        Self {
+            execution_cost_units: fee_summary.total_execution_cost_units_consumed,
+            finalization_cost_units: fee_summary.total_finalization_cost_units_consumed,
+            storage_cost: fee_summary.total_storage_cost_in_xrd,
+        }
+    }
+
+    fn metrics(&self) -> [(&'static str, Decimal); 3] {
+        [
+            ("execution_cost_units", self.execution_cost_units.into()),
+            (
+                "finalization_cost_units",
+                self.finalization_cost_units.into(),
+            ),
+            ("storage_cost_xrd", self.storage_cost),
+        ]
+    }
+}
+
+/// A cost metric of a benchmark exceeding its baseline by more than the threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostRegression {
+    pub name: String,
+    pub metric: &'static str,
+    pub baseline: Decimal,
+    pub current: Decimal,
+}
+
+impl fmt::Display for CostRegression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} increased from {} to {}",
+            self.name, self.metric, self.baseline, self.current
+        )
+    }
+}
+
+/// Named cost measurements in the order they were recorded.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CostBenchmark {
+    measurements: Vec<(String, CostMeasurement)>,
+}
+
+impl CostBenchmark {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the costs of a successfully committed transaction.
+    pub fn record(&mut self, name: &str, receipt: &Receipt) {
+        receipt.execution_receipt.expect_commit_success();
+        assert!(
+            self.get(name).is_none(),
+            "Benchmark {} recorded twice!",
+            name
+        );
+        self.measurements
+            .push((name.to_string(), CostMeasurement::from_receipt(receipt)));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CostMeasurement> {
+        self.measurements
+            .iter()
+            .find(|(measurement_name, _)| measurement_name == name)
+            .map(|(_, measurement)| measurement)
+    }
+
+    pub fn measurements(&self) -> &[(String, CostMeasurement)] {
+        &self.measurements
+    }
+
+    /// Returns the measurements as CSV with a header line.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        for (name, measurement) in &self.measurements {
+            writeln!(
+                csv,
+                "{},{},{},{}",
+                name,
+                measurement.execution_cost_units,
+                measurement.finalization_cost_units,
+                measurement.storage_cost
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// Parses measurements written by `to_csv`.
+    pub fn from_csv(csv: &str) -> Result<Self, String> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        if lines.next() != Some(CSV_HEADER) {
+            return Err(format!("Expected header: {}", CSV_HEADER));
+        }
+        let measurements = lines
+            .map(|line| {
+                let invalid = || format!("Invalid line: {}", line);
+                let fields: Vec<&str> = line.split(',').collect();
+                let [name, execution_cost_units, finalization_cost_units, storage_cost] =
+                    fields[..]
+                else {
+                    return Err(invalid());
+                };
+                let measurement = CostMeasurement {
+                    execution_cost_units: execution_cost_units.parse().map_err(|_| invalid())?,
+                    finalization_cost_units: finalization_cost_units
+                        .parse()
+                        .map_err(|_| invalid())?,
+                    storage_cost: Decimal::try_from(storage_cost).map_err(|_| invalid())?,
+                };
+                Ok((name.to_string(), measurement))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { measurements })
+    }
+
+    /// Returns all metrics exceeding their baseline by more than the relative `threshold`,
+    /// e.g. `dec!(0.05)` allows an increase of up to 5%. Benchmarks missing in the baseline are ignored.
+    pub fn regressions(&self, baseline: &CostBenchmark, threshold: Decimal) -> Vec<CostRegression> {
+        let mut regressions = Vec::new();
+        for (name, measurement) in &self.measurements {
+            let Some(baseline_measurement) = baseline.get(name) else {
+                continue;
+            };
+            for ((metric, current), (_, baseline_value)) in measurement
+                .metrics()
+                .into_iter()
+                .zip(baseline_measurement.metrics())
+            {
+                if current > baseline_value * (dec!(1) + threshold) {
+                    regressions.push(CostRegression {
+                        name: name.clone(),
+                        metric,
+                        baseline: baseline_value,
+                        current,
+                    });
+                }
+            }
+        }
+        regressions
+    }
+
+    /// Returns the names of all benchmarks missing in the baseline.
+    pub fn missing_in<'a>(&'a self, baseline: &CostBenchmark) -> Vec<&'a str> {
+        self.measurements
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| baseline.get(name).is_none())
+            .collect()
+    }
+}
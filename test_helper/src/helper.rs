@@ -11,6 +11,7 @@ pub struct PoolTestHelper {
     pub pool_address: Option<ComponentAddress>,
     pub lp_address: Option<ResourceAddress>,
     pub liquidity_pool_address: Option<ComponentAddress>,
+    pub factory_address: Option<ComponentAddress>,
     pub registry: RegistryTestHelper,
 }
 
@@ -38,6 +39,7 @@ impl PoolTestHelper {
             pool_address: None,
             lp_address: None,
             liquidity_pool_address: None,
+            factory_address: None,
             registry: RegistryTestHelper::new_with_packages(packages),
         };
 
@@ -205,6 +207,138 @@ impl PoolTestHelper {
         self
     }
 
+    pub fn migrate(
+        &mut self,
+        lp_address: ResourceAddress,
+        lp_amount: Decimal,
+        target_pool_address: ComponentAddress,
+    ) -> &mut PoolTestHelper {
+        let account_component = self.registry.env.account;
+        let pool_address = self.pool_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(account_component, lp_address, lp_amount)
+            .take_from_worktop(lp_address, lp_amount, self.registry.name("lp_bucket"))
+            .with_name_lookup(|builder, lookup| {
+                let lp_bucket = lookup.bucket(self.registry.name("lp_bucket"));
+                builder.call_method(
+                    pool_address,
+                    "migrate",
+                    manifest_args!(lp_bucket, target_pool_address),
+                )
+            });
+        self.registry.env.new_instruction("migrate", 3, 2);
+        self
+    }
+
+    pub fn migrate_with_credential(
+        &mut self,
+        lp_address: ResourceAddress,
+        lp_amount: Decimal,
+        target_pool_address: ComponentAddress,
+        credential_address: ResourceAddress,
+        credential_id: NonFungibleLocalId,
+    ) -> &mut PoolTestHelper {
+        let account_component = self.registry.env.account;
+        let pool_address = self.pool_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(account_component, lp_address, lp_amount)
+            .take_from_worktop(lp_address, lp_amount, self.registry.name("lp_bucket"))
+            .create_proof_from_account_of_non_fungibles(
+                account_component,
+                credential_address,
+                vec![credential_id],
+            )
+            .pop_from_auth_zone(self.registry.name("credential"))
+            .with_name_lookup(|builder, lookup| {
+                let lp_bucket = lookup.bucket(self.registry.name("lp_bucket"));
+                let credential = lookup.proof(self.registry.name("credential"));
+                builder.call_method(
+                    pool_address,
+                    "migrate_with_credential",
+                    manifest_args!(lp_bucket, target_pool_address, credential),
+                )
+            });
+        self.registry
+            .env
+            .new_instruction("migrate_with_credential", 5, 4);
+        self
+    }
+
+    pub fn add_liquidity_with_credential(
+        &mut self,
+        x_address: ResourceAddress,
+        x_amount: Decimal,
+        y_address: ResourceAddress,
+        y_amount: Decimal,
+        credential_address: ResourceAddress,
+        credential_id: NonFungibleLocalId,
+    ) -> &mut PoolTestHelper {
+        let account_component = self.registry.env.account;
+        let pool_address = self.pool_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(account_component, x_address, x_amount)
+            .withdraw_from_account(account_component, y_address, y_amount)
+            .take_from_worktop(x_address, x_amount, self.registry.name("x_bucket"))
+            .take_from_worktop(y_address, y_amount, self.registry.name("y_bucket"))
+            .create_proof_from_account_of_non_fungibles(
+                account_component,
+                credential_address,
+                vec![credential_id],
+            )
+            .pop_from_auth_zone(self.registry.name("credential"))
+            .with_name_lookup(|builder, lookup| {
+                let x_bucket = lookup.bucket(self.registry.name("x_bucket"));
+                let y_bucket = lookup.bucket(self.registry.name("y_bucket"));
+                let credential = lookup.proof(self.registry.name("credential"));
+                builder.call_method(
+                    pool_address,
+                    "add_liquidity_with_credential",
+                    manifest_args!(x_bucket, y_bucket, credential),
+                )
+            });
+        self.registry
+            .env
+            .new_instruction("add_liquidity_with_credential", 7, 6);
+        self
+    }
+
+    pub fn remove_liquidity_with_credential(
+        &mut self,
+        lp_address: ResourceAddress,
+        lp_amount: Decimal,
+        credential_address: ResourceAddress,
+        credential_id: NonFungibleLocalId,
+    ) -> &mut PoolTestHelper {
+        let account_component = self.registry.env.account;
+        let pool_address = self.pool_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(account_component, lp_address, lp_amount)
+            .take_from_worktop(lp_address, lp_amount, self.registry.name("lp_bucket"))
+            .create_proof_from_account_of_non_fungibles(
+                account_component,
+                credential_address,
+                vec![credential_id],
+            )
+            .pop_from_auth_zone(self.registry.name("credential"))
+            .with_name_lookup(|builder, lookup| {
+                let lp_bucket = lookup.bucket(self.registry.name("lp_bucket"));
+                let credential = lookup.proof(self.registry.name("credential"));
+                builder.call_method(
+                    pool_address,
+                    "remove_liquidity_with_credential",
+                    manifest_args!(lp_bucket, credential),
+                )
+            });
+        self.registry
+            .env
+            .new_instruction("remove_liquidity_with_credential", 5, 4);
+        self
+    }
+
     pub fn removable_liquidity(&mut self, lp_amount: Decimal) -> &mut PoolTestHelper {
         let pool_address = self.pool_address.unwrap();
         let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
@@ -350,6 +484,10 @@ impl PoolTestHelper {
         self.getter("lp_total_supply")
     }
 
+    pub fn state(&mut self) -> &mut PoolTestHelper {
+        self.getter("state")
+    }
+
     pub fn input_fee_rate(&mut self) -> &mut PoolTestHelper {
         self.getter("input_fee_rate")
     }
@@ -387,6 +525,206 @@ impl PoolTestHelper {
         self
     }
 
+    pub fn quote(
+        &mut self,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+    ) -> &mut PoolTestHelper {
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder.call_method(
+            self.pool_address.unwrap(),
+            "quote",
+            manifest_args!(input_address, input_amount),
+        );
+        self.registry.env.new_instruction("quote", 1, 0);
+        self
+    }
+
+    pub fn router_swap_path(
+        &mut self,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        path: Vec<ComponentAddress>,
+        min_output_amount: Decimal,
+        deadline: u64,
+    ) -> &mut PoolTestHelper {
+        let package_address = self.registry.env.package_address("pool");
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(self.registry.env.account, input_address, input_amount)
+            .take_from_worktop(
+                input_address,
+                input_amount,
+                self.registry.name("input_bucket"),
+            )
+            .with_name_lookup(|builder, lookup| {
+                let input_bucket = lookup.bucket(self.registry.name("input_bucket"));
+                builder.call_function(
+                    package_address,
+                    "Router",
+                    "swap_path",
+                    manifest_args!(input_bucket, path, min_output_amount, deadline),
+                )
+            });
+        self.registry.env.new_instruction("swap_path", 3, 2);
+        self
+    }
+
+    pub fn router_swap_split(
+        &mut self,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        pools: Vec<ComponentAddress>,
+        min_output_amount: Decimal,
+        deadline: u64,
+    ) -> &mut PoolTestHelper {
+        let package_address = self.registry.env.package_address("pool");
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(self.registry.env.account, input_address, input_amount)
+            .take_from_worktop(
+                input_address,
+                input_amount,
+                self.registry.name("input_bucket"),
+            )
+            .with_name_lookup(|builder, lookup| {
+                let input_bucket = lookup.bucket(self.registry.name("input_bucket"));
+                builder.call_function(
+                    package_address,
+                    "Router",
+                    "swap_split",
+                    manifest_args!(input_bucket, pools, min_output_amount, deadline),
+                )
+            });
+        self.registry.env.new_instruction("swap_split", 3, 2);
+        self
+    }
+
+    pub fn router_swap_batch(
+        &mut self,
+        swaps: Vec<(ComponentAddress, ResourceAddress, Decimal, Decimal)>,
+    ) -> &mut PoolTestHelper {
+        let package_address = self.registry.env.package_address("pool");
+        let mut manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        for (index, (_, input_address, input_amount, _)) in swaps.iter().enumerate() {
+            manifest_builder = manifest_builder
+                .withdraw_from_account(self.registry.env.account, *input_address, *input_amount)
+                .take_from_worktop(
+                    *input_address,
+                    *input_amount,
+                    self.registry.name(&format!("input_bucket_{}", index)),
+                );
+        }
+        self.registry.env.manifest_builder =
+            manifest_builder.with_name_lookup(|builder, lookup| {
+                let swaps: Vec<(ComponentAddress, ManifestBucket, Decimal)> = swaps
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (pool_address, _, _, min_output_amount))| {
+                        (
+                            *pool_address,
+                            lookup.bucket(self.registry.name(&format!("input_bucket_{}", index))),
+                            *min_output_amount,
+                        )
+                    })
+                    .collect();
+                builder.call_function(
+                    package_address,
+                    "Router",
+                    "swap_batch",
+                    manifest_args!(swaps),
+                )
+            });
+        self.registry
+            .env
+            .new_instruction("swap_batch", swaps.len() * 2 + 1, swaps.len() * 2);
+        self
+    }
+
+    pub fn router_quote_path(
+        &mut self,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        path: Vec<ComponentAddress>,
+    ) -> &mut PoolTestHelper {
+        let package_address = self.registry.env.package_address("pool");
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder.call_function(
+            package_address,
+            "Router",
+            "quote_path",
+            manifest_args!(input_address, input_amount, path),
+        );
+        self.registry.env.new_instruction("quote_path", 1, 0);
+        self
+    }
+
+    pub fn swap_with_credential(
+        &mut self,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        credential_address: ResourceAddress,
+        credential_id: NonFungibleLocalId,
+    ) -> &mut PoolTestHelper {
+        let account_component = self.registry.env.account;
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(account_component, input_address, input_amount)
+            .take_from_worktop(
+                input_address,
+                input_amount,
+                self.registry.name("input_bucket"),
+            )
+            .create_proof_from_account_of_non_fungibles(
+                account_component,
+                credential_address,
+                vec![credential_id],
+            )
+            .pop_from_auth_zone(self.registry.name("credential"))
+            .with_name_lookup(|builder, lookup| {
+                let input_bucket = lookup.bucket(self.registry.name("input_bucket"));
+                let credential = lookup.proof(self.registry.name("credential"));
+                builder.call_method(
+                    self.pool_address.unwrap(),
+                    "swap_with_credential",
+                    manifest_args!(input_bucket, credential),
+                )
+            });
+        self.registry
+            .env
+            .new_instruction("swap_with_credential", 5, 4);
+        self
+    }
+
+    pub fn swap_with_referrer(
+        &mut self,
+        input_address: ResourceAddress,
+        input_amount: Decimal,
+        referrer: ComponentAddress,
+    ) -> &mut PoolTestHelper {
+        let account_component = self.registry.env.account;
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(account_component, input_address, input_amount)
+            .take_from_worktop(
+                input_address,
+                input_amount,
+                self.registry.name("input_bucket"),
+            )
+            .with_name_lookup(|builder, lookup| {
+                let input_bucket = lookup.bucket(self.registry.name("input_bucket"));
+                builder.call_method(
+                    self.pool_address.unwrap(),
+                    "swap_with_referrer",
+                    manifest_args!(input_bucket, referrer),
+                )
+            });
+        self.registry
+            .env
+            .new_instruction("swap_with_referrer", 3, 2);
+        self
+    }
+
     pub fn instantiate_default(&mut self, verbose: bool) -> Receipt {
         self.instantiate_default_with_input_fee(dec!(0), verbose)
     }
@@ -555,6 +893,14 @@ impl PoolTestHelper {
         self.remove_liquidity(self.lp_address.unwrap(), lp_amount)
     }
 
+    pub fn migrate_default(
+        &mut self,
+        lp_amount: Decimal,
+        target_pool_address: ComponentAddress,
+    ) -> &mut PoolTestHelper {
+        self.migrate(self.lp_address.unwrap(), lp_amount, target_pool_address)
+    }
+
     pub fn flash_loan(
         &mut self,
         loan_address: ResourceAddress,
@@ -707,6 +1053,19 @@ impl PoolTestHelper {
         self
     }
 
+    pub fn observation_intervals(&mut self, intervals: Vec<(u64, u64)>) -> &mut PoolTestHelper {
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder.call_method(
+            self.pool_address.unwrap(),
+            "observation_intervals",
+            manifest_args!(intervals),
+        );
+        self.registry
+            .env
+            .new_instruction("observation_intervals", 1, 0);
+        self
+    }
+
     pub fn jump_to_timestamp_seconds(&mut self, seconds: u64) {
         let current_time = self
             .registry
@@ -1005,6 +1364,10 @@ impl PoolTestHelper {
         self.lock_metadata("hook_packages")
     }
 
+    pub fn set_whitelist_successor(&mut self, package_name: &str) -> &mut PoolTestHelper {
+        self.set_whitelist_packages("successor_packages", vec![package_name])
+    }
+
     pub fn set_whitelist_packages(
         &mut self,
         metadata_key: &str,
@@ -1055,6 +1418,228 @@ impl PoolTestHelper {
         self.registry.env.new_instruction("lock_metadata", 2, 1);
         self
     }
+
+    pub fn instantiate_factory(&mut self) -> &mut PoolTestHelper {
+        let package_address = self.registry.env.package_address("pool");
+        let admin_badge_address = self.admin_badge_address();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder.call_function(
+            package_address,
+            "Factory",
+            "instantiate",
+            manifest_args!(admin_badge_address),
+        );
+        self.registry
+            .env
+            .new_instruction("instantiate_factory", 1, 0);
+        self
+    }
+
+    pub fn instantiate_factory_direct(&mut self) -> ComponentAddress {
+        let receipt = self
+            .instantiate_factory()
+            .registry
+            .execute_expect_success(false);
+        let factory_address: ComponentAddress = receipt.outputs("instantiate_factory")[0];
+        self.factory_address = Some(factory_address);
+        factory_address
+    }
+
+    pub fn factory_instantiate_pool(
+        &mut self,
+        a_address: ResourceAddress,
+        b_address: ResourceAddress,
+        input_fee_rate: Decimal,
+        a_share: Decimal,
+    ) -> &mut PoolTestHelper {
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        let hooks_buckets: Vec<(ComponentAddress, ManifestBucket)> = Vec::new();
+        self.registry.env.manifest_builder = manifest_builder.call_method(
+            factory_address,
+            "instantiate_pool",
+            manifest_args!(
+                a_address,
+                b_address,
+                input_fee_rate,
+                dec!(0.009),
+                a_share,
+                hooks_buckets,
+            ),
+        );
+        self.registry
+            .env
+            .new_instruction("factory_instantiate_pool", 1, 0);
+        self
+    }
+
+    pub fn factory_instantiate_pool_direct(
+        &mut self,
+        a_address: ResourceAddress,
+        b_address: ResourceAddress,
+        input_fee_rate: Decimal,
+        a_share: Decimal,
+    ) -> Receipt {
+        let receipt = self
+            .factory_instantiate_pool(a_address, b_address, input_fee_rate, a_share)
+            .registry
+            .execute_expect_success(false);
+        let commit_result = receipt.execution_receipt.expect_commit_success();
+        let (pool_address, lp_address): (ComponentAddress, ResourceAddress) =
+            receipt.outputs("factory_instantiate_pool")[0];
+        self.pool_address = Some(pool_address);
+        self.lp_address = Some(lp_address);
+        self.liquidity_pool_address = Some(commit_result.new_component_addresses()[1]);
+        receipt
+    }
+
+    pub fn factory_instantiate_pool_with_liquidity(
+        &mut self,
+        a_address: ResourceAddress,
+        a_amount: Decimal,
+        b_address: ResourceAddress,
+        b_amount: Decimal,
+        input_fee_rate: Decimal,
+    ) -> &mut PoolTestHelper {
+        let account = self.registry.env.account;
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        let hooks_buckets: Vec<(ComponentAddress, ManifestBucket)> = Vec::new();
+        self.registry.env.manifest_builder = manifest_builder
+            .withdraw_from_account(account, a_address, a_amount)
+            .withdraw_from_account(account, b_address, b_amount)
+            .take_from_worktop(a_address, a_amount, self.registry.name("a_bucket"))
+            .take_from_worktop(b_address, b_amount, self.registry.name("b_bucket"))
+            .with_name_lookup(|builder, lookup| {
+                let a_bucket = lookup.bucket(self.registry.name("a_bucket"));
+                let b_bucket = lookup.bucket(self.registry.name("b_bucket"));
+                builder.call_method(
+                    factory_address,
+                    "instantiate_pool_with_liquidity",
+                    manifest_args!(
+                        a_bucket,
+                        b_bucket,
+                        input_fee_rate,
+                        dec!(0.009),
+                        dec!(0.5),
+                        hooks_buckets,
+                    ),
+                )
+            });
+        self.registry
+            .env
+            .new_instruction("factory_instantiate_pool_with_liquidity", 5, 4);
+        self
+    }
+
+    pub fn factory_set_canonical_pool(
+        &mut self,
+        pool_address: ComponentAddress,
+    ) -> &mut PoolTestHelper {
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder
+            .create_proof_from_account_of_amount(
+                self.registry.env().account,
+                self.admin_badge_address(),
+                dec!(1),
+            )
+            .call_method(
+                factory_address,
+                "set_canonical_pool",
+                manifest_args!(pool_address),
+            );
+        self.registry
+            .env
+            .new_instruction("factory_set_canonical_pool", 2, 1);
+        self
+    }
+
+    pub fn factory_pools_for_pair(
+        &mut self,
+        a_address: ResourceAddress,
+        b_address: ResourceAddress,
+        start: u64,
+        limit: u64,
+    ) -> &mut PoolTestHelper {
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder.call_method(
+            factory_address,
+            "pools_for_pair",
+            manifest_args!(a_address, b_address, start, limit),
+        );
+        self.registry.env.new_instruction("pools_for_pair", 1, 0);
+        self
+    }
+
+    pub fn factory_pair_pool_count(
+        &mut self,
+        a_address: ResourceAddress,
+        b_address: ResourceAddress,
+    ) -> &mut PoolTestHelper {
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder.call_method(
+            factory_address,
+            "pair_pool_count",
+            manifest_args!(a_address, b_address),
+        );
+        self.registry.env.new_instruction("pair_pool_count", 1, 0);
+        self
+    }
+
+    pub fn factory_pool_for_key(
+        &mut self,
+        a_address: ResourceAddress,
+        b_address: ResourceAddress,
+        input_fee_rate: Decimal,
+        a_share: Decimal,
+    ) -> &mut PoolTestHelper {
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder.call_method(
+            factory_address,
+            "pool_for_key",
+            manifest_args!(a_address, b_address, input_fee_rate, a_share),
+        );
+        self.registry.env.new_instruction("pool_for_key", 1, 0);
+        self
+    }
+
+    pub fn factory_canonical_pool(
+        &mut self,
+        a_address: ResourceAddress,
+        b_address: ResourceAddress,
+    ) -> &mut PoolTestHelper {
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder = manifest_builder.call_method(
+            factory_address,
+            "canonical_pool",
+            manifest_args!(a_address, b_address),
+        );
+        self.registry.env.new_instruction("canonical_pool", 1, 0);
+        self
+    }
+
+    pub fn factory_pools(&mut self, start: u64, limit: u64) -> &mut PoolTestHelper {
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder =
+            manifest_builder.call_method(factory_address, "pools", manifest_args!(start, limit));
+        self.registry.env.new_instruction("pools", 1, 0);
+        self
+    }
+
+    pub fn factory_pool_count(&mut self) -> &mut PoolTestHelper {
+        let factory_address = self.factory_address.unwrap();
+        let manifest_builder = mem::take(&mut self.registry.env.manifest_builder);
+        self.registry.env.manifest_builder =
+            manifest_builder.call_method(factory_address, "pool_count", manifest_args!());
+        self.registry.env.new_instruction("pool_count", 1, 0);
+        self
+    }
 }
 
 pub trait AttoDecimal {
@@ -1,3 +1,7 @@
+pub mod benchmark;
 pub mod helper;
+pub mod simulation;
 
+pub use benchmark::*;
 pub use helper::*;
+pub use simulation::*;
@@ -0,0 +1,457 @@
+//! Seeded simulation of multiple actors interacting with a pool over many time steps.
+//!
+//! All actors share the account of the test environment, while the simulation keeps track of the LP position of
+//! each liquidity provider. Runs are fully deterministic for a given seed, which allows exporting them as CSV and
+//! comparing them across changes of the pool.
+
+use std::fmt::Write as _;
+use std::mem;
+
+use ociswap_pool_events::SwapEvent;
+use oracle::ObservationInterval;
+use radix_engine::system::system_modules::execution_trace::ResourceSpecifier::Amount;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+
+use crate::PoolTestHelper;
+
+/// SplitMix64 generator, which is sufficient for simulations and avoids an additional dependency.
+#[derive(Clone, Debug)]
+pub struct SimulationRng {
+    state: u64,
+}
+
+impl SimulationRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
+    /// Returns a uniformly distributed amount in `[min, max)`.
+    pub fn amount(&mut self, min: Decimal, max: Decimal) -> Decimal {
+        to_decimal(to_f64(min) + self.next_f64() * to_f64(max - min))
+    }
+}
+
+/// Participants of a simulation, acting once per step in the order they were added.
+#[derive(Clone, Debug)]
+pub enum Actor {
+    /// Enters with `x_amount` of token X (and the matching amount of token Y) if not invested,
+    /// otherwise exits with the whole position, each with the given probability per step.
+    LiquidityProvider {
+        x_amount: Decimal,
+        enter_probability: f64,
+        exit_probability: f64,
+    },
+    /// Swaps the pool price towards the reference price, as long as the deviation exceeds the input fee rate
+    /// plus `min_deviation`.
+    Arbitrageur { min_deviation: f64 },
+    /// Swaps a random amount of up to `max_x_amount` worth of token X in a random direction with the given
+    /// probability per step.
+    Trader {
+        max_x_amount: Decimal,
+        probability: f64,
+    },
+    /// Calls a method without arguments on a component every step, e.g. `update_input_fee_rate` of a dynamic fee
+    /// hook.
+    Keeper {
+        component_address: ComponentAddress,
+        method: String,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    pub seed: u64,
+    pub steps: usize,
+    pub step_seconds: u64,
+    /// Reference price of token X in token Y at the start of the simulation.
+    pub reference_price: Decimal,
+    /// Maximum relative change of the reference price per step, following a seeded random walk.
+    pub reference_volatility: f64,
+}
+
+/// State of the pool recorded at the end of a step. Volumes and fees are cumulative since the start of the run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationStep {
+    pub step: usize,
+    pub timestamp: u64,
+    pub reference_price: Decimal,
+    pub price: Option<PreciseDecimal>,
+    pub x_reserve: Decimal,
+    pub y_reserve: Decimal,
+    pub lp_total_supply: Decimal,
+    /// Value of one LP token in token Y at the reference price.
+    pub lp_value: Option<Decimal>,
+    pub swaps: u64,
+    pub x_volume: Decimal,
+    pub y_volume: Decimal,
+    pub x_fees_lp: Decimal,
+    pub y_fees_lp: Decimal,
+    pub x_fees_protocol: Decimal,
+    pub y_fees_protocol: Decimal,
+    /// Time weighted average `price_sqrt` of the oracle over the step, if the oracle covers the whole step.
+    pub oracle_price_sqrt: Option<Decimal>,
+}
+
+pub struct Simulation<'a> {
+    pub helper: &'a mut PoolTestHelper,
+    pub config: SimulationConfig,
+    pub actors: Vec<Actor>,
+    /// LP tokens held by each actor, only non-zero for liquidity providers.
+    pub lp_positions: Vec<Decimal>,
+    pub steps: Vec<SimulationStep>,
+    rng: SimulationRng,
+    reference_price: Decimal,
+    totals: SwapTotals,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SwapTotals {
+    swaps: u64,
+    x_volume: Decimal,
+    y_volume: Decimal,
+    x_fees_lp: Decimal,
+    y_fees_lp: Decimal,
+    x_fees_protocol: Decimal,
+    y_fees_protocol: Decimal,
+}
+
+struct PoolSnapshot {
+    x_reserve: Decimal,
+    y_reserve: Decimal,
+    lp_total_supply: Decimal,
+    price_sqrt: Option<PreciseDecimal>,
+    x_share: Decimal,
+    input_fee_rate: Decimal,
+}
+
+impl<'a> Simulation<'a> {
+    /// Creates a simulation for the instantiated pool of `helper`.
+    pub fn new(
+        helper: &'a mut PoolTestHelper,
+        config: SimulationConfig,
+        actors: Vec<Actor>,
+    ) -> Self {
+        Self {
+            helper,
+            rng: SimulationRng::new(config.seed),
+            reference_price: config.reference_price,
+            lp_positions: vec![Decimal::ZERO; actors.len()],
+            actors,
+            config,
+            steps: Vec::new(),
+            totals: SwapTotals::default(),
+        }
+    }
+
+    /// Runs all steps of the simulation and returns the recorded steps.
+    pub fn run(&mut self) -> &[SimulationStep] {
+        for step in 0..self.config.steps {
+            self.step(step);
+        }
+        &self.steps
+    }
+
+    /// Runs the next step of the simulation and returns it, which allows inspecting the pool or its hooks in between.
+    pub fn run_step(&mut self) -> &SimulationStep {
+        self.step(self.steps.len());
+        self.steps.last().unwrap()
+    }
+
+    fn step(&mut self, step: usize) {
+        let start = self.current_time();
+        let change = (self.rng.next_f64() * 2.0 - 1.0) * self.config.reference_volatility;
+        self.reference_price = to_decimal(to_f64(self.reference_price) * (1.0 + change));
+
+        for index in 0..self.actors.len() {
+            self.act(index);
+        }
+
+        self.helper
+            .advance_timestamp_by_seconds(self.config.step_seconds);
+        let end = self.current_time();
+        let snapshot = self.snapshot();
+        let lp_value = if snapshot.lp_total_supply.is_positive() {
+            Some(
+                (snapshot.x_reserve * self.reference_price + snapshot.y_reserve)
+                    / snapshot.lp_total_supply,
+            )
+        } else {
+            None
+        };
+        let oracle_price_sqrt = self.oracle_price_sqrt(start, end);
+        self.steps.push(SimulationStep {
+            step,
+            timestamp: end,
+            reference_price: self.reference_price,
+            price: snapshot
+                .price_sqrt
+                .map(|price_sqrt| price_sqrt * price_sqrt),
+            x_reserve: snapshot.x_reserve,
+            y_reserve: snapshot.y_reserve,
+            lp_total_supply: snapshot.lp_total_supply,
+            lp_value,
+            swaps: self.totals.swaps,
+            x_volume: self.totals.x_volume,
+            y_volume: self.totals.y_volume,
+            x_fees_lp: self.totals.x_fees_lp,
+            y_fees_lp: self.totals.y_fees_lp,
+            x_fees_protocol: self.totals.x_fees_protocol,
+            y_fees_protocol: self.totals.y_fees_protocol,
+            oracle_price_sqrt,
+        });
+    }
+
+    fn act(&mut self, index: usize) {
+        match self.actors[index].clone() {
+            Actor::LiquidityProvider {
+                x_amount,
+                enter_probability,
+                exit_probability,
+            } => {
+                if self.lp_positions[index].is_zero() {
+                    if self.rng.chance(enter_probability) {
+                        self.enter(index, x_amount);
+                    }
+                } else if self.rng.chance(exit_probability) {
+                    self.exit(index);
+                }
+            }
+            Actor::Arbitrageur { min_deviation } => self.arbitrage(min_deviation),
+            Actor::Trader {
+                max_x_amount,
+                probability,
+            } => {
+                if self.rng.chance(probability) {
+                    let x_amount = self.rng.amount(Decimal::ZERO, max_x_amount);
+                    if self.rng.chance(0.5) {
+                        self.swap(self.helper.x_address(), x_amount);
+                    } else {
+                        self.swap(self.helper.y_address(), x_amount * self.reference_price);
+                    }
+                }
+            }
+            Actor::Keeper {
+                component_address,
+                method,
+            } => {
+                let manifest_builder = mem::take(&mut self.helper.registry.env.manifest_builder);
+                self.helper.registry.env.manifest_builder =
+                    manifest_builder.call_method(component_address, &method, manifest_args!());
+                self.helper.registry.env.new_instruction(&method, 1, 0);
+                self.helper.registry.execute_expect_success(false);
+            }
+        }
+    }
+
+    fn enter(&mut self, index: usize, x_amount: Decimal) {
+        let snapshot = self.snapshot();
+        let y_amount = if snapshot.x_reserve.is_positive() {
+            x_amount * snapshot.y_reserve / snapshot.x_reserve
+        } else {
+            x_amount * self.reference_price
+        };
+        let receipt = self
+            .helper
+            .add_liquidity_default(x_amount, y_amount)
+            .registry
+            .execute_expect_success(false);
+        let output_buckets = receipt.output_buckets("add_liquidity");
+        if let Amount(lp_address, lp_amount) = output_buckets[0][0] {
+            self.helper.lp_address = Some(lp_address);
+            self.lp_positions[index] = lp_amount;
+        }
+    }
+
+    fn exit(&mut self, index: usize) {
+        let lp_amount = mem::take(&mut self.lp_positions[index]);
+        self.helper
+            .remove_liquidity_default(lp_amount)
+            .registry
+            .execute_expect_success(false);
+    }
+
+    /// Swaps the net input amount which moves the marginal price after fees to the reference price, based on the
+    /// weighted invariant `x^x_share * y^y_share`.
+    fn arbitrage(&mut self, min_deviation: f64) {
+        let snapshot = self.snapshot();
+        let price_sqrt = match snapshot.price_sqrt {
+            Some(price_sqrt) => to_f64(price_sqrt),
+            None => return,
+        };
+        let price = price_sqrt * price_sqrt;
+        let reference_price = to_f64(self.reference_price);
+        let fee_rate = to_f64(snapshot.input_fee_rate);
+        let (x, y) = (to_f64(snapshot.x_reserve), to_f64(snapshot.y_reserve));
+        let x_share = to_f64(snapshot.x_share);
+        let ratio = x_share / (1.0 - x_share);
+
+        if price * (1.0 - fee_rate) > reference_price * (1.0 + min_deviation) {
+            // Sell X: price' = ratio * y * x^ratio / x'^(ratio + 1)
+            let target_price = reference_price / (1.0 - fee_rate);
+            let x_target = (ratio * y * x.powf(ratio) / target_price).powf(1.0 / (ratio + 1.0));
+            let input_amount = (x_target - x) / (1.0 - fee_rate);
+            self.swap(self.helper.x_address(), to_decimal(input_amount));
+        } else if price < reference_price * (1.0 - fee_rate) * (1.0 - min_deviation) {
+            // Buy X: price' = ratio * y'^(1 + 1 / ratio) / (x * y^(1 / ratio))
+            let target_price = reference_price * (1.0 - fee_rate);
+            let y_target =
+                (target_price * x * y.powf(1.0 / ratio) / ratio).powf(ratio / (ratio + 1.0));
+            let input_amount = (y_target - y) / (1.0 - fee_rate);
+            self.swap(self.helper.y_address(), to_decimal(input_amount));
+        }
+    }
+
+    fn swap(&mut self, input_address: ResourceAddress, input_amount: Decimal) {
+        if !input_amount.is_positive() {
+            return;
+        }
+        let receipt = self
+            .helper
+            .swap(input_address, input_amount)
+            .registry
+            .execute_expect_success(false);
+        let pool_node_id = *self.helper.pool_address.unwrap().as_node_id();
+        let x_address = self.helper.x_address();
+        let commit_result = receipt.execution_receipt.expect_commit_success();
+        for (EventTypeIdentifier(emitter, name), payload) in &commit_result.application_events {
+            let is_pool = matches!(emitter, Emitter::Method(node_id, ModuleId::Main) if *node_id == pool_node_id);
+            if !is_pool || name != "SwapEvent" {
+                continue;
+            }
+            let event: SwapEvent = scrypto_decode(payload).expect("Failed to decode SwapEvent");
+            let totals = &mut self.totals;
+            totals.swaps += 1;
+            if event.input_address == x_address {
+                totals.x_volume += event.input_gross_amount;
+                totals.x_fees_lp += event.input_fee_lp;
+                totals.x_fees_protocol += event.input_fee_protocol;
+            } else {
+                totals.y_volume += event.input_gross_amount;
+                totals.y_fees_lp += event.input_fee_lp;
+                totals.y_fees_protocol += event.input_fee_protocol;
+            }
+        }
+    }
+
+    fn snapshot(&mut self) -> PoolSnapshot {
+        let (x_address, y_address) = (self.helper.x_address(), self.helper.y_address());
+        self.helper
+            .total_liquidity()
+            .lp_total_supply()
+            .price_sqrt()
+            .x_share()
+            .input_fee_rate();
+        let receipt = self.helper.registry.execute_expect_success(false);
+        let total_liquidity: IndexMap<ResourceAddress, Decimal> =
+            receipt.outputs("total_liquidity")[0].clone();
+        PoolSnapshot {
+            x_reserve: total_liquidity[&x_address],
+            y_reserve: total_liquidity[&y_address],
+            lp_total_supply: receipt.outputs("lp_total_supply")[0],
+            price_sqrt: receipt.outputs("price_sqrt")[0],
+            x_share: receipt.outputs("x_share")[0],
+            input_fee_rate: receipt.outputs("input_fee_rate")[0],
+        }
+    }
+
+    fn oracle_price_sqrt(&mut self, start: u64, end: u64) -> Option<Decimal> {
+        let receipt = self
+            .helper
+            .oldest_observation_at()
+            .registry
+            .execute_expect_success(false);
+        let oldest_observation_at: Option<u64> = receipt.outputs("oldest_observation_at")[0];
+        if !oldest_observation_at
+            .is_some_and(|oldest_observation_at| oldest_observation_at <= start)
+        {
+            return None;
+        }
+
+        let receipt = self
+            .helper
+            .observation_intervals(vec![(start, end)])
+            .registry
+            .execute_expect_success(false);
+        let observation_intervals = receipt
+            .outputs::<Vec<ObservationInterval>>("observation_intervals")
+            .remove(0);
+        Some(observation_intervals[0].price_sqrt)
+    }
+
+    fn current_time(&mut self) -> u64 {
+        self.helper
+            .registry
+            .env
+            .test_runner
+            .get_current_time(TimePrecision::Second)
+            .seconds_since_unix_epoch as u64
+    }
+
+    /// Returns the recorded steps as CSV with a header line.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "step,timestamp,reference_price,price,x_reserve,y_reserve,lp_total_supply,lp_value,swaps,\
+             x_volume,y_volume,x_fees_lp,y_fees_lp,x_fees_protocol,y_fees_protocol,oracle_price_sqrt\n",
+        );
+        for step in &self.steps {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                step.step,
+                step.timestamp,
+                step.reference_price,
+                optional(step.price),
+                step.x_reserve,
+                step.y_reserve,
+                step.lp_total_supply,
+                optional(step.lp_value),
+                step.swaps,
+                step.x_volume,
+                step.y_volume,
+                step.x_fees_lp,
+                step.y_fees_lp,
+                step.x_fees_protocol,
+                step.y_fees_protocol,
+                optional(step.oracle_price_sqrt),
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// Writes the recorded steps as CSV to `path`.
+    pub fn write_csv(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_csv())
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn to_f64(value: impl ToString) -> f64 {
+    value.to_string().parse().unwrap()
+}
+
+fn to_decimal(value: f64) -> Decimal {
+    Decimal::try_from(format!("{:.18}", value.max(0.0)).as_str()).unwrap()
+}
@@ -1,2 +1,4 @@
+pub mod test_guardian;
 pub mod test_hook;
 pub mod test_hook_swap;
+pub mod test_registry;
@@ -0,0 +1,48 @@
+use scrypto::prelude::*;
+
+#[blueprint]
+mod test_guardian {
+    struct TestGuardian {}
+
+    impl TestGuardian {
+        pub fn instantiate() -> Global<TestGuardian> {
+            Self {}
+                .instantiate()
+                .prepare_to_globalize(OwnerRole::None)
+                .globalize()
+        }
+
+        pub fn pause(&mut self, pool_address: ComponentAddress) {
+            let pool: Global<AnyComponent> = pool_address.into();
+            pool.call::<(), ()>("pause", &());
+        }
+
+        pub fn unpause(&mut self, pool_address: ComponentAddress) {
+            let pool: Global<AnyComponent> = pool_address.into();
+            pool.call::<(), ()>("unpause", &());
+        }
+
+        pub fn propose_fee_rates(
+            &mut self,
+            pool_address: ComponentAddress,
+            input_fee_rate: Decimal,
+            flash_loan_fee_rate: Decimal,
+        ) -> u64 {
+            let pool: Global<AnyComponent> = pool_address.into();
+            pool.call::<(Decimal, Decimal), u64>(
+                "propose_fee_rates",
+                &(input_fee_rate, flash_loan_fee_rate),
+            )
+        }
+
+        pub fn defer_registry_sync(&mut self, pool_address: ComponentAddress, seconds: u64) -> u64 {
+            let pool: Global<AnyComponent> = pool_address.into();
+            pool.call::<(u64,), u64>("defer_registry_sync", &(seconds,))
+        }
+
+        pub fn cancel_fee_rates(&mut self, pool_address: ComponentAddress) {
+            let pool: Global<AnyComponent> = pool_address.into();
+            pool.call::<(), ()>("cancel_fee_rates", &());
+        }
+    }
+}
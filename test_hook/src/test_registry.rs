@@ -0,0 +1,84 @@
+use scrypto::prelude::*;
+
+/// Mirrors the pool's `RegistryConfig`, which the pool decodes from the `sync` response.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub struct TestRegistryConfig {
+    pub fee_protocol_share: Decimal,
+    pub next_sync_time: u64,
+    pub flash_loan_fee_protocol_share: Decimal,
+    pub paused: Option<bool>,
+    pub flash_loan_max_fraction: Option<Decimal>,
+}
+
+/// Mirrors the pool's versioned `RegistrySyncResponse`.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+pub enum TestRegistrySyncResponse {
+    V1(TestRegistryConfig),
+}
+
+#[blueprint]
+mod test_registry {
+    struct TestRegistry {
+        config: TestRegistryConfig,
+        fees: KeyValueStore<ResourceAddress, Vault>,
+        broken: bool,
+    }
+
+    impl TestRegistry {
+        pub fn instantiate(config: TestRegistryConfig) -> Global<TestRegistry> {
+            Self {
+                config,
+                fees: KeyValueStore::new(),
+                broken: false,
+            }
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::None)
+            .globalize()
+        }
+
+        pub fn set_config(&mut self, config: TestRegistryConfig) {
+            self.config = config;
+        }
+
+        pub fn set_broken(&mut self, broken: bool) {
+            self.broken = broken;
+        }
+
+        pub fn sync(
+            &mut self,
+            _pool_address: ComponentAddress,
+            x_bucket: Bucket,
+            y_bucket: Bucket,
+        ) -> TestRegistrySyncResponse {
+            assert!(!self.broken, "[TestRegistry]: Registry is broken.");
+            self.deposit(x_bucket);
+            self.deposit(y_bucket);
+            TestRegistrySyncResponse::V1(self.config.clone())
+        }
+
+        pub fn sync_lp_tokens(
+            &mut self,
+            pool_address: ComponentAddress,
+            x_bucket: Bucket,
+            y_bucket: Bucket,
+            lp_bucket: Bucket,
+        ) -> TestRegistrySyncResponse {
+            self.deposit(lp_bucket);
+            self.sync(pool_address, x_bucket, y_bucket)
+        }
+
+        pub fn fee_amount(&self, address: ResourceAddress) -> Decimal {
+            self.fees
+                .get(&address)
+                .map_or(Decimal::ZERO, |vault| vault.amount())
+        }
+
+        fn deposit(&mut self, bucket: Bucket) {
+            let address = bucket.resource_address();
+            if self.fees.get(&address).is_none() {
+                self.fees.insert(address, Vault::new(address));
+            }
+            self.fees.get_mut(&address).unwrap().put(bucket);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use ociswap_pool_hooks::HookCall;
 use ociswap_pool_test_helper::*;
 use radix_transactions::prelude::ManifestBuilder;
@@ -5,6 +7,7 @@ use scrypto::prelude::*;
 use scrypto_testenv::*;
 use std::mem;
 use test_hook::test_hook::TestAccess;
+use test_hook::test_registry::TestRegistryConfig;
 
 pub struct HookTestTestHelper {
     pub pool: PoolTestHelper,
@@ -37,6 +40,21 @@ impl HookTestTestHelper {
         }
     }
 
+    pub fn new_with_guardian() -> (HookTestTestHelper, ComponentAddress) {
+        let mut helper = HookTestTestHelper::new();
+        let guardian_address = helper.instantiate_test_guardian_output();
+        helper
+            .pool
+            .set_metadata("guardian", guardian_address)
+            .registry
+            .execute_expect_success(false);
+        helper.pool.instantiate_default(false);
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10), dec!(10));
+        (helper, guardian_address)
+    }
+
     pub fn instantiate_test_hook(
         &mut self,
         calls: Vec<HookCall>,
@@ -79,6 +97,191 @@ impl HookTestTestHelper {
         (hook_badge_address[0].0, new_resource_ads[0])
     }
 
+    pub fn instantiate_test_swap_hook_output(&mut self) -> (ComponentAddress, ResourceAddress) {
+        let package_address = self.env().package_address("test_hook");
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "TestSwapHook",
+            "instantiate",
+            manifest_args!(self.pool.x_address(), self.pool.y_address()),
+        );
+        self.env()
+            .new_instruction("instantiate_test_swap_hook", 1, 0);
+
+        let receipt = self.execute_expect_success(false);
+        let new_resource_ads = receipt
+            .execution_receipt
+            .expect_commit_success()
+            .new_resource_addresses();
+        let hook_badge_address: Vec<(ComponentAddress, Bucket)> =
+            receipt.outputs("instantiate_test_swap_hook");
+        (hook_badge_address[0].0, new_resource_ads[0])
+    }
+
+    pub fn set_hook_input_fee_rates(
+        &mut self,
+        hook_address: ComponentAddress,
+        before_swap_input_fee_rate: Option<Decimal>,
+        after_swap_input_fee_rate: Option<Decimal>,
+    ) -> &mut HookTestTestHelper {
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder = manifest_builder.call_method(
+            hook_address,
+            "set_input_fee_rates",
+            manifest_args!(before_swap_input_fee_rate, after_swap_input_fee_rate),
+        );
+        self.env().new_instruction("set_input_fee_rates", 1, 0);
+        self
+    }
+
+    pub fn instantiate_test_guardian_output(&mut self) -> ComponentAddress {
+        let package_address = self.env().package_address("test_hook");
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "TestGuardian",
+            "instantiate",
+            manifest_args!(),
+        );
+        self.env()
+            .new_instruction("instantiate_test_guardian", 1, 0);
+
+        let receipt = self.execute_expect_success(false);
+        let guardian_address: Vec<ComponentAddress> = receipt.outputs("instantiate_test_guardian");
+        guardian_address[0]
+    }
+
+    pub fn guardian_call(
+        &mut self,
+        guardian_address: ComponentAddress,
+        method_name: &str,
+    ) -> &mut HookTestTestHelper {
+        let pool_address = self.pool.pool_address.unwrap();
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder = manifest_builder.call_method(
+            guardian_address,
+            method_name,
+            manifest_args!(pool_address),
+        );
+        self.env().new_instruction(method_name, 1, 0);
+        self
+    }
+
+    pub fn guardian_propose_fee_rates(
+        &mut self,
+        guardian_address: ComponentAddress,
+        input_fee_rate: Decimal,
+        flash_loan_fee_rate: Decimal,
+    ) -> &mut HookTestTestHelper {
+        let pool_address = self.pool.pool_address.unwrap();
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder = manifest_builder.call_method(
+            guardian_address,
+            "propose_fee_rates",
+            manifest_args!(pool_address, input_fee_rate, flash_loan_fee_rate),
+        );
+        self.env().new_instruction("propose_fee_rates", 1, 0);
+        self
+    }
+
+    pub fn instantiate_test_registry_output(
+        &mut self,
+        config: TestRegistryConfig,
+    ) -> ComponentAddress {
+        let package_address = self.env().package_address("test_hook");
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder = manifest_builder.call_function(
+            package_address,
+            "TestRegistry",
+            "instantiate",
+            manifest_args!(config),
+        );
+        self.env()
+            .new_instruction("instantiate_test_registry", 1, 0);
+
+        let receipt = self.execute_expect_success(false);
+        let registry_address: Vec<ComponentAddress> = receipt.outputs("instantiate_test_registry");
+        registry_address[0]
+    }
+
+    pub fn instantiate_pool_with_registry(
+        &mut self,
+        registry_address: ComponentAddress,
+        input_fee_rate: Decimal,
+    ) {
+        self.pool
+            .set_metadata("registry", registry_address)
+            .registry
+            .execute_expect_success(false);
+        let (x_address, y_address) = (self.pool.x_address(), self.pool.y_address());
+        let receipt = self
+            .pool
+            .instantiate(x_address, y_address, input_fee_rate, dec!(0.5))
+            .registry
+            .execute_expect_success(false);
+        let commit_result = receipt.execution_receipt.expect_commit_success();
+        let (pool_address, lp_address): (ComponentAddress, ResourceAddress) =
+            receipt.outputs("instantiate")[0];
+        self.pool.pool_address = Some(pool_address);
+        self.pool.lp_address = Some(lp_address);
+        self.pool.liquidity_pool_address = Some(commit_result.new_component_addresses()[1]);
+    }
+
+    pub fn registry_fee_amount(
+        &mut self,
+        registry_address: ComponentAddress,
+        address: ResourceAddress,
+    ) -> Decimal {
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder =
+            manifest_builder.call_method(registry_address, "fee_amount", manifest_args!(address));
+        self.env().new_instruction("fee_amount", 1, 0);
+        let receipt = self.execute_expect_success(false);
+        let fee_amount: Vec<Decimal> = receipt.outputs("fee_amount");
+        fee_amount[0]
+    }
+
+    pub fn set_registry_broken(
+        &mut self,
+        registry_address: ComponentAddress,
+        broken: bool,
+    ) -> &mut HookTestTestHelper {
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder =
+            manifest_builder.call_method(registry_address, "set_broken", manifest_args!(broken));
+        self.env().new_instruction("set_broken", 1, 0);
+        self
+    }
+
+    pub fn set_registry_config(
+        &mut self,
+        registry_address: ComponentAddress,
+        config: TestRegistryConfig,
+    ) -> &mut HookTestTestHelper {
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder =
+            manifest_builder.call_method(registry_address, "set_config", manifest_args!(config));
+        self.env().new_instruction("set_config", 1, 0);
+        self
+    }
+
+    pub fn guardian_defer_registry_sync(
+        &mut self,
+        guardian_address: ComponentAddress,
+        seconds: u64,
+    ) -> &mut HookTestTestHelper {
+        let pool_address = self.pool.pool_address.unwrap();
+        let manifest_builder = mem::take(&mut self.env().manifest_builder);
+        self.env().manifest_builder = manifest_builder.call_method(
+            guardian_address,
+            "defer_registry_sync",
+            manifest_args!(pool_address, seconds),
+        );
+        self.env().new_instruction("defer_registry_sync", 1, 0);
+        self
+    }
+
     pub fn execute_all_calls(&mut self, hooks: Vec<(ComponentAddress, ResourceAddress)>) {
         self.pool.set_whitelist_registry();
         self.pool.set_whitelist_hook("test_hook");
@@ -0,0 +1,204 @@
+mod helper;
+
+#[cfg(test)]
+mod test_pool_fee_governance {
+    use super::*;
+    use helper::HookTestTestHelper;
+    use ociswap_pool::constants::FEE_RATES_UPDATE_TIMELOCK;
+    use pretty_assertions::assert_eq;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+
+    fn instantiate_with_swap_hook() -> (HookTestTestHelper, ComponentAddress) {
+        let mut helper = HookTestTestHelper::new();
+        let (hook_address, hook_badge_address) = helper.instantiate_test_swap_hook_output();
+        helper.pool.set_whitelist_registry();
+        helper.pool.set_whitelist_hook("test_hook");
+        helper
+            .pool
+            .instantiate_default_with_hooks(vec![(hook_address, hook_badge_address)], false);
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10), dec!(10));
+        (helper, hook_address)
+    }
+
+    fn fee_rates(helper: &mut HookTestTestHelper) -> (Decimal, Decimal) {
+        let receipt = helper
+            .pool
+            .input_fee_rate()
+            .flash_loan_fee_rate()
+            .registry
+            .execute_expect_success(false);
+        let input_fee_rate: Vec<Decimal> = receipt.outputs("input_fee_rate");
+        let flash_loan_fee_rate: Vec<Decimal> = receipt.outputs("flash_loan_fee_rate");
+        (input_fee_rate[0], flash_loan_fee_rate[0])
+    }
+
+    fn pending_fee_rates(helper: &mut HookTestTestHelper) -> Option<(Decimal, Decimal, u64)> {
+        let receipt = helper
+            .pool
+            .getter("pending_fee_rates")
+            .registry
+            .execute_expect_success(false);
+        let outputs: Vec<Option<(Decimal, Decimal, u64)>> = receipt.outputs("pending_fee_rates");
+        outputs[0]
+    }
+
+    fn metadata_value(helper: &mut HookTestTestHelper, key: &str) -> Option<MetadataValue> {
+        let pool_address = helper.pool.pool_address.unwrap();
+        helper
+            .env()
+            .test_runner
+            .get_metadata(pool_address.into(), key)
+    }
+
+    #[test]
+    fn test_propose_fee_rates() {
+        let (mut helper, guardian_address) = HookTestTestHelper::new_with_guardian();
+        helper
+            .guardian_propose_fee_rates(guardian_address, dec!(0.01), dec!(0.005))
+            .execute_expect_success(false);
+
+        let (input_fee_rate, flash_loan_fee_rate, _) = pending_fee_rates(&mut helper).unwrap();
+        assert_eq!(
+            (input_fee_rate, flash_loan_fee_rate),
+            (dec!(0.01), dec!(0.005))
+        );
+        assert_eq!(fee_rates(&mut helper), (dec!(0), dec!(0.009)));
+    }
+
+    #[test]
+    fn test_propose_fee_rates_above_limit() {
+        let (mut helper, guardian_address) = HookTestTestHelper::new_with_guardian();
+        helper
+            .guardian_propose_fee_rates(
+                guardian_address,
+                dec!(0.1) + dec!(0.000000000000000001),
+                dec!(0),
+            )
+            .execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_propose_fee_rates_not_guardian() {
+        let (mut helper, _) = HookTestTestHelper::new_with_guardian();
+        let pool_address = helper.pool.pool_address.unwrap();
+        let manifest_builder = std::mem::take(&mut helper.env().manifest_builder);
+        helper.env().manifest_builder = manifest_builder.call_method(
+            pool_address,
+            "propose_fee_rates",
+            manifest_args!(dec!(0.01), dec!(0.005)),
+        );
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_apply_fee_rates_timelocked() {
+        let (mut helper, guardian_address) = HookTestTestHelper::new_with_guardian();
+        helper
+            .guardian_propose_fee_rates(guardian_address, dec!(0.01), dec!(0.005))
+            .execute_expect_success(false);
+        helper
+            .pool
+            .advance_timestamp_by_seconds(FEE_RATES_UPDATE_TIMELOCK - 1);
+        helper.pool.getter("apply_fee_rates");
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_apply_fee_rates() {
+        let (mut helper, guardian_address) = HookTestTestHelper::new_with_guardian();
+        helper
+            .guardian_propose_fee_rates(guardian_address, dec!(0.01), dec!(0.005))
+            .execute_expect_success(false);
+        helper
+            .pool
+            .advance_timestamp_by_seconds(FEE_RATES_UPDATE_TIMELOCK);
+        helper.pool.getter("apply_fee_rates");
+        helper.execute_expect_success(false);
+
+        assert_eq!(fee_rates(&mut helper), (dec!(0.01), dec!(0.005)));
+        assert_eq!(pending_fee_rates(&mut helper), None);
+        assert_eq!(
+            metadata_value(&mut helper, "input_fee_rate"),
+            Some(MetadataValue::Decimal(dec!(0.01)))
+        );
+        assert_eq!(
+            metadata_value(&mut helper, "flash_loan_fee_rate"),
+            Some(MetadataValue::Decimal(dec!(0.005)))
+        );
+    }
+
+    #[test]
+    fn test_apply_fee_rates_without_proposal() {
+        let (mut helper, _) = HookTestTestHelper::new_with_guardian();
+        helper.pool.getter("apply_fee_rates");
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_after_swap_hook_proposes_input_fee_rate() {
+        let (mut helper, hook_address) = instantiate_with_swap_hook();
+        helper
+            .set_hook_input_fee_rates(hook_address, None, Some(dec!(0.01)))
+            .execute_expect_success(false);
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+
+        let (input_fee_rate, flash_loan_fee_rate, effective_at) =
+            pending_fee_rates(&mut helper).unwrap();
+        assert_eq!(
+            (input_fee_rate, flash_loan_fee_rate),
+            (dec!(0.01), dec!(0.009))
+        );
+        assert_eq!(fee_rates(&mut helper), (dec!(0), dec!(0.009)));
+
+        // Proposing the same input fee rate again does not restart the timelock.
+        helper.pool.advance_timestamp_by_seconds(60);
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+        assert_eq!(
+            pending_fee_rates(&mut helper),
+            Some((dec!(0.01), dec!(0.009), effective_at))
+        );
+
+        helper
+            .pool
+            .advance_timestamp_by_seconds(FEE_RATES_UPDATE_TIMELOCK);
+        helper.pool.getter("apply_fee_rates");
+        helper.execute_expect_success(false);
+        assert_eq!(fee_rates(&mut helper), (dec!(0.01), dec!(0.009)));
+    }
+
+    #[test]
+    fn test_after_swap_hook_unchanged_input_fee_rate() {
+        let (mut helper, hook_address) = instantiate_with_swap_hook();
+        helper
+            .set_hook_input_fee_rates(hook_address, Some(dec!(0.01)), None)
+            .execute_expect_success(false);
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+
+        assert_eq!(pending_fee_rates(&mut helper), None);
+        assert_eq!(fee_rates(&mut helper), (dec!(0), dec!(0.009)));
+    }
+
+    #[test]
+    fn test_cancel_fee_rates() {
+        let (mut helper, guardian_address) = HookTestTestHelper::new_with_guardian();
+        helper
+            .guardian_propose_fee_rates(guardian_address, dec!(0.01), dec!(0.005))
+            .execute_expect_success(false);
+        helper
+            .guardian_call(guardian_address, "cancel_fee_rates")
+            .execute_expect_success(false);
+        assert_eq!(pending_fee_rates(&mut helper), None);
+
+        helper
+            .pool
+            .advance_timestamp_by_seconds(FEE_RATES_UPDATE_TIMELOCK);
+        helper.pool.getter("apply_fee_rates");
+        helper.execute_expect_failure(false);
+    }
+}
@@ -0,0 +1,108 @@
+mod helper;
+
+#[cfg(test)]
+mod test_pool_guardian {
+    use super::*;
+    use helper::HookTestTestHelper;
+    use pretty_assertions::assert_eq;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+
+    fn instantiate_paused() -> HookTestTestHelper {
+        let (mut helper, guardian_address) = HookTestTestHelper::new_with_guardian();
+        helper
+            .guardian_call(guardian_address, "pause")
+            .execute_expect_success(false);
+        helper
+    }
+
+    fn paused(helper: &mut HookTestTestHelper) -> bool {
+        let receipt = helper
+            .pool
+            .getter("paused")
+            .registry
+            .execute_expect_success(false);
+        let outputs: Vec<bool> = receipt.outputs("paused");
+        outputs[0]
+    }
+
+    #[test]
+    fn test_guardian() {
+        let (mut helper, guardian_address) = HookTestTestHelper::new_with_guardian();
+        let receipt = helper
+            .pool
+            .getter("guardian")
+            .registry
+            .execute_expect_success(false);
+        let outputs: Vec<Option<ComponentAddress>> = receipt.outputs("guardian");
+
+        assert_eq!(outputs, vec![Some(guardian_address)]);
+        assert!(!paused(&mut helper));
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut helper = instantiate_paused();
+        assert!(paused(&mut helper));
+    }
+
+    #[test]
+    fn test_paused_swap() {
+        let mut helper = instantiate_paused();
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_paused_add_liquidity() {
+        let mut helper = instantiate_paused();
+        helper.pool.add_liquidity_default(dec!(10), dec!(10));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_paused_flash_loan() {
+        let mut helper = instantiate_paused();
+        helper.pool.flash_loan(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_paused_remove_liquidity() {
+        let mut helper = instantiate_paused();
+        helper.pool.remove_liquidity_default(dec!(1));
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_unpause() {
+        let (mut helper, guardian_address) = HookTestTestHelper::new_with_guardian();
+        helper
+            .guardian_call(guardian_address, "pause")
+            .execute_expect_success(false);
+        helper
+            .guardian_call(guardian_address, "unpause")
+            .execute_expect_success(false);
+
+        assert!(!paused(&mut helper));
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_pause_not_guardian() {
+        let (mut helper, _) = HookTestTestHelper::new_with_guardian();
+        helper.pool.getter("pause");
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_pause_without_guardian() {
+        let mut helper = HookTestTestHelper::new();
+        let guardian_address = helper.instantiate_test_guardian_output();
+        helper.pool.instantiate_default(false);
+        helper
+            .guardian_call(guardian_address, "pause")
+            .execute_expect_failure(false);
+    }
+}
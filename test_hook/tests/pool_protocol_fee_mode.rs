@@ -0,0 +1,128 @@
+mod helper;
+
+#[cfg(test)]
+mod test_pool_protocol_fee_mode {
+    use super::*;
+    use helper::HookTestTestHelper;
+    use pretty_assertions::assert_eq;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+    use test_hook::test_registry::TestRegistryConfig;
+
+    fn registry_config() -> TestRegistryConfig {
+        TestRegistryConfig {
+            fee_protocol_share: dec!(0.25),
+            next_sync_time: 0,
+            flash_loan_fee_protocol_share: dec!(1),
+            paused: None,
+            flash_loan_max_fraction: None,
+        }
+    }
+
+    fn instantiate_with_mode(protocol_fee_mode: &str) -> (HookTestTestHelper, ComponentAddress) {
+        let mut helper = HookTestTestHelper::new();
+        let registry_address = helper.instantiate_test_registry_output(registry_config());
+        helper
+            .pool
+            .set_metadata("protocol_fee_mode", protocol_fee_mode)
+            .registry
+            .execute_expect_success(false);
+        helper.instantiate_pool_with_registry(registry_address, dec!(0.01));
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10), dec!(10));
+        (helper, registry_address)
+    }
+
+    /// Swaps back and forth, the last swap syncs the fees of the previous swaps with the registry.
+    fn swap_back_and_forth(helper: &mut HookTestTestHelper) {
+        let (x_address, y_address) = (helper.pool.x_address(), helper.pool.y_address());
+        for (input_address, input_amount) in [
+            (x_address, dec!(1)),
+            (y_address, dec!(1)),
+            (x_address, dec!(0.1)),
+        ] {
+            helper.pool.swap(input_address, input_amount);
+            helper.execute_expect_success(false);
+        }
+    }
+
+    #[test]
+    fn test_protocol_fee_mode_invalid() {
+        let mut helper = HookTestTestHelper::new();
+        let registry_address = helper.instantiate_test_registry_output(registry_config());
+        helper
+            .pool
+            .set_metadata("protocol_fee_mode", "other")
+            .set_metadata("registry", registry_address)
+            .registry
+            .execute_expect_success(false);
+        let (x_address, y_address) = (helper.pool.x_address(), helper.pool.y_address());
+        helper
+            .pool
+            .instantiate(x_address, y_address, dec!(0.01), dec!(0.5))
+            .registry
+            .execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_protocol_fee_mode_skim() {
+        let (mut helper, registry_address) = instantiate_with_mode("skim");
+        swap_back_and_forth(&mut helper);
+
+        let (x_address, lp_address) = (helper.pool.x_address(), helper.pool.lp_address.unwrap());
+        assert!(helper.registry_fee_amount(registry_address, x_address) > dec!(0));
+        assert_eq!(
+            helper.registry_fee_amount(registry_address, lp_address),
+            dec!(0)
+        );
+    }
+
+    #[test]
+    fn test_protocol_fee_mode_lp_tokens() {
+        let (mut helper, registry_address) = instantiate_with_mode("lp_tokens");
+        swap_back_and_forth(&mut helper);
+
+        let (x_address, y_address, lp_address) = (
+            helper.pool.x_address(),
+            helper.pool.y_address(),
+            helper.pool.lp_address.unwrap(),
+        );
+        assert_eq!(
+            helper.registry_fee_amount(registry_address, x_address),
+            dec!(0)
+        );
+        assert_eq!(
+            helper.registry_fee_amount(registry_address, y_address),
+            dec!(0)
+        );
+
+        // The protocol owns 25% of the invariant growth of ~0.095% caused by the fees of the first two swaps.
+        let protocol_lp_amount = helper.registry_fee_amount(registry_address, lp_address);
+        let receipt = helper
+            .pool
+            .lp_total_supply()
+            .registry
+            .execute_expect_success(false);
+        let lp_total_supply: Decimal = receipt.outputs("lp_total_supply")[0];
+        let protocol_lp_fraction = protocol_lp_amount / lp_total_supply;
+        assert!(
+            protocol_lp_fraction > dec!(0.000237) && protocol_lp_fraction < dec!(0.000238),
+            "{:?}",
+            protocol_lp_fraction
+        );
+    }
+
+    #[test]
+    fn test_protocol_fee_mode_lp_tokens_no_growth() {
+        let (mut helper, registry_address) = instantiate_with_mode("lp_tokens");
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+
+        let lp_address = helper.pool.lp_address.unwrap();
+        assert_eq!(
+            helper.registry_fee_amount(registry_address, lp_address),
+            dec!(0)
+        );
+    }
+}
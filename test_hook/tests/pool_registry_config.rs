@@ -0,0 +1,219 @@
+mod helper;
+
+#[cfg(test)]
+mod test_pool_registry_config {
+    use super::*;
+    use helper::HookTestTestHelper;
+    use pretty_assertions::assert_eq;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+    use test_hook::test_registry::TestRegistryConfig;
+
+    fn registry_config() -> TestRegistryConfig {
+        TestRegistryConfig {
+            fee_protocol_share: dec!(0.1),
+            next_sync_time: 0,
+            flash_loan_fee_protocol_share: dec!(0.5),
+            paused: None,
+            flash_loan_max_fraction: None,
+        }
+    }
+
+    /// Instantiates a pool with the test registry and syncs the registry config via a swap.
+    fn instantiate_with_registry(
+        config: TestRegistryConfig,
+    ) -> (HookTestTestHelper, ComponentAddress) {
+        let mut helper = HookTestTestHelper::new();
+        let registry_address = helper.instantiate_test_registry_output(config);
+        helper.instantiate_pool_with_registry(registry_address, dec!(0));
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10), dec!(10));
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+        (helper, registry_address)
+    }
+
+    fn current_config(helper: &mut HookTestTestHelper) -> TestRegistryConfig {
+        let receipt = helper
+            .pool
+            .getter("current_config")
+            .registry
+            .execute_expect_success(false);
+        let outputs: Vec<TestRegistryConfig> = receipt.outputs("current_config");
+        outputs[0].clone()
+    }
+
+    fn flash_loan_and_repay(helper: &mut HookTestTestHelper, loan_amount: Decimal) {
+        let receipt = helper
+            .pool
+            .flash_loan_address()
+            .registry
+            .execute_expect_success(false);
+        let flash_loan_address: ResourceAddress = receipt.outputs("flash_loan_address")[0];
+        let x_address = helper.pool.x_address();
+        helper.pool.flash_loan(x_address, loan_amount).repay_loan(
+            x_address,
+            loan_amount,
+            loan_amount * dec!(0.009),
+            flash_loan_address,
+            dec!(1),
+        );
+    }
+
+    #[test]
+    fn test_current_config_legacy_registry() {
+        let mut helper = HookTestTestHelper::new();
+        helper.pool.instantiate_default(false);
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10), dec!(10));
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+
+        let config = current_config(&mut helper);
+        assert_eq!(config.flash_loan_fee_protocol_share, dec!(1));
+        assert_eq!(config.paused, None);
+        assert_eq!(config.flash_loan_max_fraction, None);
+    }
+
+    #[test]
+    fn test_current_config() {
+        let (mut helper, _) = instantiate_with_registry(registry_config());
+        assert_eq!(current_config(&mut helper), registry_config());
+    }
+
+    #[test]
+    fn test_current_config_clamped() {
+        let (mut helper, _) = instantiate_with_registry(TestRegistryConfig {
+            fee_protocol_share: dec!(0.5),
+            flash_loan_fee_protocol_share: dec!(2),
+            flash_loan_max_fraction: Some(dec!(-1)),
+            ..registry_config()
+        });
+        assert_eq!(
+            current_config(&mut helper),
+            TestRegistryConfig {
+                fee_protocol_share: dec!(0.25),
+                flash_loan_fee_protocol_share: dec!(1),
+                flash_loan_max_fraction: Some(dec!(0)),
+                ..registry_config()
+            }
+        );
+    }
+
+    /// Pauses the pool via the registry config and persists the pause with an explicit sync.
+    fn pause_via_registry(helper: &mut HookTestTestHelper, registry_address: ComponentAddress) {
+        helper
+            .set_registry_config(
+                registry_address,
+                TestRegistryConfig {
+                    paused: Some(true),
+                    ..registry_config()
+                },
+            )
+            .execute_expect_success(false);
+        helper.pool.getter("sync_registry");
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_registry_pause() {
+        let (mut helper, registry_address) = instantiate_with_registry(registry_config());
+        pause_via_registry(&mut helper, registry_address);
+
+        let receipt = helper
+            .pool
+            .getter("paused")
+            .registry
+            .execute_expect_success(false);
+        let paused: Vec<bool> = receipt.outputs("paused");
+        assert_eq!(paused, vec![true]);
+
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_failure(false);
+
+        helper.pool.remove_liquidity_default(dec!(1));
+        helper.execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_registry_pause_flash_loan_without_swap() {
+        let (mut helper, registry_address) = instantiate_with_registry(registry_config());
+        helper
+            .set_registry_config(
+                registry_address,
+                TestRegistryConfig {
+                    paused: Some(true),
+                    ..registry_config()
+                },
+            )
+            .execute_expect_success(false);
+
+        // The flash loan syncs with the registry before checking the pause flag.
+        flash_loan_and_repay(&mut helper, dec!(1));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_registry_pause_add_liquidity_without_swap() {
+        let (mut helper, registry_address) = instantiate_with_registry(registry_config());
+        helper
+            .set_registry_config(
+                registry_address,
+                TestRegistryConfig {
+                    paused: Some(true),
+                    ..registry_config()
+                },
+            )
+            .execute_expect_success(false);
+
+        // Adding liquidity syncs with the registry before checking the pause flag.
+        helper.pool.add_liquidity_default(dec!(10), dec!(10));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_registry_unpause() {
+        let (mut helper, registry_address) = instantiate_with_registry(registry_config());
+        pause_via_registry(&mut helper, registry_address);
+        helper
+            .set_registry_config(registry_address, registry_config())
+            .execute_expect_success(false);
+
+        // The swap syncs with the registry before checking the pause flag.
+        helper.pool.swap(helper.pool.x_address(), dec!(1));
+        helper.execute_expect_success(false);
+        assert_eq!(current_config(&mut helper), registry_config());
+    }
+
+    #[test]
+    fn test_flash_loan_max_fraction() {
+        let (mut helper, _) = instantiate_with_registry(TestRegistryConfig {
+            flash_loan_max_fraction: Some(dec!(0.5)),
+            ..registry_config()
+        });
+        // x reserves are 11 after the initial swap
+        flash_loan_and_repay(&mut helper, dec!(5.5));
+        helper.execute_expect_success(false);
+        flash_loan_and_repay(&mut helper, dec!(5.6));
+        helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_flash_loan_fee_split() {
+        let (mut helper, registry_address) = instantiate_with_registry(registry_config());
+        flash_loan_and_repay(&mut helper, dec!(1));
+        helper.execute_expect_success(false);
+
+        // the next swap syncs the protocol fees to the registry
+        helper.pool.swap(helper.pool.y_address(), dec!(1));
+        helper.execute_expect_success(false);
+
+        let x_address = helper.pool.x_address();
+        assert_eq!(
+            helper.registry_fee_amount(registry_address, x_address),
+            dec!(0.0045)
+        );
+    }
+}
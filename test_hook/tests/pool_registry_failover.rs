@@ -0,0 +1,322 @@
+mod helper;
+
+#[cfg(test)]
+mod test_pool_registry_failover {
+    use super::*;
+    use helper::HookTestTestHelper;
+    use ociswap_pool::constants::{
+        REGISTRY_FAILOVER_STALENESS_MIN, REGISTRY_SYNC_DEFERRAL_MAX, REGISTRY_SYNC_GRACE_PERIOD_MAX,
+    };
+    use pretty_assertions::assert_eq;
+    use scrypto::prelude::*;
+    use scrypto_testenv::*;
+    use test_hook::test_registry::TestRegistryConfig;
+
+    struct Setup {
+        helper: HookTestTestHelper,
+        guardian_address: ComponentAddress,
+        registry_address: ComponentAddress,
+        registry_fallback_address: ComponentAddress,
+    }
+
+    fn registry_config() -> TestRegistryConfig {
+        TestRegistryConfig {
+            fee_protocol_share: dec!(0.1),
+            next_sync_time: 0,
+            flash_loan_fee_protocol_share: dec!(1),
+            paused: None,
+            flash_loan_max_fraction: None,
+        }
+    }
+
+    fn instantiate_setup() -> Setup {
+        instantiate_setup_with_grace_period(0)
+    }
+
+    /// Instantiates a pool with input fee, a guardian, a test registry, a fallback test registry
+    /// and the given registry sync grace period.
+    fn instantiate_setup_with_grace_period(registry_sync_grace_period: u64) -> Setup {
+        let mut helper = HookTestTestHelper::new();
+        let guardian_address = helper.instantiate_test_guardian_output();
+        let registry_fallback_address = helper.instantiate_test_registry_output(registry_config());
+        let registry_address = helper.instantiate_test_registry_output(registry_config());
+        helper
+            .pool
+            .set_metadata("guardian", guardian_address)
+            .set_metadata("registry_fallback", registry_fallback_address)
+            .set_metadata("registry_sync_grace_period", registry_sync_grace_period)
+            .registry
+            .execute_expect_success(false);
+        helper.instantiate_pool_with_registry(registry_address, dec!(0));
+        helper
+            .pool
+            .add_liquidity_default_execute(dec!(10), dec!(10));
+        Setup {
+            helper,
+            guardian_address,
+            registry_address,
+            registry_fallback_address,
+        }
+    }
+
+    /// Collects protocol fees from a flash loan, which are synced on the next swap, and breaks the registry.
+    fn break_registry_with_fees(setup: &mut Setup) {
+        let receipt = setup
+            .helper
+            .pool
+            .flash_loan_address()
+            .registry
+            .execute_expect_success(false);
+        let flash_loan_address: ResourceAddress = receipt.outputs("flash_loan_address")[0];
+        let x_address = setup.helper.pool.x_address();
+        setup.helper.pool.flash_loan(x_address, dec!(1)).repay_loan(
+            x_address,
+            dec!(1),
+            dec!(0.009),
+            flash_loan_address,
+            dec!(1),
+        );
+        setup.helper.execute_expect_success(false);
+
+        let registry_address = setup.registry_address;
+        setup
+            .helper
+            .set_registry_broken(registry_address, true)
+            .execute_expect_success(false);
+    }
+
+    fn swap(setup: &mut Setup) -> &mut HookTestTestHelper {
+        let y_address = setup.helper.pool.y_address();
+        setup.helper.pool.swap(y_address, dec!(1));
+        &mut setup.helper
+    }
+
+    #[test]
+    fn test_swap_broken_registry() {
+        let mut setup = instantiate_setup();
+        break_registry_with_fees(&mut setup);
+        swap(&mut setup).execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_deferred_registry_sync() {
+        let mut setup = instantiate_setup();
+        break_registry_with_fees(&mut setup);
+        let guardian_address = setup.guardian_address;
+        setup
+            .helper
+            .guardian_defer_registry_sync(guardian_address, REGISTRY_SYNC_DEFERRAL_MAX)
+            .execute_expect_success(false);
+        swap(&mut setup).execute_expect_success(false);
+    }
+
+    #[test]
+    fn test_defer_registry_sync_above_limit() {
+        let mut setup = instantiate_setup();
+        let guardian_address = setup.guardian_address;
+        setup
+            .helper
+            .guardian_defer_registry_sync(guardian_address, REGISTRY_SYNC_DEFERRAL_MAX + 1)
+            .execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_deferral_expires() {
+        let mut setup = instantiate_setup();
+        break_registry_with_fees(&mut setup);
+        let guardian_address = setup.guardian_address;
+        setup
+            .helper
+            .guardian_defer_registry_sync(guardian_address, 100)
+            .execute_expect_success(false);
+        setup.helper.pool.advance_timestamp_by_seconds(100);
+        swap(&mut setup).execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_retry_registry_sync() {
+        let mut setup = instantiate_setup();
+        break_registry_with_fees(&mut setup);
+        let (guardian_address, registry_address) = (setup.guardian_address, setup.registry_address);
+        setup
+            .helper
+            .guardian_defer_registry_sync(guardian_address, REGISTRY_SYNC_DEFERRAL_MAX)
+            .execute_expect_success(false);
+
+        // retrying fails as long as the registry is broken and keeps the fees in the pool
+        setup.helper.pool.getter("retry_registry_sync");
+        setup.helper.execute_expect_failure(false);
+
+        setup
+            .helper
+            .set_registry_broken(registry_address, false)
+            .execute_expect_success(false);
+        setup.helper.pool.getter("retry_registry_sync");
+        setup.helper.execute_expect_success(false);
+
+        let x_address = setup.helper.pool.x_address();
+        assert_eq!(
+            setup
+                .helper
+                .registry_fee_amount(registry_address, x_address),
+            dec!(0.009)
+        );
+    }
+
+    #[test]
+    fn test_fail_over_registry() {
+        let mut setup = instantiate_setup();
+        break_registry_with_fees(&mut setup);
+        let guardian_address = setup.guardian_address;
+        setup
+            .helper
+            .guardian_call(guardian_address, "fail_over_registry")
+            .execute_expect_success(false);
+        swap(&mut setup).execute_expect_success(false);
+
+        let receipt = setup
+            .helper
+            .pool
+            .getter("registry")
+            .getter("registry_fallback")
+            .registry
+            .execute_expect_success(false);
+        let registry: Vec<ComponentAddress> = receipt.outputs("registry");
+        let registry_fallback: Vec<Option<ComponentAddress>> = receipt.outputs("registry_fallback");
+        assert_eq!(registry, vec![setup.registry_fallback_address]);
+        assert_eq!(registry_fallback, vec![None]);
+
+        let (registry_fallback_address, x_address) = (
+            setup.registry_fallback_address,
+            setup.helper.pool.x_address(),
+        );
+        assert_eq!(
+            setup
+                .helper
+                .registry_fee_amount(registry_fallback_address, x_address),
+            dec!(0.009)
+        );
+    }
+
+    #[test]
+    fn test_fail_over_registry_twice() {
+        let mut setup = instantiate_setup();
+        let guardian_address = setup.guardian_address;
+        setup
+            .helper
+            .guardian_call(guardian_address, "fail_over_registry")
+            .execute_expect_success(false);
+        setup
+            .helper
+            .guardian_call(guardian_address, "fail_over_registry")
+            .execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_fail_over_registry_not_guardian() {
+        let mut setup = instantiate_setup();
+        setup.helper.pool.getter("fail_over_registry");
+        setup.helper.execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_swap_broken_registry_within_grace_period() {
+        let mut setup = instantiate_setup_with_grace_period(REGISTRY_SYNC_GRACE_PERIOD_MAX);
+        break_registry_with_fees(&mut setup);
+        swap(&mut setup).execute_expect_success(false);
+
+        let receipt = setup
+            .helper
+            .pool
+            .getter("registry_sync_grace_period")
+            .registry
+            .execute_expect_success(false);
+        let grace_period: Vec<u64> = receipt.outputs("registry_sync_grace_period");
+        assert_eq!(grace_period, vec![REGISTRY_SYNC_GRACE_PERIOD_MAX]);
+    }
+
+    #[test]
+    fn test_grace_period_expires() {
+        let mut setup = instantiate_setup_with_grace_period(REGISTRY_SYNC_GRACE_PERIOD_MAX);
+        break_registry_with_fees(&mut setup);
+        setup
+            .helper
+            .pool
+            .advance_timestamp_by_seconds(REGISTRY_SYNC_GRACE_PERIOD_MAX);
+        swap(&mut setup).execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_grace_period_above_limit() {
+        let mut helper = HookTestTestHelper::new();
+        let registry_address = helper.instantiate_test_registry_output(registry_config());
+        helper
+            .pool
+            .set_metadata("registry", registry_address)
+            .set_metadata(
+                "registry_sync_grace_period",
+                REGISTRY_SYNC_GRACE_PERIOD_MAX + 1,
+            )
+            .registry
+            .execute_expect_success(false);
+        let (x_address, y_address) = (helper.pool.x_address(), helper.pool.y_address());
+        helper
+            .pool
+            .instantiate(x_address, y_address, dec!(0), dec!(0.5))
+            .registry
+            .execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_registry_fallback_without_grace_period() {
+        let mut helper = HookTestTestHelper::new();
+        let registry_fallback_address = helper.instantiate_test_registry_output(registry_config());
+        let registry_address = helper.instantiate_test_registry_output(registry_config());
+        helper
+            .pool
+            .set_metadata("registry", registry_address)
+            .set_metadata("registry_fallback", registry_fallback_address)
+            .registry
+            .execute_expect_success(false);
+        let (x_address, y_address) = (helper.pool.x_address(), helper.pool.y_address());
+        helper
+            .pool
+            .instantiate(x_address, y_address, dec!(0), dec!(0.5))
+            .registry
+            .execute_expect_failure(false);
+    }
+
+    #[test]
+    fn test_fail_over_stale_registry() {
+        let mut setup = instantiate_setup();
+        break_registry_with_fees(&mut setup);
+        setup
+            .helper
+            .pool
+            .advance_timestamp_by_seconds(REGISTRY_FAILOVER_STALENESS_MIN);
+
+        // callable by anyone once the registry is stale
+        setup.helper.pool.getter("fail_over_stale_registry");
+        setup.helper.execute_expect_success(false);
+        swap(&mut setup).execute_expect_success(false);
+
+        let (registry_fallback_address, x_address) = (
+            setup.registry_fallback_address,
+            setup.helper.pool.x_address(),
+        );
+        assert_eq!(
+            setup
+                .helper
+                .registry_fee_amount(registry_fallback_address, x_address),
+            dec!(0.009)
+        );
+    }
+
+    #[test]
+    fn test_fail_over_stale_registry_not_stale() {
+        let mut setup = instantiate_setup();
+        break_registry_with_fees(&mut setup);
+        setup.helper.pool.getter("fail_over_stale_registry");
+        setup.helper.execute_expect_failure(false);
+    }
+}
@@ -0,0 +1,265 @@
+//! Records the costs of pool operations and compares them with `tests/benchmarks/baseline.csv`.
+//!
+//! The test fails if any cost exceeds its baseline by more than `COST_REGRESSION_THRESHOLD` (default 5%), if a
+//! benchmark is missing in the baseline or if no baseline exists. Run with `UPDATE_COST_BASELINE=1` to write the
+//! current costs as new baseline.
+//!
+//! `test_cost_benchmarks` is ignored by default and runs via `cargo test --test benchmarks -- --ignored`.
+
+use std::fs;
+use std::mem;
+
+use ociswap_pool_test_helper::*;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+
+const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/benchmarks/baseline.csv");
+const DEFAULT_REGRESSION_THRESHOLD: Decimal = dec!(0.05);
+const MAX_HOOKS: usize = 3;
+const ORACLE_INTERVALS: [u64; 2] = [1, 10];
+
+fn regression_threshold() -> Decimal {
+    match std::env::var("COST_REGRESSION_THRESHOLD") {
+        Ok(threshold) => Decimal::try_from(threshold.as_str())
+            .expect("COST_REGRESSION_THRESHOLD needs to be a decimal number"),
+        Err(_) => DEFAULT_REGRESSION_THRESHOLD,
+    }
+}
+
+/// Instantiates a pool with the given share of token X and adds liquidity.
+fn pool(x_share: Decimal) -> PoolTestHelper {
+    let mut helper = PoolTestHelper::new_without_instantiate_registry();
+    helper.instantiate_default_with_all_fees(dec!(0.003), dec!(0.1), x_share, false);
+    helper
+        .add_liquidity_default(dec!(100000), dec!(5000))
+        .registry
+        .execute_expect_success(false);
+    helper
+}
+
+/// Instantiates a balanced pool with `hook_count` instances of `TestSwapHook` and adds liquidity.
+fn pool_with_hooks(hook_count: usize) -> PoolTestHelper {
+    let packages: HashMap<&str, &str> = vec![
+        ("registry", "registry"),
+        ("pool", "."),
+        ("test_hook", "test_hook"),
+    ]
+    .into_iter()
+    .collect();
+    let mut helper = PoolTestHelper::new_with_packages(packages, true);
+    helper.set_whitelist_registry();
+    helper.set_whitelist_hook("test_hook");
+
+    let package_address = helper.registry.env.package_address("test_hook");
+    let mut hooks = Vec::new();
+    for _ in 0..hook_count {
+        let manifest_builder = mem::take(&mut helper.registry.env.manifest_builder);
+        helper.registry.env.manifest_builder = manifest_builder.call_function(
+            package_address,
+            "TestSwapHook",
+            "instantiate",
+            manifest_args!(helper.x_address(), helper.y_address()),
+        );
+        helper
+            .registry
+            .env
+            .new_instruction("instantiate_test_hook", 1, 0);
+        let receipt = helper.registry.execute_expect_success(false);
+        let hook_badge_address = receipt
+            .execution_receipt
+            .expect_commit_success()
+            .new_resource_addresses()[0];
+        let outputs: Vec<(ComponentAddress, Bucket)> = receipt.outputs("instantiate_test_hook");
+        hooks.push((outputs[0].0, hook_badge_address));
+    }
+
+    helper.instantiate_default_with_hooks(hooks, false);
+    helper
+        .add_liquidity_default(dec!(100000), dec!(5000))
+        .registry
+        .execute_expect_success(false);
+    helper
+}
+
+/// Swaps once before measuring, so the measured swap does not include one-off costs like the first oracle
+/// observation.
+fn record_swap(benchmark: &mut CostBenchmark, name: &str, mut helper: PoolTestHelper) {
+    helper
+        .swap_x_default(dec!(10))
+        .registry
+        .execute_expect_success(false);
+    helper.advance_timestamp_by_seconds(60);
+    let receipt = helper
+        .swap_y_default(dec!(1))
+        .registry
+        .execute_expect_success(false);
+    benchmark.record(name, &receipt);
+}
+
+fn record_liquidity(benchmark: &mut CostBenchmark) {
+    let mut helper = pool(dec!(0.5));
+    let receipt = helper
+        .add_liquidity_default(dec!(1000), dec!(50))
+        .registry
+        .execute_expect_success(false);
+    benchmark.record("add_liquidity", &receipt);
+
+    let receipt = helper
+        .remove_liquidity_default(dec!(100))
+        .registry
+        .execute_expect_success(false);
+    benchmark.record("remove_liquidity", &receipt);
+}
+
+fn record_flash_loan(benchmark: &mut CostBenchmark) {
+    let mut helper = pool(dec!(0.5));
+    let receipt = helper
+        .flash_loan_address()
+        .registry
+        .execute_expect_success(false);
+    let flash_loan_address: ResourceAddress = receipt.outputs("flash_loan_address")[0];
+
+    helper.flash_loan(helper.x_address(), dec!(100));
+    let receipt = helper
+        .repay_loan(
+            helper.x_address(),
+            dec!(100),
+            dec!(0.9),
+            flash_loan_address,
+            dec!(1),
+        )
+        .registry
+        .execute_expect_success(false);
+    benchmark.record("flash_loan_round_trip", &receipt);
+}
+
+fn record_sync_registry(benchmark: &mut CostBenchmark) {
+    let mut helper = pool(dec!(0.5));
+    // Collect protocol fees to be sent to the registry.
+    helper
+        .swap_x_default(dec!(10))
+        .registry
+        .execute_expect_success(false);
+    helper.advance_timestamp_by_seconds(60);
+    let receipt = helper
+        .getter("sync_registry")
+        .registry
+        .execute_expect_success(false);
+    benchmark.record("sync_registry", &receipt);
+}
+
+fn record_oracle(benchmark: &mut CostBenchmark) {
+    let mut helper = pool(dec!(0.5));
+    for _ in 0..10 {
+        helper.advance_timestamp_by_seconds(60);
+        helper
+            .swap_x_default(dec!(10))
+            .registry
+            .execute_expect_success(false);
+    }
+    helper.advance_timestamp_by_seconds(60);
+
+    let receipt = helper
+        .oldest_observation_at()
+        .registry
+        .execute_expect_success(false);
+    let oldest_observation_at: Option<u64> = receipt.outputs("oldest_observation_at")[0];
+    let start = oldest_observation_at.unwrap();
+    let now = helper
+        .registry
+        .env
+        .test_runner
+        .get_current_time(TimePrecision::Second)
+        .seconds_since_unix_epoch as u64;
+
+    for interval_count in ORACLE_INTERVALS {
+        let length = (now - start) / interval_count;
+        let intervals: Vec<(u64, u64)> = (0..interval_count)
+            .map(|index| (start + index * length, start + (index + 1) * length))
+            .collect();
+        let receipt = helper
+            .observation_intervals(intervals)
+            .registry
+            .execute_expect_success(false);
+        benchmark.record(
+            &format!("oracle_observation_intervals_{}", interval_count),
+            &receipt,
+        );
+    }
+}
+
+fn run_benchmarks() -> CostBenchmark {
+    let mut benchmark = CostBenchmark::new();
+    record_swap(&mut benchmark, "swap_balanced", pool(dec!(0.5)));
+    record_swap(&mut benchmark, "swap_imbalanced", pool(dec!(0.8)));
+    for hook_count in 0..=MAX_HOOKS {
+        record_swap(
+            &mut benchmark,
+            &format!("swap_hooks_{}", hook_count),
+            pool_with_hooks(hook_count),
+        );
+    }
+    record_liquidity(&mut benchmark);
+    record_flash_loan(&mut benchmark);
+    record_sync_registry(&mut benchmark);
+    record_oracle(&mut benchmark);
+    benchmark
+}
+
+#[test]
+#[ignore = "requires the committed cost baseline, run with --ignored"]
+fn test_cost_benchmarks() {
+    let benchmark = run_benchmarks();
+    println!("{}", benchmark.to_csv());
+
+    let update = std::env::var("UPDATE_COST_BASELINE").is_ok_and(|update| update != "0");
+    if update {
+        fs::create_dir_all(std::path::Path::new(BASELINE_PATH).parent().unwrap()).unwrap();
+        fs::write(BASELINE_PATH, benchmark.to_csv()).unwrap();
+        println!("Cost baseline written to {}", BASELINE_PATH);
+        return;
+    }
+    let baseline = fs::read_to_string(BASELINE_PATH).unwrap_or_else(|_| {
+        panic!(
+            "Cost baseline {} is missing, run with UPDATE_COST_BASELINE=1 and commit it",
+            BASELINE_PATH
+        )
+    });
+    let baseline = CostBenchmark::from_csv(&baseline).unwrap();
+
+    let missing = benchmark.missing_in(&baseline);
+    assert!(
+        missing.is_empty(),
+        "Benchmarks missing in the baseline, run with UPDATE_COST_BASELINE=1: {:?}",
+        missing
+    );
+    let regressions = benchmark.regressions(&baseline, regression_threshold());
+    assert!(
+        regressions.is_empty(),
+        "Cost regressions:\n{}",
+        regressions
+            .iter()
+            .map(|regression| regression.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
+
+#[test]
+fn test_cost_benchmark_csv_round_trip() {
+    let mut benchmark = CostBenchmark::new();
+    let mut helper = pool(dec!(0.5));
+    let receipt = helper
+        .swap_x_default(dec!(10))
+        .registry
+        .execute_expect_success(false);
+    benchmark.record("swap", &receipt);
+
+    assert_eq!(
+        CostBenchmark::from_csv(&benchmark.to_csv()).unwrap(),
+        benchmark
+    );
+    assert!(benchmark.regressions(&benchmark, dec!(0)).is_empty());
+    assert!(benchmark.missing_in(&benchmark).is_empty());
+    assert_eq!(benchmark.missing_in(&CostBenchmark::new()), vec!["swap"]);
+}
@@ -0,0 +1,70 @@
+use ociswap_pool_client::{decode_swap_event, PoolManifestBuilder, PoolMethod};
+use ociswap_pool_test_helper::*;
+use pretty_assertions::assert_eq;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+use std::mem;
+
+fn instantiate() -> PoolTestHelper {
+    let mut helper = PoolTestHelper::new();
+    helper.instantiate_default_with_input_fee(dec!(0.003), false);
+    helper.add_liquidity_default_execute(dec!(100), dec!(200));
+    helper
+}
+
+#[test]
+fn test_client_swap() {
+    let mut helper = instantiate();
+    let (pool_address, x_address, y_address) = (
+        helper.pool_address.unwrap(),
+        helper.x_address(),
+        helper.y_address(),
+    );
+    let receipt = helper
+        .quote(x_address, dec!(10))
+        .registry
+        .execute_expect_success(false);
+    let quote: Decimal = receipt.outputs("quote")[0];
+
+    let account = helper.registry.env.account;
+    helper.registry.env.manifest_builder = PoolManifestBuilder::from_builder(
+        mem::take(&mut helper.registry.env.manifest_builder),
+        account,
+    )
+    .swap(pool_address, x_address, dec!(10))
+    .into_builder();
+    helper.registry.env.new_instruction("swap", 3, 2);
+    let receipt = helper.registry.execute_expect_success(false);
+    let output: Vec<Bucket> = receipt.outputs("swap");
+    assert_eq!(output.len(), 1);
+
+    let swap_events: Vec<_> = receipt
+        .execution_receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .filter_map(|(_, payload)| decode_swap_event(payload).ok())
+        .collect();
+    assert_eq!(swap_events.len(), 1);
+    assert_eq!(swap_events[0].input_address, x_address);
+    assert_eq!(swap_events[0].input_gross_amount, dec!(10));
+    assert_eq!(swap_events[0].output_address, y_address);
+    assert_eq!(swap_events[0].output_return_amount, quote);
+}
+
+#[test]
+fn test_client_getters() {
+    let mut helper = instantiate();
+    let pool_address = helper.pool_address.unwrap();
+    let account = helper.registry.env.account;
+    helper.registry.env.manifest_builder = PoolManifestBuilder::from_builder(
+        mem::take(&mut helper.registry.env.manifest_builder),
+        account,
+    )
+    .call(pool_address, PoolMethod::InputFeeRate)
+    .into_builder();
+    helper.registry.env.new_instruction("input_fee_rate", 1, 0);
+    let receipt = helper.registry.execute_expect_success(false);
+    let input_fee_rate: Vec<Decimal> = receipt.outputs("input_fee_rate");
+    assert_eq!(input_fee_rate, vec![dec!(0.003)]);
+}
@@ -0,0 +1,351 @@
+use ociswap_pool::constants::FACTORY_PAGE_SIZE_MAX;
+use ociswap_pool::factory::FactoryPool;
+use ociswap_pool_test_helper::*;
+use pretty_assertions::assert_eq;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+use std::mem;
+
+fn instantiate_factory() -> PoolTestHelper {
+    let mut helper = PoolTestHelper::new();
+    helper.set_whitelist_registry();
+    helper.instantiate_factory_direct();
+    helper
+}
+
+fn pools_for_pair(
+    helper: &mut PoolTestHelper,
+    a_address: ResourceAddress,
+    b_address: ResourceAddress,
+) -> Vec<FactoryPool> {
+    let receipt = helper
+        .factory_pools_for_pair(a_address, b_address, 0, FACTORY_PAGE_SIZE_MAX)
+        .registry
+        .execute_expect_success(false);
+    receipt.outputs("pools_for_pair")[0].clone()
+}
+
+fn canonical_pool(helper: &mut PoolTestHelper) -> Option<ComponentAddress> {
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let receipt = helper
+        .factory_canonical_pool(x_address, y_address)
+        .registry
+        .execute_expect_success(false);
+    receipt.outputs("canonical_pool")[0]
+}
+
+#[test]
+fn test_factory_instantiate_pool() {
+    let mut helper = instantiate_factory();
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.003),
+        dec!(0.8),
+    );
+
+    let pools = pools_for_pair(&mut helper, helper.x_address(), helper.y_address());
+    assert_eq!(
+        pools,
+        vec![FactoryPool {
+            index: 0,
+            pool_address: helper.pool_address.unwrap(),
+            lp_address: helper.lp_address.unwrap(),
+            x_address: helper.x_address(),
+            y_address: helper.y_address(),
+            input_fee_rate: dec!(0.003),
+            x_share: dec!(0.8),
+        }]
+    );
+    assert_eq!(
+        pools_for_pair(&mut helper, helper.y_address(), helper.x_address()),
+        pools
+    );
+}
+
+#[test]
+fn test_factory_instantiate_pool_reversed_pair() {
+    let mut helper = instantiate_factory();
+    helper.factory_instantiate_pool_direct(
+        helper.y_address(),
+        helper.x_address(),
+        dec!(0.003),
+        dec!(0.8),
+    );
+
+    let pools = pools_for_pair(&mut helper, helper.x_address(), helper.y_address());
+    assert_eq!(pools.len(), 1);
+    assert_eq!(pools[0].x_address, helper.x_address());
+    assert_eq!(pools[0].y_address, helper.y_address());
+    assert_eq!(pools[0].x_share, dec!(0.2));
+}
+
+#[test]
+fn test_factory_instantiate_pool_with_liquidity() {
+    let mut helper = instantiate_factory();
+    helper
+        .factory_instantiate_pool_with_liquidity(
+            helper.x_address(),
+            dec!(10),
+            helper.y_address(),
+            dec!(10),
+            dec!(0.003),
+        )
+        .registry
+        .execute_expect_success(false);
+
+    let pools = pools_for_pair(&mut helper, helper.x_address(), helper.y_address());
+    assert_eq!(pools.len(), 1);
+    assert_eq!(pools[0].input_fee_rate, dec!(0.003));
+    assert_eq!(pools[0].x_share, dec!(0.5));
+}
+
+#[test]
+fn test_factory_instantiate_pool_duplicate() {
+    let mut helper = instantiate_factory();
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.003),
+        dec!(0.8),
+    );
+    helper
+        .factory_instantiate_pool(
+            helper.x_address(),
+            helper.y_address(),
+            dec!(0.003),
+            dec!(0.8),
+        )
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_factory_instantiate_pool_duplicate_reversed_pair() {
+    let mut helper = instantiate_factory();
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.003),
+        dec!(0.8),
+    );
+    helper
+        .factory_instantiate_pool(
+            helper.y_address(),
+            helper.x_address(),
+            dec!(0.003),
+            dec!(0.2),
+        )
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_factory_instantiate_pool_different_fee_tier_and_weight() {
+    let mut helper = instantiate_factory();
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.003),
+        dec!(0.5),
+    );
+    let first_pool_address = helper.pool_address.unwrap();
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.01),
+        dec!(0.5),
+    );
+    let second_pool_address = helper.pool_address.unwrap();
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.003),
+        dec!(0.8),
+    );
+    let third_pool_address = helper.pool_address.unwrap();
+
+    let pool_addresses: Vec<ComponentAddress> =
+        pools_for_pair(&mut helper, helper.x_address(), helper.y_address())
+            .into_iter()
+            .map(|pool| pool.pool_address)
+            .collect();
+    assert_eq!(
+        pool_addresses,
+        vec![first_pool_address, second_pool_address, third_pool_address]
+    );
+
+    let receipt = helper
+        .factory_pool_for_key(
+            helper.y_address(),
+            helper.x_address(),
+            dec!(0.01),
+            dec!(0.5),
+        )
+        .registry
+        .execute_expect_success(false);
+    let outputs: Vec<Option<ComponentAddress>> = receipt.outputs("pool_for_key");
+    assert_eq!(outputs, vec![Some(second_pool_address)]);
+}
+
+#[test]
+fn test_factory_pool_for_key_missing() {
+    let mut helper = instantiate_factory();
+    let receipt = helper
+        .factory_pool_for_key(
+            helper.x_address(),
+            helper.y_address(),
+            dec!(0.003),
+            dec!(0.5),
+        )
+        .registry
+        .execute_expect_success(false);
+    let outputs: Vec<Option<ComponentAddress>> = receipt.outputs("pool_for_key");
+    assert_eq!(outputs, vec![None]);
+    assert_eq!(
+        pools_for_pair(&mut helper, helper.x_address(), helper.y_address()),
+        vec![]
+    );
+}
+
+#[test]
+fn test_factory_pools_pagination() {
+    let mut helper = instantiate_factory();
+    let input_fee_rates = [dec!(0.0001), dec!(0.0005), dec!(0.003), dec!(0.01)];
+    for input_fee_rate in input_fee_rates {
+        helper.factory_instantiate_pool_direct(
+            helper.x_address(),
+            helper.y_address(),
+            input_fee_rate,
+            dec!(0.5),
+        );
+    }
+
+    let receipt = helper
+        .factory_pool_count()
+        .factory_pools(0, 3)
+        .factory_pools(3, 3)
+        .factory_pools(10, 3)
+        .registry
+        .execute_expect_success(false);
+    let pool_count: Vec<u64> = receipt.outputs("pool_count");
+    assert_eq!(pool_count, vec![4]);
+
+    let pages: Vec<Vec<FactoryPool>> = receipt.outputs("pools");
+    let page_input_fee_rates: Vec<Vec<Decimal>> = pages
+        .iter()
+        .map(|page| page.iter().map(|pool| pool.input_fee_rate).collect())
+        .collect();
+    assert_eq!(
+        page_input_fee_rates,
+        vec![
+            vec![dec!(0.0001), dec!(0.0005), dec!(0.003)],
+            vec![dec!(0.01)],
+            vec![],
+        ]
+    );
+    let indices: Vec<u64> = pages.concat().iter().map(|pool| pool.index).collect();
+    assert_eq!(indices, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_factory_pools_for_pair_pagination() {
+    let mut helper = instantiate_factory();
+    let input_fee_rates = [dec!(0.0001), dec!(0.0005), dec!(0.003)];
+    for input_fee_rate in input_fee_rates {
+        helper.factory_instantiate_pool_direct(
+            helper.x_address(),
+            helper.y_address(),
+            input_fee_rate,
+            dec!(0.5),
+        );
+    }
+    // A pool of another pair does not count towards the pair.
+    let z_address = helper.v_address();
+    helper.factory_instantiate_pool_direct(helper.x_address(), z_address, dec!(0.003), dec!(0.5));
+
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let receipt = helper
+        .factory_pair_pool_count(y_address, x_address)
+        .factory_pools_for_pair(x_address, y_address, 0, 2)
+        .factory_pools_for_pair(y_address, x_address, 2, 2)
+        .factory_pools_for_pair(x_address, y_address, 3, 2)
+        .factory_pools_for_pair(x_address, y_address, 0, FACTORY_PAGE_SIZE_MAX + 1)
+        .registry
+        .execute_expect_success(false);
+    let pair_pool_count: Vec<u64> = receipt.outputs("pair_pool_count");
+    assert_eq!(pair_pool_count, vec![3]);
+
+    let pages: Vec<Vec<FactoryPool>> = receipt.outputs("pools_for_pair");
+    let page_input_fee_rates: Vec<Vec<Decimal>> = pages
+        .iter()
+        .map(|page| page.iter().map(|pool| pool.input_fee_rate).collect())
+        .collect();
+    assert_eq!(
+        page_input_fee_rates,
+        vec![
+            vec![dec!(0.0001), dec!(0.0005)],
+            vec![dec!(0.003)],
+            vec![],
+            vec![dec!(0.0001), dec!(0.0005), dec!(0.003)],
+        ]
+    );
+}
+
+#[test]
+fn test_factory_set_canonical_pool() {
+    let mut helper = instantiate_factory();
+    assert_eq!(canonical_pool(&mut helper), None);
+
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.003),
+        dec!(0.5),
+    );
+    let pool_address = helper.pool_address.unwrap();
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.01),
+        dec!(0.5),
+    );
+    helper
+        .factory_set_canonical_pool(pool_address)
+        .registry
+        .execute_expect_success(false);
+
+    assert_eq!(canonical_pool(&mut helper), Some(pool_address));
+}
+
+#[test]
+fn test_factory_set_canonical_pool_without_owner_badge() {
+    let mut helper = instantiate_factory();
+    helper.factory_instantiate_pool_direct(
+        helper.x_address(),
+        helper.y_address(),
+        dec!(0.003),
+        dec!(0.5),
+    );
+    let factory_address = helper.factory_address.unwrap();
+    let pool_address = helper.pool_address.unwrap();
+    helper.registry.env.manifest_builder = mem::take(&mut helper.registry.env.manifest_builder)
+        .call_method(
+            factory_address,
+            "set_canonical_pool",
+            manifest_args!(pool_address),
+        );
+    helper.registry.execute_expect_failure(false);
+}
+
+#[test]
+fn test_factory_set_canonical_pool_unknown_pool() {
+    let mut helper = PoolTestHelper::new();
+    helper.instantiate_default(false);
+    let pool_address = helper.pool_address.unwrap();
+    helper.instantiate_factory_direct();
+    helper
+        .factory_set_canonical_pool(pool_address)
+        .registry
+        .execute_expect_failure(false);
+}
@@ -1,9 +1,13 @@
 use common::math::*;
+use ociswap_pool::pool::{PoolHook, PoolState};
+use ociswap_pool_client::decode_pool_state;
+use ociswap_pool_hooks::HookCall;
 use ociswap_pool_test_helper::PoolTestHelper;
 use pretty_assertions::assert_eq;
 use radix_engine::system::system_modules::execution_trace::ResourceSpecifier;
 use scrypto::prelude::*;
 use scrypto_testenv::environment::TestHelperExecution;
+use std::mem;
 
 #[test]
 fn test_getters_after_instantiation() {
@@ -153,3 +157,165 @@ fn test_after_first_transaction() {
         )
     );
 }
+
+#[test]
+fn test_state() {
+    let input_fee_rate = dec!(0.003251);
+    let x_share = dec!(0.615);
+    let flash_loan_fee_rate = dec!(0.01021);
+    let fee_protocol_share = dec!(0.0187136);
+
+    let mut helper = PoolTestHelper::new_without_instantiate_registry();
+    helper.registry.instantiate_execute(
+        helper.registry.admin_badge_address(),
+        fee_protocol_share,
+        1,
+        1,
+    );
+    helper.set_whitelist_registry();
+    helper.instantiate_full_direct(
+        helper.a_address(),
+        helper.b_address(),
+        input_fee_rate,
+        flash_loan_fee_rate,
+        x_share,
+        false,
+    );
+    helper
+        .add_liquidity_default(dec!(100), dec!(100))
+        .registry
+        .execute_expect_success(false);
+    helper
+        .swap(helper.y_address(), dec!(10))
+        .registry
+        .execute_expect_success(false);
+
+    helper.state();
+    for getter in [
+        "x_address",
+        "y_address",
+        "x_divisibility",
+        "y_divisibility",
+        "lp_address",
+        "flash_loan_address",
+        "liquidity_pool",
+        "total_liquidity",
+        "lp_total_supply",
+        "price_sqrt",
+        "pending_fee_rates",
+        "protocol_fee_mode",
+        "registry",
+        "registry_fallback",
+        "current_config",
+        "registry_sync_deferred_until",
+        "registry_sync_grace_period",
+        "guardian",
+        "paused",
+        "observations_limit",
+        "observations_stored",
+        "oldest_observation_at",
+        "last_observation_index",
+    ] {
+        helper.getter(getter);
+    }
+    let receipt = helper.registry.execute_expect_success(false);
+
+    let state: PoolState = receipt.outputs("state").remove(0);
+    let total_liquidity: IndexMap<ResourceAddress, Decimal> =
+        receipt.outputs("total_liquidity").remove(0);
+    assert_eq!(
+        state,
+        PoolState {
+            pool_address: helper.pool_address.unwrap(),
+            x_address: receipt.outputs("x_address")[0],
+            y_address: receipt.outputs("y_address")[0],
+            x_divisibility: receipt.outputs("x_divisibility")[0],
+            y_divisibility: receipt.outputs("y_divisibility")[0],
+            lp_address: receipt.outputs("lp_address")[0],
+            flash_loan_address: receipt.outputs("flash_loan_address")[0],
+            liquidity_pool: receipt.outputs("liquidity_pool")[0],
+            x_share,
+            ratio: x_share / (dec!(1) - x_share),
+            x_reserve: total_liquidity[&helper.x_address()],
+            y_reserve: total_liquidity[&helper.y_address()],
+            lp_total_supply: receipt.outputs("lp_total_supply")[0],
+            price_sqrt: receipt.outputs("price_sqrt")[0],
+            input_fee_rate,
+            flash_loan_fee_rate,
+            pending_fee_rates: receipt.outputs("pending_fee_rates").remove(0),
+            protocol_fee_mode: receipt.outputs("protocol_fee_mode")[0],
+            x_protocol_fee: dec!(0),
+            // The Y swap pays an input fee of 10 * 0.003251 = 0.03251, of which 0.0187136 is kept for the protocol.
+            y_protocol_fee: dec!(0.000608379136),
+            protocol_fee_checkpoint: None,
+            registry: receipt.outputs("registry")[0],
+            registry_fallback: receipt.outputs("registry_fallback")[0],
+            registry_config: receipt.outputs("current_config").remove(0),
+            registry_sync_deferred_until: receipt.outputs("registry_sync_deferred_until")[0],
+            registry_sync_grace_period: receipt.outputs("registry_sync_grace_period")[0],
+            hooks: vec![],
+            guardian: receipt.outputs("guardian")[0],
+            paused: receipt.outputs("paused")[0],
+            observations_limit: receipt.outputs("observations_limit")[0],
+            observations_stored: receipt.outputs("observations_stored")[0],
+            oldest_observation_at: receipt.outputs("oldest_observation_at")[0],
+            last_observation_index: receipt.outputs("last_observation_index")[0],
+        }
+    );
+    assert_eq!(state.registry_config.fee_protocol_share, fee_protocol_share);
+    assert_client_decodes_state(&state);
+}
+
+#[test]
+fn test_state_hooks() {
+    let packages: HashMap<&str, &str> = vec![
+        ("registry", "registry"),
+        ("pool", "."),
+        ("test_hook", "test_hook"),
+    ]
+    .into_iter()
+    .collect();
+    let mut helper = PoolTestHelper::new_with_packages(packages, true);
+    helper.set_whitelist_registry();
+    helper.set_whitelist_hook("test_hook");
+
+    let package_address = helper.registry.env.package_address("test_hook");
+    let manifest_builder = mem::take(&mut helper.registry.env.manifest_builder);
+    helper.registry.env.manifest_builder = manifest_builder.call_function(
+        package_address,
+        "TestSwapHook",
+        "instantiate",
+        manifest_args!(helper.x_address(), helper.y_address()),
+    );
+    helper
+        .registry
+        .env
+        .new_instruction("instantiate_test_hook", 1, 0);
+    let receipt = helper.registry.execute_expect_success(false);
+    let hook_badge_address = receipt
+        .execution_receipt
+        .expect_commit_success()
+        .new_resource_addresses()[0];
+    let outputs: Vec<(ComponentAddress, Bucket)> = receipt.outputs("instantiate_test_hook");
+    let hook_address = outputs[0].0;
+
+    helper.instantiate_default_with_hooks(vec![(hook_address, hook_badge_address)], false);
+    let receipt = helper.state().registry.execute_expect_success(false);
+    let state: PoolState = receipt.outputs("state").remove(0);
+
+    assert_eq!(
+        state.hooks,
+        vec![PoolHook {
+            component: hook_address,
+            calls: vec![HookCall::BeforeSwap, HookCall::AfterSwap],
+        }]
+    );
+    assert_client_decodes_state(&state);
+}
+
+/// Asserts that the client mirror of `PoolState` decodes the output of `state` and encodes it identically.
+fn assert_client_decodes_state(state: &PoolState) {
+    let output = scrypto_encode(state).unwrap();
+    let client_state = decode_pool_state(&output).unwrap();
+    assert_eq!(scrypto_encode(&client_state).unwrap(), output);
+}
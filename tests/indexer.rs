@@ -0,0 +1,177 @@
+use ociswap_pool_events::*;
+use ociswap_pool_test_helper::*;
+use pretty_assertions::assert_eq;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+
+fn index_receipt(indexer: &mut PoolIndexer, receipt: &Receipt, timestamp: u64) {
+    let commit_result = receipt.execution_receipt.expect_commit_success();
+    for (EventTypeIdentifier(emitter, name), payload) in &commit_result.application_events {
+        let emitter: GlobalAddress = match emitter {
+            Emitter::Function(blueprint_id) => blueprint_id.package_address.into(),
+            Emitter::Method(node_id, ModuleId::Main) => match GlobalAddress::try_from(node_id.0) {
+                Ok(address) => address,
+                Err(_) => continue,
+            },
+            Emitter::Method(..) => continue,
+        };
+        indexer
+            .apply_raw(emitter, timestamp, name, payload)
+            .expect("Failed to decode event");
+    }
+}
+
+fn reserves(helper: &mut PoolTestHelper) -> PairAmounts {
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let receipt = helper
+        .total_liquidity()
+        .registry
+        .execute_expect_success(false);
+    let total_liquidity: IndexMap<ResourceAddress, Decimal> =
+        receipt.outputs("total_liquidity")[0].clone();
+    PairAmounts {
+        x: total_liquidity[&x_address],
+        y: total_liquidity[&y_address],
+    }
+}
+
+fn price_sqrt(helper: &mut PoolTestHelper) -> Option<PreciseDecimal> {
+    let receipt = helper.price_sqrt().registry.execute_expect_success(false);
+    receipt.outputs("price_sqrt")[0]
+}
+
+fn instantiate() -> (PoolTestHelper, PoolIndexer) {
+    let mut helper = PoolTestHelper::new();
+    let package_address = helper.registry.env.package_address("pool");
+    let mut indexer = PoolIndexer::new(vec![package_address]);
+    let receipt = helper.instantiate_default_with_input_fee(dec!(0.003), false);
+    index_receipt(&mut indexer, &receipt, 0);
+    let receipt = helper
+        .add_liquidity_default(dec!(100), dec!(200))
+        .registry
+        .execute_expect_success(false);
+    index_receipt(&mut indexer, &receipt, 1);
+    helper.lp_address = Some(receipt.output_buckets("add_liquidity")[0][0].address());
+    (helper, indexer)
+}
+
+#[test]
+fn test_indexer_instantiate() {
+    let mut indexer = PoolIndexer::new(vec![]);
+    let mut helper = PoolTestHelper::new();
+    let package_address = helper.registry.env.package_address("pool");
+    let receipt = helper.instantiate_default_with_input_fee(dec!(0.003), false);
+    index_receipt(&mut indexer, &receipt, 0);
+    assert_eq!(indexer.pools().count(), 0);
+
+    let mut indexer = PoolIndexer::new(vec![package_address]);
+    index_receipt(&mut indexer, &receipt, 0);
+    let pool = indexer.pool(helper.pool_address.unwrap()).unwrap();
+    assert_eq!(
+        (
+            pool.liquidity_pool_address,
+            pool.x_address,
+            pool.y_address,
+            pool.x_share,
+            pool.reserves,
+            pool.prices.clone()
+        ),
+        (
+            helper.liquidity_pool_address.unwrap(),
+            helper.x_address(),
+            helper.y_address(),
+            dec!(0.5),
+            PairAmounts::default(),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn test_indexer_liquidity() {
+    let (mut helper, mut indexer) = instantiate();
+    let pool_address = helper.pool_address.unwrap();
+    assert_eq!(
+        indexer.pool(pool_address).unwrap().reserves,
+        PairAmounts {
+            x: dec!(100),
+            y: dec!(200)
+        }
+    );
+
+    let receipt = helper
+        .remove_liquidity_default(dec!(10))
+        .registry
+        .execute_expect_success(false);
+    index_receipt(&mut indexer, &receipt, 2);
+    assert_eq!(
+        indexer.pool(pool_address).unwrap().reserves,
+        reserves(&mut helper)
+    );
+}
+
+#[test]
+fn test_indexer_swaps() {
+    let (mut helper, mut indexer) = instantiate();
+
+    let receipt = helper
+        .swap_x_default(dec!(10))
+        .registry
+        .execute_expect_success(false);
+    index_receipt(&mut indexer, &receipt, 2);
+    let receipt = helper
+        .swap_y_default(dec!(20))
+        .registry
+        .execute_expect_success(false);
+    index_receipt(&mut indexer, &receipt, 3);
+    let receipt = helper
+        .swap_x_default(dec!(5))
+        .registry
+        .execute_expect_success(false);
+    index_receipt(&mut indexer, &receipt, 4);
+
+    let pool_address = helper.pool_address.unwrap();
+    let expected_reserves = reserves(&mut helper);
+    let expected_price_sqrt = price_sqrt(&mut helper);
+    let pool = indexer.pool(pool_address).unwrap();
+    assert_eq!(pool.reserves, expected_reserves);
+    assert_eq!(
+        pool.volume,
+        PairAmounts {
+            x: dec!(15),
+            y: dec!(20)
+        }
+    );
+    assert_eq!(
+        pool.fees_lp,
+        PairAmounts {
+            x: dec!(0.045),
+            y: dec!(0.06)
+        }
+    );
+    assert_eq!(pool.fees_protocol, PairAmounts::default());
+    assert_eq!(pool.swap_count, 3);
+    assert_eq!(
+        pool.prices
+            .iter()
+            .map(|price_point| price_point.timestamp)
+            .collect::<Vec<u64>>(),
+        vec![2, 3, 4]
+    );
+    assert_eq!(
+        pool.prices.last().map(|price_point| price_point.price_sqrt),
+        expected_price_sqrt
+    );
+}
+
+#[test]
+fn test_indexer_ignores_other_pools() {
+    let (mut helper, _) = instantiate();
+    let mut indexer = PoolIndexer::new(vec![]);
+    let receipt = helper
+        .swap_x_default(dec!(10))
+        .registry
+        .execute_expect_success(false);
+    index_receipt(&mut indexer, &receipt, 2);
+    assert_eq!(indexer.pools().count(), 0);
+}
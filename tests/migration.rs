@@ -0,0 +1,289 @@
+use ociswap_pool_events::MigrationEvent;
+use ociswap_pool_test_helper::*;
+use pretty_assertions::assert_eq;
+use radix_engine::system::system_modules::execution_trace::ResourceSpecifier::Amount;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+use std::mem;
+
+/// Instantiates a target pool with the given liquidity and afterwards a source pool with 10 X and 10 Y, which the
+/// helper points to.
+///
+/// # Returns
+/// The address of the target pool, its LP token address and the amount of LP tokens minted for its liquidity.
+fn instantiate_pools(
+    target_x_amount: Decimal,
+    target_y_amount: Decimal,
+) -> (PoolTestHelper, ComponentAddress, ResourceAddress, Decimal) {
+    let mut helper = PoolTestHelper::new();
+    helper.instantiate_default(false);
+    let target_pool_address = helper.pool_address.unwrap();
+    let receipt = helper
+        .add_liquidity_default(target_x_amount, target_y_amount)
+        .registry
+        .execute_expect_success(false);
+    let Amount(target_lp_address, target_lp_amount) =
+        receipt.output_buckets("add_liquidity")[0][0].clone()
+    else {
+        panic!("Expected fungible LP tokens");
+    };
+
+    helper.instantiate_default(false);
+    helper
+        .add_liquidity_default(dec!(10), dec!(10))
+        .registry
+        .execute_expect_success(false);
+    (
+        helper,
+        target_pool_address,
+        target_lp_address,
+        target_lp_amount,
+    )
+}
+
+#[derive(ScryptoSbor, ManifestSbor, NonFungibleData)]
+struct TestCredential {
+    name: String,
+}
+
+/// Instantiates a target pool with 10 X and 10 Y and afterwards a source pool with an `AccessControlHook`, to which
+/// 10 X and 10 Y are added with the credential `#1#`. The helper points to the source pool.
+///
+/// # Returns
+/// The address of the target pool, its LP token address and amount and the credential resource address.
+fn instantiate_access_controlled_pools() -> (
+    PoolTestHelper,
+    ComponentAddress,
+    ResourceAddress,
+    Decimal,
+    ResourceAddress,
+) {
+    let packages: HashMap<&str, &str> = vec![
+        ("registry", "registry"),
+        ("pool", "."),
+        ("standard_hooks", "standard_hooks"),
+    ]
+    .into_iter()
+    .collect();
+    let mut helper = PoolTestHelper::new_with_packages(packages, true);
+    helper.instantiate_default(false);
+    let target_pool_address = helper.pool_address.unwrap();
+    let receipt = helper
+        .add_liquidity_default(dec!(10), dec!(10))
+        .registry
+        .execute_expect_success(false);
+    let Amount(target_lp_address, target_lp_amount) =
+        receipt.output_buckets("add_liquidity")[0][0].clone()
+    else {
+        panic!("Expected fungible LP tokens");
+    };
+
+    let account = helper.registry.env.account;
+    let manifest_builder = mem::take(&mut helper.registry.env.manifest_builder);
+    helper.registry.env.manifest_builder = manifest_builder
+        .create_non_fungible_resource(
+            OwnerRole::None,
+            NonFungibleIdType::Integer,
+            true,
+            NonFungibleResourceRoles::default(),
+            metadata!(),
+            Some(vec![(
+                NonFungibleLocalId::integer(1),
+                TestCredential {
+                    name: "Credential 1".into(),
+                },
+            )]),
+        )
+        .try_deposit_entire_worktop_or_abort(account, None);
+    helper
+        .registry
+        .env
+        .new_instruction("create_credential_resource", 2, 0);
+    let receipt = helper.registry.execute_expect_success(false);
+    let credential_address = receipt
+        .execution_receipt
+        .expect_commit_success()
+        .new_resource_addresses()[0];
+
+    let package_address = helper.registry.env.package_address("standard_hooks");
+    let owner_badge = helper.admin_badge_address();
+    let manifest_builder = mem::take(&mut helper.registry.env.manifest_builder);
+    helper.registry.env.manifest_builder = manifest_builder.call_function(
+        package_address,
+        "AccessControlHook",
+        "instantiate",
+        manifest_args!(owner_badge, indexset!(credential_address)),
+    );
+    helper
+        .registry
+        .env
+        .new_instruction("instantiate_access_control_hook", 1, 0);
+    let receipt = helper.registry.execute_expect_success(false);
+    let commit_result = receipt.execution_receipt.expect_commit_success();
+    let hook = (
+        commit_result.new_component_addresses()[0],
+        commit_result.new_resource_addresses()[0],
+    );
+
+    helper.set_whitelist_hook("standard_hooks");
+    helper.instantiate_default_with_hooks(vec![hook], false);
+    helper
+        .add_liquidity_with_credential(
+            helper.x_address(),
+            dec!(10),
+            helper.y_address(),
+            dec!(10),
+            credential_address,
+            NonFungibleLocalId::integer(1),
+        )
+        .registry
+        .execute_expect_success(false);
+    helper.set_whitelist_successor("pool");
+    (
+        helper,
+        target_pool_address,
+        target_lp_address,
+        target_lp_amount,
+        credential_address,
+    )
+}
+
+fn migration_events(receipt: &Receipt) -> Vec<MigrationEvent> {
+    receipt
+        .execution_receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .filter(|(EventTypeIdentifier(_, name), _)| name == "MigrationEvent")
+        .map(|(_, payload)| scrypto_decode(payload).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_migrate() {
+    let (mut helper, target_pool_address, target_lp_address, target_lp_amount) =
+        instantiate_pools(dec!(10), dec!(20));
+    helper.set_whitelist_successor("pool");
+
+    // Redeems 5 X and 5 Y, of which 2.5 X and 5 Y fit the 1:2 ratio of the target pool.
+    let receipt = helper
+        .migrate_default(dec!(5), target_pool_address)
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("migrate"),
+        vec![vec![
+            Amount(target_lp_address, target_lp_amount / 4),
+            Amount(helper.x_address(), dec!(2.5))
+        ]]
+    );
+    assert_eq!(
+        migration_events(&receipt),
+        vec![MigrationEvent {
+            target_pool_address,
+            lp_amount: dec!(5),
+            x_amount: dec!(5),
+            y_amount: dec!(5),
+            target_lp_address,
+            target_lp_amount: target_lp_amount / 4,
+            remainder_address: Some(helper.x_address()),
+            remainder_amount: dec!(2.5),
+        }]
+    );
+
+    helper.remove_liquidity_success(dec!(5), dec!(5), dec!(5));
+}
+
+#[test]
+fn test_migrate_without_remainder() {
+    let (mut helper, target_pool_address, target_lp_address, target_lp_amount) =
+        instantiate_pools(dec!(10), dec!(10));
+    helper.set_whitelist_successor("pool");
+
+    let receipt = helper
+        .migrate_default(dec!(10), target_pool_address)
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("migrate"),
+        vec![vec![Amount(target_lp_address, target_lp_amount)]]
+    );
+    let events = migration_events(&receipt);
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        (events[0].remainder_address, events[0].remainder_amount),
+        (None, dec!(0))
+    );
+}
+
+#[test]
+fn test_migrate_successor_not_approved() {
+    let (mut helper, target_pool_address, _, _) = instantiate_pools(dec!(10), dec!(10));
+    helper
+        .migrate_default(dec!(5), target_pool_address)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_migrate_successor_packages_without_pool_package() {
+    let (mut helper, target_pool_address, _, _) = instantiate_pools(dec!(10), dec!(10));
+    helper.set_whitelist_successor("registry");
+    helper
+        .migrate_default(dec!(5), target_pool_address)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_migrate_wrong_lp_token() {
+    let (mut helper, target_pool_address, target_lp_address, _) =
+        instantiate_pools(dec!(10), dec!(10));
+    helper.set_whitelist_successor("pool");
+    helper
+        .migrate(target_lp_address, dec!(5), target_pool_address)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_migrate_to_itself() {
+    let (mut helper, _, _, _) = instantiate_pools(dec!(10), dec!(10));
+    helper.set_whitelist_successor("pool");
+    let pool_address = helper.pool_address.unwrap();
+    helper
+        .migrate_default(dec!(5), pool_address)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_migrate_access_controlled_pool_without_credential() {
+    let (mut helper, target_pool_address, _, _, _) = instantiate_access_controlled_pools();
+    helper
+        .migrate_default(dec!(5), target_pool_address)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_migrate_access_controlled_pool_with_credential() {
+    let (mut helper, target_pool_address, target_lp_address, target_lp_amount, credential_address) =
+        instantiate_access_controlled_pools();
+    let lp_address = helper.lp_address.unwrap();
+    let receipt = helper
+        .migrate_with_credential(
+            lp_address,
+            dec!(5),
+            target_pool_address,
+            credential_address,
+            NonFungibleLocalId::integer(1),
+        )
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("migrate_with_credential"),
+        vec![vec![Amount(target_lp_address, target_lp_amount / 2)]]
+    );
+    assert_eq!(migration_events(&receipt).len(), 1);
+}
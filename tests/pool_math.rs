@@ -0,0 +1,150 @@
+use ociswap_pool_math::*;
+use ociswap_pool_test_helper::*;
+use pretty_assertions::assert_eq;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+use test_case::test_case;
+
+fn instantiate(
+    input_fee_rate: Decimal,
+    x_share: Decimal,
+    x_amount: Decimal,
+    y_amount: Decimal,
+) -> PoolTestHelper {
+    let mut helper = PoolTestHelper::new();
+    helper.instantiate_default_with_all_fees(input_fee_rate, dec!(0), x_share, false);
+    helper.add_liquidity_default_execute(x_amount, y_amount);
+    helper
+}
+
+fn reserves(helper: &mut PoolTestHelper) -> (Decimal, Decimal) {
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let receipt = helper
+        .total_liquidity()
+        .registry
+        .execute_expect_success(false);
+    let total_liquidity: IndexMap<ResourceAddress, Decimal> =
+        receipt.outputs("total_liquidity")[0].clone();
+    (total_liquidity[&x_address], total_liquidity[&y_address])
+}
+
+fn native_output_amount(
+    helper: &mut PoolTestHelper,
+    input_fee_rate: Decimal,
+    x_share: Decimal,
+    swap_type: SwapType,
+    input_amount: Decimal,
+) -> Decimal {
+    let (x_vault, y_vault) = reserves(helper);
+    let (input_vault, output_vault) = match swap_type {
+        SwapType::BuyX => (y_vault, x_vault),
+        SwapType::SellX => (x_vault, y_vault),
+    };
+    let (input_amount_net, _, _) = input_amount_net(input_amount, input_fee_rate, dec!(0), 18);
+    output_amount(
+        input_vault,
+        output_vault,
+        input_amount_net,
+        x_share / (dec!(1) - x_share),
+        swap_type,
+        18,
+    )
+}
+
+fn input_address(helper: &PoolTestHelper, swap_type: SwapType) -> ResourceAddress {
+    match swap_type {
+        SwapType::BuyX => helper.y_address(),
+        SwapType::SellX => helper.x_address(),
+    }
+}
+
+#[test_case(dec!(0), dec!(0.5), SwapType::SellX, dec!(10) ; "balanced_sell_x")]
+#[test_case(dec!(0), dec!(0.5), SwapType::BuyX, dec!(10) ; "balanced_buy_x")]
+#[test_case(dec!(0.003), dec!(0.5), SwapType::SellX, dec!(12345.6789) ; "balanced_fee_sell_x")]
+#[test_case(dec!(0.1), dec!(0.5), SwapType::BuyX, Decimal::ATTO ; "balanced_fee_buy_x_atto")]
+#[test_case(dec!(0), dec!(0.8), SwapType::SellX, dec!(10) ; "imbalanced_sell_x")]
+#[test_case(dec!(0), dec!(0.8), SwapType::BuyX, dec!(10) ; "imbalanced_buy_x")]
+#[test_case(dec!(0.003), dec!(0.2), SwapType::SellX, dec!(99999) ; "imbalanced_fee_sell_x")]
+#[test_case(dec!(0.05), dec!(0.95), SwapType::BuyX, dec!(0.000001) ; "imbalanced_fee_buy_x")]
+#[test_case(dec!(0.01), dec!(0.05), SwapType::SellX, dec!(1000000) ; "imbalanced_min_share")]
+fn test_output_amount_matches_pool(
+    input_fee_rate: Decimal,
+    x_share: Decimal,
+    swap_type: SwapType,
+    input_amount: Decimal,
+) {
+    let mut helper = instantiate(input_fee_rate, x_share, dec!(100000), dec!(5000));
+    for _ in 0..3 {
+        let output_amount = native_output_amount(
+            &mut helper,
+            input_fee_rate,
+            x_share,
+            swap_type,
+            input_amount,
+        );
+        helper.swap_success(
+            input_address(&helper, swap_type),
+            input_amount,
+            output_amount,
+        );
+    }
+}
+
+#[test_case(dec!(0.5) ; "balanced")]
+#[test_case(dec!(0.8) ; "imbalanced")]
+fn test_price_sqrt_matches_pool(x_share: Decimal) {
+    let mut helper = instantiate(dec!(0.003), x_share, dec!(100000), dec!(5000));
+    helper
+        .swap(helper.x_address(), dec!(777))
+        .registry
+        .execute_expect_success(false);
+
+    let (x_vault, y_vault) = reserves(&mut helper);
+    let receipt = helper.price_sqrt().registry.execute_expect_success(false);
+    let pool_price_sqrt: Option<PreciseDecimal> = receipt.outputs("price_sqrt")[0];
+    assert_eq!(
+        pool_price_sqrt,
+        price_sqrt(x_vault, y_vault, x_share / (dec!(1) - x_share))
+    );
+}
+
+#[test_case(dec!(0), dec!(0.5), SwapType::SellX, dec!(100) ; "balanced_sell_x")]
+#[test_case(dec!(0.003), dec!(0.5), SwapType::BuyX, dec!(5000) ; "balanced_fee_buy_x")]
+#[test_case(dec!(0.003), dec!(0.8), SwapType::SellX, dec!(1.5) ; "imbalanced_fee_sell_x")]
+#[test_case(dec!(0.1), dec!(0.2), SwapType::BuyX, dec!(30000) ; "imbalanced_fee_buy_x")]
+fn test_exact_output_matches_pool(
+    input_fee_rate: Decimal,
+    x_share: Decimal,
+    swap_type: SwapType,
+    output_amount_expected: Decimal,
+) {
+    let mut helper = instantiate(input_fee_rate, x_share, dec!(100000), dec!(5000));
+    let (x_vault, y_vault) = reserves(&mut helper);
+    let (input_vault, output_vault) = match swap_type {
+        SwapType::BuyX => (y_vault, x_vault),
+        SwapType::SellX => (x_vault, y_vault),
+    };
+    let input_amount_net = input_amount_for_output(
+        input_vault,
+        output_vault,
+        output_amount_expected,
+        x_share / (dec!(1) - x_share),
+        swap_type,
+        18,
+    );
+    let input_amount = input_amount_gross(input_amount_net, input_fee_rate, 18);
+
+    let output_amount = native_output_amount(
+        &mut helper,
+        input_fee_rate,
+        x_share,
+        swap_type,
+        input_amount,
+    );
+    assert!(output_amount >= output_amount_expected);
+    helper.swap_success(
+        input_address(&helper, swap_type),
+        input_amount,
+        output_amount,
+    );
+}
@@ -0,0 +1,261 @@
+use num_bigint::BigUint;
+use ociswap_pool::constants::{
+    FLASH_LOAN_FEE_RATE_MAX, INPUT_FEE_RATE_MAX, MAXIMUM_SHARE, MINIMUM_SHARE,
+};
+use ociswap_pool_test_helper::*;
+use proptest::prelude::*;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+
+const DIVISIBILITIES: [u8; 4] = [18, 8, 6, 2];
+
+fn attos(amount: Decimal) -> BigUint {
+    BigUint::parse_bytes(amount.attos().to_string().as_bytes(), 10).unwrap()
+}
+
+fn percent(share: Decimal) -> u32 {
+    (share * dec!(100)).to_string().parse().unwrap()
+}
+
+fn fraction(amount: Decimal, fraction_bps: u64, divisibility: u8) -> Decimal {
+    (amount * Decimal::from(fraction_bps) / dec!(10000))
+        .checked_round(divisibility, RoundingMode::ToNegativeInfinity)
+        .unwrap()
+}
+
+#[derive(Clone, Debug)]
+struct PoolParameters {
+    x_share_percent: u32,
+    input_fee_rate: Decimal,
+    flash_loan_fee_rate: Decimal,
+    x_divisibility: u8,
+    y_divisibility: u8,
+    x_amount: u64,
+    y_amount: u64,
+}
+
+prop_compose! {
+    fn pool_parameters()(
+        x_share_percent in percent(MINIMUM_SHARE)..=percent(MAXIMUM_SHARE),
+        input_fee_rate_bps in 0u64..=1000,
+        flash_loan_fee_rate_bps in 0u64..=1000,
+        x_divisibility in prop::sample::select(DIVISIBILITIES.to_vec()),
+        y_divisibility in prop::sample::select(DIVISIBILITIES.to_vec()),
+        x_amount in 1u64..=1_000_000_000,
+        y_amount in 1u64..=1_000_000_000,
+    ) -> PoolParameters {
+        PoolParameters {
+            x_share_percent,
+            input_fee_rate: min(
+                Decimal::from(input_fee_rate_bps) / dec!(10000),
+                INPUT_FEE_RATE_MAX,
+            ),
+            flash_loan_fee_rate: min(
+                Decimal::from(flash_loan_fee_rate_bps) / dec!(10000),
+                FLASH_LOAN_FEE_RATE_MAX,
+            ),
+            x_divisibility,
+            y_divisibility,
+            x_amount,
+            y_amount,
+        }
+    }
+}
+
+struct Pool {
+    helper: PoolTestHelper,
+    x_address: ResourceAddress,
+    y_address: ResourceAddress,
+    x_share_percent: u32,
+    x_divisibility: u8,
+    y_divisibility: u8,
+}
+
+#[derive(Clone, Debug)]
+struct PoolState {
+    x_vault: Decimal,
+    y_vault: Decimal,
+    lp_total_supply: Decimal,
+}
+
+impl Pool {
+    /// Instantiates a pool of two new tokens with the given divisibilities and adds the initial liquidity.
+    fn new(parameters: &PoolParameters) -> Self {
+        let mut helper = PoolTestHelper::new();
+        helper.set_whitelist_registry();
+        let account = helper.registry.env.account;
+        let a_address = helper.registry.env.test_runner.create_fungible_resource(
+            dec!(1000000000000000),
+            parameters.x_divisibility,
+            account,
+        );
+        let b_address = helper.registry.env.test_runner.create_fungible_resource(
+            dec!(1000000000000000),
+            parameters.y_divisibility,
+            account,
+        );
+        let a_share = Decimal::from(parameters.x_share_percent) / dec!(100);
+        helper.instantiate_full_direct(
+            a_address,
+            b_address,
+            parameters.input_fee_rate,
+            parameters.flash_loan_fee_rate,
+            a_share,
+            false,
+        );
+
+        // The pool sorts the tokens by address, which can swap the roles of A and B.
+        let receipt = helper
+            .getter("x_address")
+            .registry
+            .execute_expect_success(false);
+        let x_address: ResourceAddress = receipt.outputs("x_address")[0];
+        let (y_address, x_share_percent, x_divisibility, y_divisibility) = if x_address == a_address
+        {
+            (
+                b_address,
+                parameters.x_share_percent,
+                parameters.x_divisibility,
+                parameters.y_divisibility,
+            )
+        } else {
+            (
+                a_address,
+                100 - parameters.x_share_percent,
+                parameters.y_divisibility,
+                parameters.x_divisibility,
+            )
+        };
+        helper
+            .add_liquidity(
+                a_address,
+                Decimal::from(parameters.x_amount),
+                b_address,
+                Decimal::from(parameters.y_amount),
+            )
+            .registry
+            .execute_expect_success(false);
+
+        Self {
+            helper,
+            x_address,
+            y_address,
+            x_share_percent,
+            x_divisibility,
+            y_divisibility,
+        }
+    }
+
+    fn state(&mut self) -> PoolState {
+        let receipt = self
+            .helper
+            .total_liquidity()
+            .lp_total_supply()
+            .registry
+            .execute_expect_success(false);
+        let total_liquidity: IndexMap<ResourceAddress, Decimal> =
+            receipt.outputs("total_liquidity")[0].clone();
+        PoolState {
+            x_vault: total_liquidity[&self.x_address],
+            y_vault: total_liquidity[&self.y_address],
+            lp_total_supply: receipt.outputs("lp_total_supply")[0],
+        }
+    }
+
+    /// Returns the weighted invariant `x^x_share * y^y_share` raised to the power of 100, which is exact
+    /// for shares with two decimal places.
+    fn invariant(&self, state: &PoolState) -> BigUint {
+        attos(state.x_vault).pow(self.x_share_percent)
+            * attos(state.y_vault).pow(100 - self.x_share_percent)
+    }
+
+    /// Compares the redemption value of one LP token, `invariant / lp_total_supply`, without divisions.
+    fn lp_value_not_decreased(&self, before: &PoolState, after: &PoolState) -> bool {
+        self.invariant(after) * attos(before.lp_total_supply).pow(100)
+            >= self.invariant(before) * attos(after.lp_total_supply).pow(100)
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn test_swaps_never_decrease_invariant(
+        parameters in pool_parameters(),
+        swaps in prop::collection::vec((any::<bool>(), 1u64..=20_000), 1..6),
+    ) {
+        let mut pool = Pool::new(&parameters);
+        let initial_state = pool.state();
+        let mut state = initial_state.clone();
+
+        for (sell_x, fraction_bps) in swaps {
+            let (input_address, input_amount) = if sell_x {
+                (pool.x_address, fraction(state.x_vault, fraction_bps, pool.x_divisibility))
+            } else {
+                (pool.y_address, fraction(state.y_vault, fraction_bps, pool.y_divisibility))
+            };
+            if input_amount.is_zero() {
+                continue;
+            }
+            // Swaps may be rejected, e.g. if the output rounds down to zero, which leaves the pool untouched.
+            pool.helper.swap(input_address, input_amount).registry.execute(false);
+
+            let new_state = pool.state();
+            prop_assert!(
+                pool.invariant(&new_state) >= pool.invariant(&state),
+                "Invariant decreased: {:?} -> {:?}",
+                state,
+                new_state
+            );
+            prop_assert!(pool.lp_value_not_decreased(&state, &new_state));
+            state = new_state;
+        }
+        prop_assert!(pool.lp_value_not_decreased(&initial_state, &state));
+    }
+
+    #[test]
+    fn test_flash_loan_round_trip_leaves_pool_whole(
+        parameters in pool_parameters(),
+        loan_x in any::<bool>(),
+        fraction_bps in 1u64..=5_000,
+    ) {
+        let mut pool = Pool::new(&parameters);
+        let state = pool.state();
+        let (loan_address, loan_amount, divisibility) = if loan_x {
+            let amount = fraction(state.x_vault, fraction_bps, pool.x_divisibility);
+            (pool.x_address, amount, pool.x_divisibility)
+        } else {
+            let amount = fraction(state.y_vault, fraction_bps, pool.y_divisibility);
+            (pool.y_address, amount, pool.y_divisibility)
+        };
+        prop_assume!(loan_amount > Decimal::ZERO);
+
+        let receipt = pool
+            .helper
+            .flash_loan_address()
+            .registry
+            .execute_expect_success(false);
+        let flash_loan_address: ResourceAddress = receipt.outputs("flash_loan_address")[0];
+        // Repaying more than due returns the excess, so one additional unit covers any fee rounding.
+        let fee_amount = (loan_amount * parameters.flash_loan_fee_rate)
+            .checked_round(divisibility, RoundingMode::ToPositiveInfinity)
+            .unwrap()
+            + Decimal::ONE / 10u64.pow(divisibility as u32);
+        pool.helper.flash_loan(loan_address, loan_amount);
+        pool.helper
+            .repay_loan(
+                loan_address,
+                loan_amount,
+                fee_amount,
+                flash_loan_address,
+                dec!(1),
+            )
+            .registry
+            .execute_expect_success(false);
+
+        let new_state = pool.state();
+        prop_assert!(new_state.x_vault >= state.x_vault);
+        prop_assert!(new_state.y_vault >= state.y_vault);
+        prop_assert_eq!(new_state.lp_total_supply, state.lp_total_supply);
+    }
+}
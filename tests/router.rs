@@ -0,0 +1,367 @@
+use ociswap_pool_test_helper::*;
+use pretty_assertions::assert_eq;
+use radix_engine::system::system_modules::execution_trace::ResourceSpecifier::Amount;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+
+fn instantiate_pool(
+    helper: &mut PoolTestHelper,
+    a_address: ResourceAddress,
+    a_amount: Decimal,
+    b_address: ResourceAddress,
+    b_amount: Decimal,
+) -> ComponentAddress {
+    let receipt = helper
+        .instantiate_with_liquidity(a_address, a_amount, b_address, b_amount, dec!(0.003))
+        .registry
+        .execute_expect_success(false);
+    let (pool_address, _): (ComponentAddress, Bucket) =
+        receipt.outputs("instantiate_with_liquidity")[0];
+    pool_address
+}
+
+fn instantiate_route() -> (PoolTestHelper, Vec<ComponentAddress>) {
+    let mut helper = PoolTestHelper::new();
+    helper.set_whitelist_registry();
+    let (x_address, y_address, u_address) =
+        (helper.x_address(), helper.y_address(), helper.u_address());
+    let xy_pool = instantiate_pool(&mut helper, x_address, dec!(100), y_address, dec!(200));
+    let yu_pool = instantiate_pool(&mut helper, u_address, dec!(300), y_address, dec!(100));
+    (helper, vec![xy_pool, yu_pool])
+}
+
+fn quote_path(
+    helper: &mut PoolTestHelper,
+    input_address: ResourceAddress,
+    input_amount: Decimal,
+    path: Vec<ComponentAddress>,
+) -> Decimal {
+    let receipt = helper
+        .router_quote_path(input_address, input_amount, path)
+        .registry
+        .execute_expect_success(false);
+    receipt.outputs("quote_path")[0]
+}
+
+#[test]
+fn test_pool_quote_equals_swap() {
+    let mut helper = PoolTestHelper::new();
+    helper.instantiate_default_with_input_fee(dec!(0.003), false);
+    helper.add_liquidity_default_execute(dec!(100), dec!(200));
+
+    let receipt = helper
+        .quote(helper.x_address(), dec!(10))
+        .registry
+        .execute_expect_success(false);
+    let quote: Decimal = receipt.outputs("quote")[0];
+    assert!(quote > Decimal::ZERO);
+
+    helper.swap_success(helper.x_address(), dec!(10), quote);
+}
+
+#[test]
+fn test_pool_quote_foreign_token() {
+    let mut helper = PoolTestHelper::new();
+    helper.instantiate_default(false);
+    helper.add_liquidity_default_execute(dec!(100), dec!(200));
+    helper
+        .quote(helper.u_address(), dec!(10))
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_router_swap_path_equals_quote_path() {
+    let (mut helper, path) = instantiate_route();
+    let (x_address, u_address) = (helper.x_address(), helper.u_address());
+    let quote = quote_path(&mut helper, x_address, dec!(10), path.clone());
+    assert!(quote > Decimal::ZERO);
+
+    let receipt = helper
+        .router_swap_path(x_address, dec!(10), path, quote, u64::MAX)
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("swap_path"),
+        vec![vec![Amount(u_address, quote)]]
+    );
+}
+
+#[test]
+fn test_router_swap_path_reversed() {
+    let (mut helper, mut path) = instantiate_route();
+    path.reverse();
+    let (x_address, u_address) = (helper.x_address(), helper.u_address());
+    let quote = quote_path(&mut helper, u_address, dec!(10), path.clone());
+
+    let receipt = helper
+        .router_swap_path(u_address, dec!(10), path, quote, u64::MAX)
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("swap_path"),
+        vec![vec![Amount(x_address, quote)]]
+    );
+}
+
+#[test]
+fn test_router_swap_path_single_pool() {
+    let (mut helper, path) = instantiate_route();
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let path = vec![path[0]];
+    let quote = quote_path(&mut helper, x_address, dec!(10), path.clone());
+
+    let receipt = helper
+        .router_swap_path(x_address, dec!(10), path, dec!(0), u64::MAX)
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("swap_path"),
+        vec![vec![Amount(y_address, quote)]]
+    );
+}
+
+#[test]
+fn test_router_swap_path_below_min_output() {
+    let (mut helper, path) = instantiate_route();
+    let x_address = helper.x_address();
+    let quote = quote_path(&mut helper, x_address, dec!(10), path.clone());
+    helper
+        .router_swap_path(x_address, dec!(10), path, quote + Decimal::ATTO, u64::MAX)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_router_swap_path_deadline() {
+    let (mut helper, path) = instantiate_route();
+    let x_address = helper.x_address();
+    helper.jump_to_timestamp_seconds(3600);
+    helper
+        .router_swap_path(x_address, dec!(10), path.clone(), dec!(0), 3600)
+        .registry
+        .execute_expect_success(false);
+    helper
+        .router_swap_path(x_address, dec!(10), path, dec!(0), 3599)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_router_swap_path_empty() {
+    let (mut helper, _) = instantiate_route();
+    let x_address = helper.x_address();
+    helper
+        .router_swap_path(x_address, dec!(10), vec![], dec!(0), u64::MAX)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_router_swap_path_disconnected() {
+    let (mut helper, path) = instantiate_route();
+    let u_address = helper.u_address();
+    helper
+        .router_swap_path(u_address, dec!(10), path, dec!(0), u64::MAX)
+        .registry
+        .execute_expect_failure(false);
+}
+
+fn instantiate_pair_pools(
+    input_fee_rates: Vec<Decimal>,
+) -> (PoolTestHelper, Vec<ComponentAddress>) {
+    let mut helper = PoolTestHelper::new();
+    helper.set_whitelist_registry();
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let pools = input_fee_rates
+        .into_iter()
+        .map(|input_fee_rate| {
+            let receipt = helper
+                .instantiate_with_liquidity(
+                    x_address,
+                    dec!(100),
+                    y_address,
+                    dec!(100),
+                    input_fee_rate,
+                )
+                .registry
+                .execute_expect_success(false);
+            let (pool_address, _): (ComponentAddress, Bucket) =
+                receipt.outputs("instantiate_with_liquidity")[0];
+            pool_address
+        })
+        .collect();
+    (helper, pools)
+}
+
+#[test]
+fn test_router_swap_split_equal_pools() {
+    let (mut helper, pools) = instantiate_pair_pools(vec![dec!(0.003), dec!(0.003)]);
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let single_pool_quote = quote_path(&mut helper, x_address, dec!(50), vec![pools[0]]);
+    let half_quote = quote_path(&mut helper, x_address, dec!(25), vec![pools[0]]);
+
+    let receipt = helper
+        .router_swap_split(x_address, dec!(50), pools, dec!(0), u64::MAX)
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("swap_split"),
+        vec![vec![Amount(y_address, half_quote * 2)]]
+    );
+    assert!(half_quote * 2 > single_pool_quote);
+}
+
+#[test]
+fn test_router_swap_split_skips_expensive_pool() {
+    let (mut helper, pools) = instantiate_pair_pools(vec![dec!(0.003), dec!(0.1)]);
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let quote = quote_path(&mut helper, x_address, dec!(1), vec![pools[0]]);
+
+    let receipt = helper
+        .router_swap_split(x_address, dec!(1), pools, quote, u64::MAX)
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("swap_split"),
+        vec![vec![Amount(y_address, quote)]]
+    );
+}
+
+#[test]
+fn test_router_swap_split_beats_single_pools() {
+    let (mut helper, pools) = instantiate_pair_pools(vec![dec!(0.003), dec!(0.01), dec!(0.03)]);
+    let y_address = helper.y_address();
+    let best_single_pool_quote = pools
+        .clone()
+        .into_iter()
+        .map(|pool| quote_path(&mut helper, y_address, dec!(60), vec![pool]))
+        .max()
+        .unwrap();
+
+    helper
+        .router_swap_split(y_address, dec!(60), pools, best_single_pool_quote, u64::MAX)
+        .registry
+        .execute_expect_success(false);
+}
+
+#[test]
+fn test_router_swap_split_different_pairs() {
+    let (mut helper, path) = instantiate_route();
+    let y_address = helper.y_address();
+    helper
+        .router_swap_split(y_address, dec!(10), path, dec!(0), u64::MAX)
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_router_swap_split_duplicate_pools() {
+    let (mut helper, pools) = instantiate_pair_pools(vec![dec!(0.003)]);
+    let x_address = helper.x_address();
+    helper
+        .router_swap_split(
+            x_address,
+            dec!(10),
+            vec![pools[0], pools[0]],
+            dec!(0),
+            u64::MAX,
+        )
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_router_swap_split_below_min_output() {
+    let (mut helper, pools) = instantiate_pair_pools(vec![dec!(0.003), dec!(0.003)]);
+    let x_address = helper.x_address();
+    let half_quote = quote_path(&mut helper, x_address, dec!(25), vec![pools[0]]);
+    helper
+        .router_swap_split(
+            x_address,
+            dec!(50),
+            pools,
+            half_quote * 2 + Decimal::ATTO,
+            u64::MAX,
+        )
+        .registry
+        .execute_expect_failure(false);
+}
+
+#[test]
+fn test_router_swap_batch() {
+    let (mut helper, path) = instantiate_route();
+    let (x_address, y_address, u_address) =
+        (helper.x_address(), helper.y_address(), helper.u_address());
+    let xy_quote = quote_path(&mut helper, x_address, dec!(10), vec![path[0]]);
+    let uy_quote = quote_path(&mut helper, u_address, dec!(30), vec![path[1]]);
+
+    let receipt = helper
+        .router_swap_batch(vec![
+            (path[0], x_address, dec!(10), xy_quote),
+            (path[1], u_address, dec!(30), uy_quote),
+        ])
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("swap_batch"),
+        vec![vec![
+            Amount(y_address, xy_quote),
+            Amount(y_address, uy_quote)
+        ]]
+    );
+}
+
+#[test]
+fn test_router_swap_batch_same_pool() {
+    let (mut helper, path) = instantiate_route();
+    let (x_address, y_address) = (helper.x_address(), helper.y_address());
+    let xy_quote = quote_path(&mut helper, x_address, dec!(10), vec![path[0]]);
+
+    let receipt = helper
+        .router_swap_batch(vec![
+            (path[0], x_address, dec!(10), xy_quote),
+            (path[0], y_address, xy_quote, dec!(0)),
+        ])
+        .registry
+        .execute_expect_success(false);
+    let output_buckets = receipt.output_buckets("swap_batch");
+    assert_eq!(output_buckets[0][0], Amount(y_address, xy_quote));
+}
+
+#[test]
+fn test_router_swap_batch_leg_below_min_output() {
+    let (mut helper, path) = instantiate_route();
+    let (x_address, u_address) = (helper.x_address(), helper.u_address());
+    let xy_quote = quote_path(&mut helper, x_address, dec!(10), vec![path[0]]);
+    let uy_quote = quote_path(&mut helper, u_address, dec!(30), vec![path[1]]);
+
+    helper
+        .router_swap_batch(vec![
+            (path[0], x_address, dec!(10), xy_quote),
+            (path[1], u_address, dec!(30), uy_quote + Decimal::ATTO),
+        ])
+        .registry
+        .execute_expect_failure(false);
+
+    // The failed batch has not been applied, so the first leg still returns the quoted amount.
+    let receipt = helper
+        .router_swap_batch(vec![(path[0], x_address, dec!(10), xy_quote)])
+        .registry
+        .execute_expect_success(false);
+    assert_eq!(
+        receipt.output_buckets("swap_batch"),
+        vec![vec![Amount(helper.y_address(), xy_quote)]]
+    );
+}
+
+#[test]
+fn test_router_swap_batch_empty() {
+    let mut helper = PoolTestHelper::new();
+    let receipt = helper
+        .router_swap_batch(vec![])
+        .registry
+        .execute_expect_success(false);
+    let outputs: Vec<Vec<Bucket>> = receipt.outputs("swap_batch");
+    assert_eq!(outputs, vec![vec![]]);
+}
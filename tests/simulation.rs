@@ -0,0 +1,217 @@
+use ociswap_pool_test_helper::*;
+use pretty_assertions::assert_eq;
+use scrypto::prelude::*;
+use scrypto_testenv::*;
+use std::mem;
+
+fn config(seed: u64) -> SimulationConfig {
+    SimulationConfig {
+        seed,
+        steps: 12,
+        step_seconds: 600,
+        reference_price: dec!(2),
+        reference_volatility: 0.02,
+    }
+}
+
+fn actors() -> Vec<Actor> {
+    vec![
+        Actor::LiquidityProvider {
+            x_amount: dec!(50),
+            enter_probability: 0.5,
+            exit_probability: 0.3,
+        },
+        Actor::Trader {
+            max_x_amount: dec!(5),
+            probability: 0.8,
+        },
+        Actor::Arbitrageur {
+            min_deviation: 0.001,
+        },
+    ]
+}
+
+/// Mirrors `DynamicFeeParameters` of the `standard_hooks` package.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq)]
+struct DynamicFeeParameters {
+    fee_floor: Decimal,
+    fee_ceiling: Decimal,
+    volatility_ceiling: Decimal,
+    interval_seconds: u64,
+    intervals: u16,
+}
+
+/// Instantiates a pool with a `DynamicFeeHook` measuring the volatility over intervals of one simulation step.
+fn instantiate_with_dynamic_fee_hook() -> (PoolTestHelper, ComponentAddress) {
+    let packages: HashMap<&str, &str> = vec![
+        ("registry", "registry"),
+        ("pool", "."),
+        ("standard_hooks", "standard_hooks"),
+    ]
+    .into_iter()
+    .collect();
+    let mut helper = PoolTestHelper::new_with_packages(packages, true);
+    helper.set_whitelist_registry();
+    helper.set_whitelist_hook("standard_hooks");
+
+    let package_address = helper.registry.env.package_address("standard_hooks");
+    let owner_badge = helper.admin_badge_address();
+    let manifest_builder = mem::take(&mut helper.registry.env.manifest_builder);
+    helper.registry.env.manifest_builder = manifest_builder.call_function(
+        package_address,
+        "DynamicFeeHook",
+        "instantiate",
+        manifest_args!(
+            owner_badge,
+            DynamicFeeParameters {
+                fee_floor: dec!(0.001),
+                fee_ceiling: dec!(0.05),
+                volatility_ceiling: dec!(0.05),
+                interval_seconds: 600,
+                intervals: 3,
+            }
+        ),
+    );
+    helper
+        .registry
+        .env
+        .new_instruction("instantiate_dynamic_fee_hook", 1, 0);
+    let receipt = helper.registry.execute_expect_success(false);
+    let commit_result = receipt.execution_receipt.expect_commit_success();
+    let hook_address = commit_result.new_component_addresses()[0];
+    let hook_badge_address = commit_result.new_resource_addresses()[0];
+
+    helper.instantiate_default_with_hooks(vec![(hook_address, hook_badge_address)], false);
+    helper.add_liquidity_default_execute(dec!(1000), dec!(2000));
+    (helper, hook_address)
+}
+
+fn hook_input_fee_rate(helper: &mut PoolTestHelper, hook_address: ComponentAddress) -> Decimal {
+    let manifest_builder = mem::take(&mut helper.registry.env.manifest_builder);
+    helper.registry.env.manifest_builder =
+        manifest_builder.call_method(hook_address, "input_fee_rate", manifest_args!());
+    helper.registry.env.new_instruction("input_fee_rate", 1, 0);
+    let receipt = helper.registry.execute_expect_success(false);
+    receipt.outputs("input_fee_rate")[0]
+}
+
+fn run(seed: u64) -> (Vec<SimulationStep>, String) {
+    let mut helper = PoolTestHelper::new();
+    helper.instantiate_default_with_input_fee(dec!(0.003), false);
+    helper.add_liquidity_default_execute(dec!(1000), dec!(2000));
+    let mut simulation = Simulation::new(&mut helper, config(seed), actors());
+    let steps = simulation.run().to_vec();
+    (steps, simulation.to_csv())
+}
+
+#[test]
+fn test_simulation_deterministic() {
+    let (steps, csv) = run(42);
+    assert_eq!(run(42), (steps.clone(), csv.clone()));
+    assert_ne!(run(43).1, csv);
+
+    assert_eq!(steps.len(), 12);
+    assert_eq!(csv.lines().count(), 13);
+    assert!(csv.starts_with("step,timestamp,reference_price,price,"));
+}
+
+#[test]
+fn test_simulation_records() {
+    let (steps, _) = run(7);
+    for pair in steps.windows(2) {
+        assert_eq!(pair[1].timestamp - pair[0].timestamp, 600);
+        assert!(pair[1].swaps >= pair[0].swaps);
+        assert!(pair[1].x_volume >= pair[0].x_volume);
+        assert!(pair[1].y_fees_lp >= pair[0].y_fees_lp);
+    }
+    let last = steps.last().unwrap();
+    assert!(last.swaps > 0);
+    // Input fee of 0.3% without protocol share, rounded per swap
+    assert!(
+        (last.x_fees_lp - last.x_volume * dec!(0.003))
+            .checked_abs()
+            .unwrap()
+            <= Decimal::ATTO * Decimal::from(last.swaps)
+    );
+    assert_eq!(last.x_fees_protocol, dec!(0));
+    assert!(steps.iter().any(|step| step.oracle_price_sqrt.is_some()));
+}
+
+#[test]
+fn test_simulation_arbitrage_tracks_reference_price() {
+    let (steps, _) = run(1);
+    for step in steps {
+        let price = Decimal::try_from(step.price.unwrap()).unwrap();
+        let deviation = (price / step.reference_price - dec!(1))
+            .checked_abs()
+            .unwrap();
+        assert!(
+            deviation < dec!(0.01),
+            "Step {}: price {} deviates from reference price {}",
+            step.step,
+            price,
+            step.reference_price
+        );
+    }
+}
+
+#[test]
+fn test_simulation_keeper_dynamic_fee_hook() {
+    let (mut helper, hook_address) = instantiate_with_dynamic_fee_hook();
+    let mut keeper_and_actors = vec![Actor::Keeper {
+        component_address: hook_address,
+        method: "update_input_fee_rate".to_string(),
+    }];
+    keeper_and_actors.extend(actors());
+    let mut simulation = Simulation::new(&mut helper, config(5), keeper_and_actors);
+
+    let mut previous: Option<SimulationStep> = None;
+    let mut input_fee_rates = Vec::new();
+    for _ in 0..simulation.config.steps {
+        let step = simulation.run_step().clone();
+        // The keeper acts first, so all swaps of the step are charged the rate it set.
+        let input_fee_rate = hook_input_fee_rate(simulation.helper, hook_address);
+        let (swaps, x_volume, y_volume, x_fees, y_fees) = match &previous {
+            Some(previous) => (
+                step.swaps - previous.swaps,
+                step.x_volume - previous.x_volume,
+                step.y_volume - previous.y_volume,
+                step.x_fees_lp + step.x_fees_protocol
+                    - previous.x_fees_lp
+                    - previous.x_fees_protocol,
+                step.y_fees_lp + step.y_fees_protocol
+                    - previous.y_fees_lp
+                    - previous.y_fees_protocol,
+            ),
+            None => (
+                step.swaps,
+                step.x_volume,
+                step.y_volume,
+                step.x_fees_lp + step.x_fees_protocol,
+                step.y_fees_lp + step.y_fees_protocol,
+            ),
+        };
+        // Input fees rounded per swap
+        let tolerance = Decimal::ATTO * Decimal::from(swaps);
+        assert!(
+            (x_fees - x_volume * input_fee_rate).checked_abs().unwrap() <= tolerance,
+            "Step {}: X fees {} do not match the input fee rate {}",
+            step.step,
+            x_fees,
+            input_fee_rate
+        );
+        assert!(
+            (y_fees - y_volume * input_fee_rate).checked_abs().unwrap() <= tolerance,
+            "Step {}: Y fees {} do not match the input fee rate {}",
+            step.step,
+            y_fees,
+            input_fee_rate
+        );
+        input_fee_rates.push(input_fee_rate);
+        previous = Some(step);
+    }
+
+    // The keeper raises the rate above the floor once the oracle covers two intervals.
+    assert_eq!(input_fee_rates[0], dec!(0.001));
+    assert!(input_fee_rates.iter().any(|rate| *rate > dec!(0.001)));
+}